@@ -140,6 +140,21 @@ impl<T> fmt::Debug for Range<T> {
     }
 }
 
+impl<T> Range<T> {
+    /// Return the range covering the handles from `first` to `last`, inclusive.
+    pub(crate) const fn new_from_bounds(first: Handle<T>, last: Handle<T>) -> Self {
+        Range {
+            inner: first.index() as u32..last.index() as u32 + 1,
+            marker: PhantomData,
+        }
+    }
+
+    /// Return true if the range doesn't contain any handles.
+    pub const fn is_empty(&self) -> bool {
+        self.inner.start >= self.inner.end
+    }
+}
+
 impl<T> Iterator for Range<T> {
     type Item = Handle<T>;
     fn next(&mut self) -> Option<Self::Item> {
@@ -153,8 +168,15 @@ impl<T> Iterator for Range<T> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.end.saturating_sub(self.inner.start) as usize;
+        (len, Some(len))
+    }
 }
 
+impl<T> ExactSizeIterator for Range<T> {}
+
 /// An arena holding some kind of component (e.g., type, constant,
 /// instruction, etc.) that can be referenced.
 ///
@@ -361,6 +383,21 @@ impl<T> ops::Index<Range<T>> for Arena<T> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn range_from() {
+        let mut arena: Arena<u8> = Arena::new();
+        arena.append(0, Default::default());
+        assert!(arena.range_from(arena.len()).is_empty());
+
+        let start = arena.len();
+        let t1 = arena.append(1, Default::default());
+        let t2 = arena.append(2, Default::default());
+        let range = arena.range_from(start);
+        assert!(!range.is_empty());
+        assert_eq!(range.len(), 2);
+        assert_eq!(range.collect::<Vec<_>>(), [t1, t2]);
+    }
+
     #[test]
     fn append_non_unique() {
         let mut arena: Arena<u8> = Arena::new();
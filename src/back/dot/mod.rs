@@ -55,6 +55,7 @@ impl StatementGraph {
                     condition,
                     ref accept,
                     ref reject,
+                    ..
                 } => {
                     self.dependencies.push((id, condition, "condition"));
                     let accept_id = self.add(accept);
@@ -38,6 +38,8 @@ bitflags::bitflags! {
         const FMA = 1 << 18;
         /// Texture samples query
         const TEXTURE_SAMPLES = 1 << 19;
+        /// 2 byte floats.
+        const FLOAT16 = 1 << 20;
     }
 }
 
@@ -107,6 +109,7 @@ impl FeaturesManager {
         // of samples in a image and neither do bound checks on the sample argument
         // of texelFecth
         check_feature!(TEXTURE_SAMPLES, 150);
+        check_feature!(FLOAT16, 450, 310);
 
         // Return an error if there are missing features
         if missing.is_empty() {
@@ -140,6 +143,14 @@ impl FeaturesManager {
             writeln!(out, "#extension GL_ARB_gpu_shader_fp64 : require")?;
         }
 
+        if self.0.contains(Features::FLOAT16) {
+            // https://github.com/KhronosGroup/GLSL/blob/master/extensions/ext/GL_EXT_shader_explicit_arithmetic_types.txt
+            writeln!(
+                out,
+                "#extension GL_EXT_shader_explicit_arithmetic_types_float16 : require"
+            )?;
+        }
+
         if self.0.contains(Features::CUBE_TEXTURES_ARRAY) {
             if version.is_es() {
                 // https://www.khronos.org/registry/OpenGL/extensions/EXT/EXT_texture_cube_map_array.txt
@@ -200,11 +211,6 @@ impl FeaturesManager {
             writeln!(out, "#extension GL_OES_sample_variables : require")?;
         }
 
-        if self.0.contains(Features::SAMPLE_VARIABLES) && version.is_es() {
-            // https://www.khronos.org/registry/OpenGL/extensions/OES/OES_sample_variables.txt
-            writeln!(out, "#extension GL_OES_sample_variables : require")?;
-        }
-
         if self.0.contains(Features::MULTI_VIEW) {
             // https://github.com/KhronosGroup/GLSL/blob/master/extensions/ext/GL_EXT_multiview.txt
             writeln!(out, "#extension GL_EXT_multiview : require")?;
@@ -404,8 +410,12 @@ impl<'a, W> Writer<'a, W> {
 
     /// Helper method that checks the [`Features`] needed by a scalar
     fn scalar_required_features(&mut self, kind: ScalarKind, width: Bytes) {
-        if kind == ScalarKind::Float && width == 8 {
-            self.features.request(Features::DOUBLE_TYPE);
+        if kind == ScalarKind::Float {
+            match width {
+                2 => self.features.request(Features::FLOAT16),
+                8 => self.features.request(Features::DOUBLE_TYPE),
+                _ => {}
+            }
         }
     }
 
@@ -426,7 +436,7 @@ impl<'a, W> Writer<'a, W> {
                             crate::BuiltIn::CullDistance => {
                                 self.features.request(Features::CULL_DISTANCE)
                             }
-                            crate::BuiltIn::SampleIndex => {
+                            crate::BuiltIn::SampleIndex | crate::BuiltIn::SampleMask => {
                                 self.features.request(Features::SAMPLE_VARIABLES)
                             }
                             crate::BuiltIn::ViewIndex => {
@@ -141,9 +141,7 @@ impl Version {
         }
     }
 
-    /// Checks if the version supports all of the explicit layouts:
-    /// - `location=` qualifiers for bindings
-    /// - `binding=` qualifiers for resources
+    /// Checks if the version supports `location=` qualifiers for bindings
     ///
     /// Note: `location=` for vertex inputs and fragment outputs is supported
     /// unconditionally for GLES 300.
@@ -151,6 +149,14 @@ impl Version {
         *self >= Version::Embedded(310) || *self >= Version::Desktop(410)
     }
 
+    /// Checks if the version supports `binding=` qualifiers for resources
+    ///
+    /// Note: On older versions the resources have to be bound by name instead,
+    /// using the names reported in the [`ReflectionInfo`].
+    fn supports_explicit_bindings(&self) -> bool {
+        *self >= Version::Embedded(310) || *self >= Version::Desktop(420)
+    }
+
     fn supports_early_depth_test(&self) -> bool {
         *self >= Version::Desktop(130) || *self >= Version::Embedded(310)
     }
@@ -389,6 +395,9 @@ pub struct Writer<'a, W> {
     entry_point: &'a crate::EntryPoint,
     /// The index of the selected entry point.
     entry_point_idx: proc::EntryPointIndex,
+    /// Whether the selected entry point, or any function it calls, issues a
+    /// storage memory barrier.
+    uses_storage_barriers: bool,
     /// A generator for unique block numbers.
     block_id: IdGenerator,
     /// Set of expressions that have associated temporary variables.
@@ -416,6 +425,9 @@ impl<'a, W: Write> Writer<'a, W> {
             log::error!("Version {}", options.version);
             return Err(Error::VersionNotSupported);
         }
+        if back::uses_specialized_array_lengths(module) {
+            return Err(Error::Custom("specialized array length".to_string()));
+        }
 
         // Try to find the entry point and corresponding index
         let ep_idx = module
@@ -443,6 +455,7 @@ impl<'a, W: Write> Writer<'a, W> {
             reflection_names_globals: crate::FastHashMap::default(),
             entry_point: &module.entry_points[ep_idx],
             entry_point_idx: ep_idx as u16,
+            uses_storage_barriers: uses_storage_barriers(module, &module.entry_points[ep_idx]),
 
             block_id: IdGenerator::default(),
             named_expressions: Default::default(),
@@ -587,7 +600,7 @@ impl<'a, W: Write> Writer<'a, W> {
                     }
 
                     // Gether the location if needed
-                    let layout_binding = if self.options.version.supports_explicit_locations() {
+                    let layout_binding = if self.options.version.supports_explicit_bindings() {
                         let br = global.binding.as_ref().unwrap();
                         self.options.binding_map.get(br).cloned()
                     } else {
@@ -879,29 +892,32 @@ impl<'a, W: Write> Writer<'a, W> {
         handle: Handle<crate::GlobalVariable>,
         global: &crate::GlobalVariable,
     ) -> BackendResult {
-        if self.options.version.supports_explicit_locations() {
-            if let Some(ref br) = global.binding {
-                match self.options.binding_map.get(br) {
-                    Some(binding) => {
-                        let layout = match global.space {
-                            crate::AddressSpace::Storage { .. } => {
-                                if self.options.version.supports_std430_layout() {
-                                    "std430, "
-                                } else {
-                                    "std140, "
-                                }
+        if let Some(ref br) = global.binding {
+            match self.options.binding_map.get(br) {
+                Some(binding) => {
+                    let layout = match global.space {
+                        crate::AddressSpace::Storage { .. } => {
+                            if self.options.version.supports_std430_layout() {
+                                "std430, "
+                            } else {
+                                "std140, "
                             }
-                            crate::AddressSpace::Uniform => "std140, ",
-                            _ => "",
-                        };
+                        }
+                        crate::AddressSpace::Uniform => "std140, ",
+                        _ => "",
+                    };
+                    if self.options.version.supports_explicit_bindings() {
                         write!(self.out, "layout({}binding = {}) ", layout, binding)?
+                    } else if !layout.is_empty() {
+                        // The caller binds the block by name instead
+                        write!(self.out, "layout({}) ", layout.trim_end_matches(", "))?
                     }
-                    None => {
-                        log::debug!("unassigned binding for {:?}", global.name);
-                        if let crate::AddressSpace::Storage { .. } = global.space {
-                            if self.options.version.supports_std430_layout() {
-                                write!(self.out, "layout(std430) ")?
-                            }
+                }
+                None => {
+                    log::debug!("unassigned binding for {:?}", global.name);
+                    if let crate::AddressSpace::Storage { .. } = global.space {
+                        if self.options.version.supports_std430_layout() {
+                            write!(self.out, "layout(std430) ")?
                         }
                     }
                 }
@@ -909,6 +925,11 @@ impl<'a, W: Write> Writer<'a, W> {
         }
 
         if let crate::AddressSpace::Storage { access } = global.space {
+            // Storage barriers only make the writes to `coherent` buffers
+            // visible to the other invocations.
+            if access.contains(crate::StorageAccess::STORE) && self.uses_storage_barriers {
+                write!(self.out, "coherent ")?;
+            }
             self.write_storage_access(access)?;
         }
 
@@ -1171,8 +1192,26 @@ impl<'a, W: Write> Writer<'a, W> {
                 sampling,
             } => (location, interpolation, sampling),
             crate::Binding::BuiltIn(built_in) => {
-                if let crate::BuiltIn::Position { invariant: true } = built_in {
-                    writeln!(self.out, "invariant {};", glsl_built_in(built_in, output))?;
+                match built_in {
+                    crate::BuiltIn::Position { invariant: true } => {
+                        writeln!(self.out, "invariant {};", glsl_built_in(built_in, output))?;
+                    }
+                    // The clip and cull distance arrays are implicitly sized, so they
+                    // need to be redeclared with their size to be assigned as a whole.
+                    // ES doesn't support them without an extension, and their stores
+                    // are skipped there.
+                    crate::BuiltIn::ClipDistance | crate::BuiltIn::CullDistance
+                        if output && !self.options.version.is_es() =>
+                    {
+                        if let TypeInner::Array { base, size, .. } = self.module.types[ty].inner {
+                            write!(self.out, "out ")?;
+                            self.write_type(ty)?;
+                            write!(self.out, " {}", glsl_built_in(built_in, output))?;
+                            self.write_array_size(base, size)?;
+                            writeln!(self.out, ";")?;
+                        }
+                    }
+                    _ => {}
                 }
                 return Ok(());
             }
@@ -1193,6 +1232,13 @@ impl<'a, W: Write> Writer<'a, W> {
             write!(self.out, "layout(location = {}) ", location)?;
         }
 
+        // Integers are never interpolated, and GLSL requires them to be
+        // qualified as `flat` in both stages.
+        let interpolation = match self.module.types[ty].inner.scalar_kind() {
+            Some(crate::ScalarKind::Float) => interpolation,
+            _ => Some(crate::Interpolation::Flat),
+        };
+
         // Write the interpolation qualifier.
         if let Some(interp) = interpolation {
             if emit_interpolation_and_auxiliary {
@@ -1665,6 +1711,7 @@ impl<'a, W: Write> Writer<'a, W> {
                 condition,
                 ref accept,
                 ref reject,
+                ..
             } => {
                 write!(self.out, "{}", level)?;
                 write!(self.out, "if (")?;
@@ -1844,12 +1891,14 @@ impl<'a, W: Write> Writer<'a, W> {
                                             }
                                         }
 
+                                        let binding = member.binding.as_ref().unwrap();
+                                        let conversion = output_conversion(binding);
                                         let varying_name = VaryingName {
-                                            binding: member.binding.as_ref().unwrap(),
+                                            binding,
                                             stage: ep.stage,
                                             output: true,
                                         };
-                                        write!(self.out, "{} = ", varying_name)?;
+                                        write!(self.out, "{} = {}", varying_name, conversion.0)?;
 
                                         if let Some(struct_name) = temp_struct_name {
                                             write!(self.out, "{}", struct_name)?;
@@ -1860,22 +1909,25 @@ impl<'a, W: Write> Writer<'a, W> {
                                         // Write field name
                                         writeln!(
                                             self.out,
-                                            ".{};",
+                                            ".{}{};",
                                             &self.names
-                                                [&NameKey::StructMember(result.ty, index as u32)]
+                                                [&NameKey::StructMember(result.ty, index as u32)],
+                                            conversion.1
                                         )?;
                                         write!(self.out, "{}", level)?;
                                     }
                                 }
                                 _ => {
+                                    let binding = result.binding.as_ref().unwrap();
+                                    let conversion = output_conversion(binding);
                                     let name = VaryingName {
-                                        binding: result.binding.as_ref().unwrap(),
+                                        binding,
                                         stage: ep.stage,
                                         output: true,
                                     };
-                                    write!(self.out, "{} = ", name)?;
+                                    write!(self.out, "{} = {}", name, conversion.0)?;
                                     self.write_expr(value, ctx)?;
-                                    writeln!(self.out, ";")?;
+                                    writeln!(self.out, "{};", conversion.1)?;
                                     write!(self.out, "{}", level)?;
                                 }
                             }
@@ -2914,35 +2966,33 @@ impl<'a, W: Write> Writer<'a, W> {
                         use crate::ScalarKind as Sk;
 
                         let source_kind = inner.scalar_kind().unwrap();
-                        let conv_op = match (source_kind, target_kind) {
-                            (Sk::Float, Sk::Sint) => "floatBitsToInt",
-                            (Sk::Float, Sk::Uint) => "floatBitsToUint",
-                            (Sk::Sint, Sk::Float) => "intBitsToFloat",
-                            (Sk::Uint, Sk::Float) => "uintBitsToFloat",
-                            // There is no way to bitcast between Uint/Sint in glsl. Use constructor conversion
-                            (Sk::Uint, Sk::Sint) => "int",
-                            (Sk::Sint, Sk::Uint) => "uint",
-
-                            (Sk::Bool, Sk::Sint) => "int",
-                            (Sk::Bool, Sk::Uint) => "uint",
-                            (Sk::Bool, Sk::Float) => "float",
-
-                            (Sk::Sint, Sk::Bool) => "bool",
-                            (Sk::Uint, Sk::Bool) => "bool",
-                            (Sk::Float, Sk::Bool) => "bool",
-
-                            // No conversion needed
-                            (Sk::Sint, Sk::Sint) => "",
-                            (Sk::Uint, Sk::Uint) => "",
-                            (Sk::Float, Sk::Float) => "",
-                            (Sk::Bool, Sk::Bool) => "",
+                        let bits_op = match (source_kind, target_kind) {
+                            (Sk::Float, Sk::Sint) => Some("floatBitsToInt"),
+                            (Sk::Float, Sk::Uint) => Some("floatBitsToUint"),
+                            (Sk::Sint, Sk::Float) => Some("intBitsToFloat"),
+                            (Sk::Uint, Sk::Float) => Some("uintBitsToFloat"),
+                            _ => None,
                         };
-                        write!(self.out, "{}", conv_op)?;
-                        if !conv_op.is_empty() {
+                        if let Some(op) = bits_op {
+                            write!(self.out, "{}(", op)?;
+                            self.write_expr(expr, ctx)?;
+                            write!(self.out, ")")?
+                        } else if source_kind == target_kind {
+                            // No conversion needed
+                            self.write_expr(expr, ctx)?;
+                        } else {
+                            // There is no way to bitcast between the other kinds
+                            // in glsl. Use constructor conversion, which keeps
+                            // the bits of integers.
+                            let scalar = glsl_scalar(target_kind, 4)?;
+                            match *inner {
+                                TypeInner::Vector { size, .. } => {
+                                    write!(self.out, "{}vec{}", scalar.prefix, size as u8)?
+                                }
+                                _ => write!(self.out, "{}", scalar.full)?,
+                            }
                             write!(self.out, "(")?;
-                        }
-                        self.write_expr(expr, ctx)?;
-                        if !conv_op.is_empty() {
+                            self.write_expr(expr, ctx)?;
                             write!(self.out, ")")?
                         }
                     }
@@ -3064,6 +3114,9 @@ impl<'a, W: Write> Writer<'a, W> {
                     .expect("Bad array size")
                 {
                     proc::IndexableLength::Known(count) => count,
+                    proc::IndexableLength::Specializable(_) => {
+                        return Err(Error::Custom("specialized array length".to_string()))
+                    }
                     proc::IndexableLength::Dynamic => return Ok(()),
                 };
                 self.write_type(base)?;
@@ -3199,7 +3252,7 @@ struct ScalarString<'a> {
 /// Check [`ScalarString`](ScalarString) for the information provided
 ///
 /// # Errors
-/// If a [`Float`](crate::ScalarKind::Float) with an width that isn't 4 or 8
+/// If a [`Float`](crate::ScalarKind::Float) with an width that isn't 2, 4 or 8
 const fn glsl_scalar(
     kind: crate::ScalarKind,
     width: crate::Bytes,
@@ -3216,6 +3269,10 @@ const fn glsl_scalar(
             full: "uint",
         },
         Sk::Float => match width {
+            2 => ScalarString {
+                prefix: "f16",
+                full: "float16_t",
+            },
             4 => ScalarString {
                 prefix: "",
                 full: "float",
@@ -3233,6 +3290,15 @@ const fn glsl_scalar(
     })
 }
 
+/// Returns the text to write around a value stored to the output `binding`,
+/// for outputs whose glsl type differs from the IR one.
+const fn output_conversion(binding: &crate::Binding) -> (&'static str, &'static str) {
+    match *binding {
+        crate::Binding::BuiltIn(crate::BuiltIn::SampleMask) => ("int(", ")"),
+        _ => ("", ""),
+    }
+}
+
 /// Helper function that returns the glsl variable name for a builtin
 const fn glsl_built_in(built_in: crate::BuiltIn, output: bool) -> &'static str {
     use crate::BuiltIn as Bi;
@@ -3258,12 +3324,15 @@ const fn glsl_built_in(built_in: crate::BuiltIn, output: bool) -> &'static str {
         Bi::FragDepth => "gl_FragDepth",
         Bi::FrontFacing => "gl_FrontFacing",
         Bi::PrimitiveIndex => "uint(gl_PrimitiveID)",
-        Bi::SampleIndex => "gl_SampleID",
+        Bi::SampleIndex => "uint(gl_SampleID)",
+        // The sample masks are arrays of signed integers, with one element
+        // per 32 samples. Writes to the output mask are converted by
+        // `output_conversion`.
         Bi::SampleMask => {
             if output {
-                "gl_SampleMask"
+                "gl_SampleMask[0]"
             } else {
-                "gl_SampleMaskIn"
+                "uint(gl_SampleMaskIn[0])"
             }
         }
         // compute
@@ -3377,3 +3446,215 @@ fn is_value_init_supported(module: &crate::Module, ty: Handle<crate::Type>) -> b
         _ => false,
     }
 }
+
+/// Check if the entry point `ep`, or any function it calls, issues a storage
+/// memory barrier.
+fn uses_storage_barriers(module: &crate::Module, ep: &crate::EntryPoint) -> bool {
+    /// Check if `block` issues a storage memory barrier, and add the
+    /// functions it calls to `calls`.
+    fn has_storage_barrier(block: &crate::Block, calls: &mut Vec<Handle<crate::Function>>) -> bool {
+        use crate::Statement;
+
+        block.iter().any(|statement| match *statement {
+            Statement::Barrier(flags) => flags.contains(crate::Barrier::STORAGE),
+            Statement::Call { function, .. } => {
+                calls.push(function);
+                false
+            }
+            Statement::Block(ref block) => has_storage_barrier(block, calls),
+            Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => has_storage_barrier(accept, calls) || has_storage_barrier(reject, calls),
+            Statement::Switch { ref cases, .. } => cases
+                .iter()
+                .any(|case| has_storage_barrier(&case.body, calls)),
+            Statement::Loop {
+                ref body,
+                ref continuing,
+            } => has_storage_barrier(body, calls) || has_storage_barrier(continuing, calls),
+            _ => false,
+        })
+    }
+
+    let mut calls = Vec::new();
+    if has_storage_barrier(&ep.function.body, &mut calls) {
+        return true;
+    }
+    let mut visited = crate::FastHashSet::default();
+    while let Some(handle) = calls.pop() {
+        if visited.insert(handle) && has_storage_barrier(&module.functions[handle].body, &mut calls)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Validate `module`, and write its `main` entry point of `shader_stage`
+/// with `options`.
+#[cfg(test)]
+fn write_test_module(
+    module: &crate::Module,
+    options: &Options,
+    shader_stage: ShaderStage,
+) -> String {
+    let info = valid::Validator::new(valid::ValidationFlags::all(), valid::Capabilities::empty())
+        .validate(module)
+        .unwrap();
+    let pipeline_options = PipelineOptions {
+        shader_stage,
+        entry_point: "main".to_string(),
+    };
+    let mut out = String::new();
+    Writer::new(&mut out, module, &info, options, &pipeline_options)
+        .unwrap()
+        .write()
+        .unwrap();
+    out
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_barriers() {
+    let mut module = crate::front::wgsl::parse_str(
+        "
+        @compute @workgroup_size(1)
+        fn main() {
+            workgroupBarrier();
+        }
+        ",
+    )
+    .unwrap();
+
+    let mut write = |flags| {
+        for statement in module.entry_points[0].function.body.iter_mut() {
+            if let crate::Statement::Barrier(ref mut barrier) = *statement {
+                *barrier = flags;
+            }
+        }
+        write_test_module(&module, &Options::default(), ShaderStage::Compute)
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.contains("arrier"))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(write(crate::Barrier::empty()), ["barrier();"]);
+    assert_eq!(
+        write(crate::Barrier::WORK_GROUP | crate::Barrier::STORAGE),
+        [
+            "memoryBarrierBuffer();",
+            "memoryBarrierShared();",
+            "barrier();"
+        ]
+    );
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_invariant_position() {
+    let module = crate::front::wgsl::parse_str(
+        "
+        @vertex
+        fn main() -> @builtin(position) @invariant vec4<f32> {
+            return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        }
+        ",
+    )
+    .unwrap();
+
+    let out = write_test_module(&module, &Options::default(), ShaderStage::Vertex);
+    let lines: Vec<_> = out.lines().map(str::trim).collect();
+    let invariant = lines
+        .iter()
+        .position(|&line| line == "invariant gl_Position;");
+    let main = lines.iter().position(|&line| line == "void main() {");
+    // The declaration has to come before any use of `gl_Position`.
+    assert!(invariant.unwrap() < main.unwrap(), "{}", out);
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_storage_qualifiers() {
+    let write = |source| {
+        let module = crate::front::wgsl::parse_str(source).unwrap();
+        let options = Options {
+            version: Version::Desktop(450),
+            ..Default::default()
+        };
+        write_test_module(&module, &options, ShaderStage::Compute)
+            .lines()
+            .filter(|line| line.contains(" buffer "))
+            .map(|line| line[line.find(") ").unwrap() + 2..].to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let source = "
+        struct Data { values: array<u32> }
+        @group(0) @binding(0) var<storage> input: Data;
+        @group(0) @binding(1) var<storage, read_write> output: Data;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+            output.values[id.x] = input.values[id.x];
+        }
+    ";
+    assert_eq!(
+        write(source),
+        [
+            "readonly buffer Data_block_0Compute {",
+            "buffer Data_block_1Compute {"
+        ]
+    );
+
+    // Writes are only visible across invocations after a barrier if the
+    // buffer is coherent.
+    let source = "
+        struct Data { values: array<u32> }
+        @group(0) @binding(0) var<storage> input: Data;
+        @group(0) @binding(1) var<storage, read_write> output: Data;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            output.values[index] = input.values[index];
+            storageBarrier();
+            output.values[index] = output.values[63u - index];
+        }
+    ";
+    assert_eq!(
+        write(source),
+        [
+            "readonly buffer Data_block_0Compute {",
+            "coherent buffer Data_block_1Compute {"
+        ]
+    );
+
+    // Only the barriers of the functions the entry point calls count.
+    let source = "
+        struct Data { values: array<u32> }
+        @group(0) @binding(0) var<storage, read_write> output: Data;
+
+        fn sync() {
+            storageBarrier();
+        }
+
+        fn unused() {
+            storageBarrier();
+        }
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            output.values[index] = index;
+            CALL
+        }
+    ";
+    let (called, uncalled) = (
+        source.replace("CALL", "sync();"),
+        source.replace("CALL", ""),
+    );
+    assert_eq!(write(&called), ["coherent buffer Data_block_0Compute {"]);
+    assert_eq!(write(&uncalled), ["buffer Data_block_0Compute {"]);
+}
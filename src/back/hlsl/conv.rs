@@ -20,7 +20,6 @@ impl crate::ScalarKind {
             Self::Sint => Ok("int"),
             Self::Uint => Ok("uint"),
             Self::Float => match width {
-                2 => Ok("half"),
                 4 => Ok("float"),
                 8 => Ok("double"),
                 _ => Err(Error::UnsupportedScalar(self, width)),
@@ -278,24 +278,23 @@ impl<'a, W: Write> super::Writer<'a, W> {
 
         // GetDimensions Overloaded Methods
         // https://docs.microsoft.com/en-us/windows/win32/direct3dhlsl/dx-graphics-hlsl-to-getdimensions#overloaded-methods
-        let (ret_swizzle, number_of_params) = match wiq.query {
-            ImageQuery::Size | ImageQuery::SizeLevel => {
-                let ret = match wiq.dim {
-                    IDim::D1 => "x",
-                    IDim::D2 => "xy",
-                    IDim::D3 => "xyz",
-                    IDim::Cube => "xy",
-                };
-                (ret, ret.len() + array_coords + extra_coords)
-            }
-            ImageQuery::NumLevels | ImageQuery::NumSamples | ImageQuery::NumLayers => {
-                if wiq.arrayed || wiq.dim == IDim::D3 {
-                    ("w", 4)
-                } else {
-                    ("z", 3)
-                }
+        //
+        // The outputs are the size, followed by the layer count if the image
+        // is arrayed, followed by the mip level or sample count.
+        let size_coords = match wiq.dim {
+            IDim::D1 => 1,
+            IDim::D2 | IDim::Cube => 2,
+            IDim::D3 => 3,
+        };
+        let number_of_params = size_coords + array_coords + extra_coords;
+        let ret_components = match wiq.query {
+            ImageQuery::Size | ImageQuery::SizeLevel => 0..size_coords,
+            ImageQuery::NumLayers => size_coords..size_coords + 1,
+            ImageQuery::NumLevels | ImageQuery::NumSamples => {
+                number_of_params - 1..number_of_params
             }
         };
+        let ret_swizzle: String = COMPONENTS[ret_components].iter().collect();
 
         // Write `GetDimensions` function.
         writeln!(self.out, "{}uint4 {};", INDENT, RETURN_VARIABLE_NAME)?;
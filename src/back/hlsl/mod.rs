@@ -193,6 +193,10 @@ pub struct Options {
     /// Add special constants to `SV_VertexIndex` and `SV_InstanceIndex`,
     /// to make them work like in Vulkan/Metal, with help of the host.
     pub special_constants_binding: Option<BindTarget>,
+    /// Zero the `groupshared` variables at the start of compute entry points,
+    /// since HLSL leaves them uninitialized.
+    #[cfg_attr(feature = "deserialize", serde(default))]
+    pub zero_initialize_workgroup_memory: bool,
 }
 
 impl Default for Options {
@@ -202,6 +206,7 @@ impl Default for Options {
             binding_map: BindingMap::default(),
             fake_missing_bindings: true,
             special_constants_binding: None,
+            zero_initialize_workgroup_memory: false,
         }
     }
 }
@@ -277,3 +282,108 @@ pub struct Writer<'a, W> {
     wrapped: Wrapped,
     temp_access_chain: Vec<storage::SubAccess>,
 }
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_constant_buffer_syntax() {
+    let source = "
+        struct Globals {
+            scale: vec4<f32>,
+        }
+
+        @group(0) @binding(0)
+        var<uniform> globals: Globals;
+
+        @group(0) @binding(1)
+        var<uniform> offset: vec4<f32>;
+
+        @vertex
+        fn main() -> @builtin(position) vec4<f32> {
+            return globals.scale * offset;
+        }
+    ";
+    let module = crate::front::wgsl::parse_str(source).unwrap();
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    let write = |shader_model| {
+        let options = Options {
+            shader_model,
+            ..Default::default()
+        };
+        let mut hlsl = String::new();
+        Writer::new(&mut hlsl, &options)
+            .write(&module, &info)
+            .unwrap();
+        hlsl
+    };
+
+    let hlsl = write(ShaderModel::V5_0);
+    assert!(hlsl.contains("cbuffer globals : register(b0) { Globals globals; }"));
+    assert!(hlsl.contains("cbuffer offset : register(b1) { float4 offset; }"));
+
+    let hlsl = write(ShaderModel::V5_1);
+    assert!(hlsl.contains("ConstantBuffer<Globals> globals : register(b0);"));
+    // Only structures can be wrapped in `ConstantBuffer<T>`.
+    assert!(hlsl.contains("cbuffer offset : register(b1) { float4 offset; }"));
+    // Members are accessed the same way in both cases.
+    assert!(hlsl.contains("globals.scale"));
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_workgroup_zero_init() {
+    let source = "
+        var<workgroup> data: array<f32, 64>;
+        var<workgroup> grid: array<array<u32, 2>, 4>;
+        var<workgroup> total: atomic<u32>;
+
+        @compute @workgroup_size(16, 2)
+        fn main(@builtin(local_invocation_id) id: vec3<u32>) {
+            data[id.x] = f32(grid[id.y][0]);
+            atomicAdd(&total, 1u);
+        }
+    ";
+    let module = crate::front::wgsl::parse_str(source).unwrap();
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    let write = |zero_initialize_workgroup_memory| {
+        let options = Options {
+            zero_initialize_workgroup_memory,
+            ..Default::default()
+        };
+        let mut hlsl = String::new();
+        Writer::new(&mut hlsl, &options)
+            .write(&module, &info)
+            .unwrap();
+        hlsl
+    };
+
+    let hlsl = write(false);
+    assert!(!hlsl.contains("SV_GroupIndex"));
+    assert!(!hlsl.contains("GroupMemoryBarrierWithGroupSync"));
+
+    let hlsl = write(true);
+    for line in [
+        "void main(uint3 id : SV_GroupThreadID, uint local_invocation_index : SV_GroupIndex)",
+        "for (uint i = local_invocation_index; i < 64u; i += 32u) {",
+        "data[i] = (float)0;",
+        "for (uint i_1 = local_invocation_index; i_1 < 4u; i_1 += 32u) {",
+        "for (uint i_2 = 0u; i_2 < 2u; i_2++) {",
+        "grid[i_1][i_2] = (uint)0;",
+        "if (local_invocation_index == 0u) {",
+        "total = (uint)0;",
+        "GroupMemoryBarrierWithGroupSync();",
+    ] {
+        assert!(hlsl.contains(line), "Missing {:?} in:\n{}", line, hlsl);
+    }
+}
@@ -102,6 +102,13 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
     ) -> Result<super::ReflectionInfo, Error> {
         self.reset(module);
 
+        if back::uses_float16(module) {
+            return Err(Error::UnsupportedScalar(ScalarKind::Float, 2));
+        }
+        if back::uses_specialized_array_lengths(module) {
+            return Err(Error::Unimplemented("specialized array length".to_string()));
+        }
+
         // Write special constants, if needed
         if let Some(ref bt) = self.options.special_constants_binding {
             writeln!(self.out, "struct {} {{", SPECIAL_CBUF_TYPE)?;
@@ -587,6 +594,15 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 self.write_type(module, global.ty)?;
                 ""
             }
+            crate::AddressSpace::Uniform if self.uses_constant_buffer_type(inner) => {
+                // `ConstantBuffer<T>` is only available in SM 5.1 and above,
+                // and only wraps structures, e.g.
+                // `ConstantBuffer<Foo> foo: register(b0);`
+                write!(self.out, "ConstantBuffer<")?;
+                self.write_type(module, global.ty)?;
+                write!(self.out, ">")?;
+                "b"
+            }
             crate::AddressSpace::Uniform => {
                 // constant buffer declarations are expected to be inlined, e.g.
                 // `cbuffer foo: register(b0) { field1: type1; }`
@@ -659,7 +675,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             }
         }
 
-        if global.space == crate::AddressSpace::Uniform {
+        if global.space == crate::AddressSpace::Uniform && !self.uses_constant_buffer_type(inner) {
             write!(self.out, " {{ ")?;
             // Even though Naga IR matrices are column-major, we must describe
             // matrices passed from the CPU as being in row-major order.  See
@@ -682,6 +698,13 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         Ok(())
     }
 
+    /// Returns true if a uniform buffer of type `inner` is declared as
+    /// `ConstantBuffer<T>` rather than a classic `cbuffer` block.
+    fn uses_constant_buffer_type(&self, inner: &TypeInner) -> bool {
+        self.options.shader_model >= super::ShaderModel::V5_1
+            && matches!(*inner, TypeInner::Struct { .. })
+    }
+
     /// Helper method used to write global constants
     ///
     /// # Notes
@@ -1011,6 +1034,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         // Write function name
         write!(self.out, " {}(", name)?;
 
+        // The expression giving the local invocation index, and the name of
+        // the argument to add for it when the entry point has none, for
+        // zeroing the workgroup memory.
+        let mut zero_init_index = None;
+        let mut extra_argument = None;
+
         // Write function arguments for non entry point functions
         match func_ctx.ty {
             back::FunctionType::Function(handle) => {
@@ -1045,6 +1074,17 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                     write!(self.out, "{} {}", ep_input.ty_name, ep_input.arg_name,)?;
                 } else {
                     let stage = module.entry_points[ep_index as usize].stage;
+                    if self.need_workgroup_zero_init(module, func_ctx) {
+                        let index = match self.local_invocation_index(module, func, ep_index) {
+                            Some(index) => index,
+                            None => {
+                                let name = self.namer.call("local_invocation_index");
+                                extra_argument = Some(name.clone());
+                                name
+                            }
+                        };
+                        zero_init_index = Some(index);
+                    }
                     for (index, arg) in func.arguments.iter().enumerate() {
                         if index != 0 {
                             write!(self.out, ", ")?;
@@ -1063,6 +1103,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                             self.write_semantic(binding, Some((stage, Io::Input)))?;
                         }
                     }
+                    if let Some(name) = extra_argument {
+                        if !func.arguments.is_empty() {
+                            write!(self.out, ", ")?;
+                        }
+                        write!(self.out, "uint {} : SV_GroupIndex", name)?;
+                    }
                 }
             }
         }
@@ -1125,6 +1171,12 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
             writeln!(self.out)?;
         }
 
+        if let back::FunctionType::EntryPoint(ep_index) = func_ctx.ty {
+            if let Some(ref index) = zero_init_index {
+                self.write_workgroup_zero_init(module, func_ctx, ep_index, index)?;
+            }
+        }
+
         // Write the function body (statement list)
         for sta in func.body.iter() {
             // The indentation should always be 1 when writing the function body
@@ -1199,6 +1251,7 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 condition,
                 ref accept,
                 ref reject,
+                ..
             } => {
                 write!(self.out, "{}", level)?;
                 write!(self.out, "if (")?;
@@ -2141,41 +2194,69 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
                 convert,
             } => {
                 let inner = func_ctx.info[expr].ty.inner_with(&module.types);
-                let get_width = |src_width| kind.to_hlsl_str(convert.unwrap_or(src_width));
-                match *inner {
-                    TypeInner::Vector { size, width, .. } => {
-                        write!(
-                            self.out,
-                            "{}{}(",
-                            get_width(width)?,
-                            back::vector_size_str(size)
-                        )?;
-                    }
-                    TypeInner::Scalar { width, .. } => {
-                        write!(self.out, "{}(", get_width(width)?,)?;
-                    }
-                    TypeInner::Matrix {
-                        columns,
-                        rows,
-                        width,
-                    } => {
-                        write!(
-                            self.out,
-                            "{}{}x{}(",
-                            get_width(width)?,
-                            back::vector_size_str(columns),
-                            back::vector_size_str(rows)
-                        )?;
-                    }
-                    _ => {
-                        return Err(Error::Unimplemented(format!(
-                            "write_expr expression::as {:?}",
-                            inner
-                        )));
-                    }
-                };
-                self.write_expr(module, expr, func_ctx)?;
-                write!(self.out, ")")?;
+                if convert.is_none() && inner.scalar_kind() == Some(kind) {
+                    // Identity constructors, like `vec2(v)`, keep the value.
+                    self.write_expr(module, expr, func_ctx)?;
+                } else if convert.is_none() {
+                    // Bitcasts keep the width, and the `as*` intrinsics work
+                    // on all the shapes of 32-bit values.
+                    let width = match *inner {
+                        TypeInner::Scalar { width, .. }
+                        | TypeInner::Vector { width, .. }
+                        | TypeInner::Matrix { width, .. } => Some(width),
+                        _ => None,
+                    };
+                    let fun = match (kind, width) {
+                        (ScalarKind::Sint, Some(4)) => "asint",
+                        (ScalarKind::Uint, Some(4)) => "asuint",
+                        (ScalarKind::Float, Some(4)) => "asfloat",
+                        _ => {
+                            return Err(Error::Unimplemented(format!(
+                                "write_expr expression::as bitcast of {:?} to {:?}",
+                                inner, kind
+                            )));
+                        }
+                    };
+                    write!(self.out, "{}(", fun)?;
+                    self.write_expr(module, expr, func_ctx)?;
+                    write!(self.out, ")")?;
+                } else {
+                    let get_width = |src_width| kind.to_hlsl_str(convert.unwrap_or(src_width));
+                    match *inner {
+                        TypeInner::Vector { size, width, .. } => {
+                            write!(
+                                self.out,
+                                "{}{}(",
+                                get_width(width)?,
+                                back::vector_size_str(size)
+                            )?;
+                        }
+                        TypeInner::Scalar { width, .. } => {
+                            write!(self.out, "{}(", get_width(width)?,)?;
+                        }
+                        TypeInner::Matrix {
+                            columns,
+                            rows,
+                            width,
+                        } => {
+                            write!(
+                                self.out,
+                                "{}{}x{}(",
+                                get_width(width)?,
+                                back::vector_size_str(columns),
+                                back::vector_size_str(rows)
+                            )?;
+                        }
+                        _ => {
+                            return Err(Error::Unimplemented(format!(
+                                "write_expr expression::as {:?}",
+                                inner
+                            )));
+                        }
+                    };
+                    self.write_expr(module, expr, func_ctx)?;
+                    write!(self.out, ")")?;
+                }
             }
             Expression::Math {
                 fun,
@@ -2540,6 +2621,137 @@ impl<'a, W: fmt::Write> super::Writer<'a, W> {
         Ok(())
     }
 
+    /// Return whether the entry point has `groupshared` variables to zero.
+    fn need_workgroup_zero_init(&self, module: &Module, func_ctx: &back::FunctionCtx<'_>) -> bool {
+        self.options.zero_initialize_workgroup_memory
+            && module.global_variables.iter().any(|(handle, var)| {
+                var.space == crate::AddressSpace::WorkGroup && !func_ctx.info[handle].is_empty()
+            })
+    }
+
+    /// Return the expression reading the `SV_GroupIndex` argument of the
+    /// entry point, if it has one.
+    fn local_invocation_index(
+        &self,
+        module: &Module,
+        func: &crate::Function,
+        ep_index: u16,
+    ) -> Option<String> {
+        let is_index = |binding: &Option<crate::Binding>| {
+            *binding
+                == Some(crate::Binding::BuiltIn(
+                    crate::BuiltIn::LocalInvocationIndex,
+                ))
+        };
+        for (arg_index, arg) in func.arguments.iter().enumerate() {
+            let arg_name = &self.names[&NameKey::EntryPointArgument(ep_index, arg_index as u32)];
+            if is_index(&arg.binding) {
+                return Some(arg_name.clone());
+            }
+            if let TypeInner::Struct { ref members, .. } = module.types[arg.ty].inner {
+                if let Some(member_index) = members.iter().position(|m| is_index(&m.binding)) {
+                    let member_name =
+                        &self.names[&NameKey::StructMember(arg.ty, member_index as u32)];
+                    return Some(format!("{}.{}", arg_name, member_name));
+                }
+            }
+        }
+        None
+    }
+
+    /// Zero the `groupshared` variables used by the entry point, then wait for
+    /// the whole group to see them.
+    ///
+    /// The elements of arrays are split between the invocations of the group
+    /// by their local invocation index, the other variables are written by the
+    /// first invocation.
+    fn write_workgroup_zero_init(
+        &mut self,
+        module: &Module,
+        func_ctx: &back::FunctionCtx<'_>,
+        ep_index: u16,
+        index: &str,
+    ) -> BackendResult {
+        let ep = &module.entry_points[ep_index as usize];
+        let invocations: u32 = ep.workgroup_size.iter().product();
+        let level = back::Level(1);
+
+        for (handle, var) in module.global_variables.iter() {
+            if var.space != crate::AddressSpace::WorkGroup || func_ctx.info[handle].is_empty() {
+                continue;
+            }
+            let name = self.names[&NameKey::GlobalVariable(handle)].clone();
+            match module.types[var.ty].inner {
+                TypeInner::Array {
+                    base,
+                    size: crate::ArraySize::Constant(size),
+                    ..
+                } => {
+                    let count = module.constants[size].to_array_length().unwrap();
+                    let loop_index = self.namer.call("i");
+                    writeln!(
+                        self.out,
+                        "{}for (uint {i} = {}; {i} < {}u; {i} += {}u) {{",
+                        level,
+                        index,
+                        count,
+                        invocations,
+                        i = loop_index
+                    )?;
+                    let element = format!("{}[{}]", name, loop_index);
+                    self.write_zero_init_element(module, base, element, level.next())?;
+                    writeln!(self.out, "{}}}", level)?;
+                }
+                _ => {
+                    writeln!(self.out, "{}if ({} == 0u) {{", level, index)?;
+                    self.write_zero_init_element(module, var.ty, name, level.next())?;
+                    writeln!(self.out, "{}}}", level)?;
+                }
+            }
+        }
+
+        writeln!(self.out, "{}GroupMemoryBarrierWithGroupSync();", level)?;
+        writeln!(self.out)?;
+        Ok(())
+    }
+
+    /// Zero `target`, looping over the elements of arrays, which can't be
+    /// assigned at once.
+    fn write_zero_init_element(
+        &mut self,
+        module: &Module,
+        ty: Handle<crate::Type>,
+        target: String,
+        level: back::Level,
+    ) -> BackendResult {
+        match module.types[ty].inner {
+            TypeInner::Array {
+                base,
+                size: crate::ArraySize::Constant(size),
+                ..
+            } => {
+                let count = module.constants[size].to_array_length().unwrap();
+                let loop_index = self.namer.call("i");
+                writeln!(
+                    self.out,
+                    "{}for (uint {i} = 0u; {i} < {}u; {i}++) {{",
+                    level,
+                    count,
+                    i = loop_index
+                )?;
+                let element = format!("{}[{}]", target, loop_index);
+                self.write_zero_init_element(module, base, element, level.next())?;
+                writeln!(self.out, "{}}}", level)?;
+            }
+            _ => {
+                write!(self.out, "{}{} = ", level, target)?;
+                self.write_default_init(module, ty)?;
+                writeln!(self.out, ";")?;
+            }
+        }
+        Ok(())
+    }
+
     /// Helper function that write default zero initialization
     fn write_default_init(&mut self, module: &Module, ty: Handle<crate::Type>) -> BackendResult {
         match module.types[ty].inner {
@@ -184,6 +184,68 @@ const fn vector_size_str(size: crate::VectorSize) -> &'static str {
     }
 }
 
+/// Return whether the module uses 2 byte floats, in its types, constants or
+/// conversions.
+///
+/// # Notes
+/// Used by `msl-out`, `wgsl-out`, `hlsl-out`, which don't support them.
+fn uses_float16(module: &crate::Module) -> bool {
+    use crate::{ScalarKind as Sk, TypeInner as Ti};
+
+    let in_types = module.types.iter().any(|(_, ty)| match ty.inner {
+        Ti::Scalar { kind, width }
+        | Ti::Vector { kind, width, .. }
+        | Ti::ValuePointer { kind, width, .. } => kind == Sk::Float && width == 2,
+        Ti::Matrix { width, .. } => width == 2,
+        _ => false,
+    });
+    let in_constants = module.constants.iter().any(|(_, constant)| {
+        matches!(
+            constant.inner,
+            crate::ConstantInner::Scalar {
+                width: 2,
+                value: crate::ScalarValue::Float(_),
+            }
+        )
+    });
+    let in_conversions = module
+        .functions
+        .iter()
+        .map(|(_, function)| function)
+        .chain(module.entry_points.iter().map(|ep| &ep.function))
+        .flat_map(|function| function.expressions.iter())
+        .any(|(_, expression)| {
+            matches!(
+                *expression,
+                crate::Expression::As {
+                    kind: Sk::Float,
+                    convert: Some(2),
+                    ..
+                }
+            )
+        });
+    in_types || in_constants || in_conversions
+}
+
+/// Return whether the module has arrays sized by specialization constants.
+///
+/// # Notes
+/// Used by `msl-out`, `glsl-out`, `wgsl-out`, `hlsl-out`, which would write
+/// out their default length.
+fn uses_specialized_array_lengths(module: &crate::Module) -> bool {
+    module.types.iter().any(|(_, ty)| match ty.inner {
+        crate::TypeInner::Array {
+            size: crate::ArraySize::Constant(size),
+            ..
+        }
+        | crate::TypeInner::BindingArray {
+            size: crate::ArraySize::Constant(size),
+            ..
+        } => module.constants[size].specialization.is_some(),
+        _ => false,
+    })
+}
+
 impl crate::TypeInner {
     const fn is_handle(&self) -> bool {
         match *self {
@@ -162,6 +162,8 @@ pub enum EntryPointError {
     MissingPushConstants,
     #[error("mapping for sizes buffer is missing")]
     MissingSizesBuffer,
+    #[error("{0:?} and {1:?} are mapped to the same slot")]
+    ConflictingBindings(crate::ResourceBinding, crate::ResourceBinding),
 }
 
 /// Points in the MSL code where we might emit a pipeline input or output.
@@ -242,15 +244,15 @@ impl Options {
         match *binding {
             crate::Binding::BuiltIn(mut built_in) => {
                 if let crate::BuiltIn::Position { ref mut invariant } = built_in {
-                    if *invariant && self.lang_version < (2, 1) {
-                        return Err(Error::UnsupportedAttribute("invariant".to_string()));
-                    }
-
                     // The 'invariant' attribute may only appear on vertex
                     // shader outputs, not fragment shader inputs.
                     if !matches!(mode, LocationMode::VertexOutput) {
                         *invariant = false;
                     }
+
+                    if *invariant && self.lang_version < (2, 1) {
+                        return Err(Error::UnsupportedAttribute("invariant".to_string()));
+                    }
                 }
 
                 Ok(ResolvedBinding::BuiltIn(built_in))
@@ -477,6 +479,9 @@ pub struct TranslationInfo {
     ///
     ///Note: Some entry points may fail translation because of missing bindings.
     pub entry_point_names: Vec<Result<String, EntryPointError>>,
+    /// The slots of the resources used by each entry point, in the same order.
+    /// Entry points that failed translation get an empty map.
+    pub entry_point_resources: Vec<BindingMap>,
 }
 
 pub fn write_string(
@@ -495,3 +500,135 @@ fn test_error_size() {
     use std::mem::size_of;
     assert_eq!(size_of::<Error>(), 32);
 }
+
+#[test]
+fn test_invariant_position() {
+    let binding = crate::Binding::BuiltIn(crate::BuiltIn::Position { invariant: true });
+    let resolve = |lang_version, mode| {
+        let options = Options {
+            lang_version,
+            ..Default::default()
+        };
+        options
+            .resolve_local_binding(&binding, mode)
+            .map(|resolved| {
+                let mut attribute = String::new();
+                resolved.try_fmt(&mut attribute).unwrap();
+                attribute
+            })
+    };
+
+    assert_eq!(
+        resolve((2, 1), LocationMode::VertexOutput).unwrap(),
+        " [[position, invariant]]"
+    );
+    assert!(matches!(
+        resolve((2, 0), LocationMode::VertexOutput),
+        Err(Error::UnsupportedAttribute(_))
+    ));
+    // Fragment inputs drop the attribute, so older versions are fine.
+    assert_eq!(
+        resolve((2, 0), LocationMode::FragmentInput).unwrap(),
+        " [[position]]"
+    );
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_depth_textures() {
+    let module = crate::front::wgsl::parse_str(
+        "
+        @group(0) @binding(0) var shadow: texture_depth_2d;
+        @group(0) @binding(1) var shadow_array: texture_depth_2d_array;
+        @group(0) @binding(2) var shadow_cube: texture_depth_cube;
+        @group(0) @binding(3) var compare: sampler_comparison;
+
+        @fragment
+        fn main(@location(0) uv: vec3<f32>) -> @location(0) f32 {
+            return textureSampleCompare(shadow, compare, uv.xy, 0.5)
+                + textureSampleCompare(shadow_array, compare, uv.xy, 1, 0.5)
+                + textureSampleCompare(shadow_cube, compare, uv, 0.5);
+        }
+        ",
+    )
+    .unwrap();
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+    let (source, _) = write_string(
+        &module,
+        &info,
+        &Options::default(),
+        &PipelineOptions::default(),
+    )
+    .unwrap();
+
+    for ty in [
+        "metal::depth2d<float, metal::access::sample> shadow",
+        "metal::depth2d_array<float, metal::access::sample> shadow_array",
+        "metal::depthcube<float, metal::access::sample> shadow_cube",
+        "metal::sampler compare",
+    ] {
+        assert!(source.contains(ty), "Missing {:?} in:\n{}", ty, source);
+    }
+    assert_eq!(source.matches(".sample_compare(compare, ").count(), 3);
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_resource_slots() {
+    let module = crate::front::wgsl::parse_str(
+        "
+        struct Data { value: f32 }
+        @group(0) @binding(0) var<uniform> first: Data;
+        @group(0) @binding(1) var<uniform> second: Data;
+
+        @fragment
+        fn main() -> @location(0) f32 {
+            return first.value + second.value;
+        }
+        ",
+    )
+    .unwrap();
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+    let write = |slots: [Slot; 2]| {
+        let mut options = Options::default();
+        for (binding, slot) in slots.iter().enumerate() {
+            options.per_stage_map.fs.resources.insert(
+                crate::ResourceBinding {
+                    group: 0,
+                    binding: binding as u32,
+                },
+                BindTarget {
+                    buffer: Some(*slot),
+                    ..Default::default()
+                },
+            );
+        }
+        write_string(&module, &info, &options, &PipelineOptions::default()).unwrap()
+    };
+
+    let (source, translation) = write([5, 2]);
+    assert!(source.contains("first [[buffer(5)]]"), "{}", source);
+    assert!(source.contains("second [[buffer(2)]]"), "{}", source);
+    let slots: Vec<_> = translation.entry_point_resources[0]
+        .values()
+        .map(|target| target.buffer)
+        .collect();
+    assert_eq!(slots, [Some(5), Some(2)]);
+
+    let (_, translation) = write([3, 3]);
+    assert!(matches!(
+        translation.entry_point_names[0],
+        Err(EntryPointError::ConflictingBindings(..))
+    ));
+    assert!(translation.entry_point_resources[0].is_empty());
+}
@@ -417,6 +417,69 @@ fn should_pack_struct_member(
     }
 }
 
+/// Collect the bind targets of the resources used by an entry point, checking
+/// that no two of them share a slot.
+///
+/// Binding arrays occupy as many consecutive slots as they have elements.
+fn entry_point_resources(
+    module: &crate::Module,
+    fun_info: &valid::FunctionInfo,
+    map: &super::PerStageResources,
+) -> Result<super::BindingMap, super::EntryPointError> {
+    // The slot ranges taken so far, per kind of slot.
+    let mut taken: [Vec<(std::ops::Range<u32>, &crate::ResourceBinding)>; 3] = Default::default();
+    let mut resources = super::BindingMap::new();
+    for (var_handle, var) in module.global_variables.iter() {
+        if fun_info[var_handle].is_empty() {
+            continue;
+        }
+        let (binding, target) = match var.binding {
+            Some(ref binding) => match map.resources.get(binding) {
+                Some(target) => (binding, target),
+                None => continue,
+            },
+            None => continue,
+        };
+        let (base, count) = match module.types[var.ty].inner {
+            crate::TypeInner::BindingArray { base, size } => {
+                let count = match target.binding_array_size {
+                    Some(count) => count,
+                    None => match size {
+                        crate::ArraySize::Constant(c) => {
+                            module.constants[c].to_array_length().unwrap_or(1)
+                        }
+                        crate::ArraySize::Dynamic => 1,
+                    },
+                };
+                (base, count)
+            }
+            _ => (var.ty, 1),
+        };
+        let (kind, slot) = match module.types[base].inner {
+            crate::TypeInner::Image { .. } => (0, target.texture),
+            crate::TypeInner::Sampler { .. } => match target.sampler {
+                Some(super::BindSamplerTarget::Resource(slot)) => (1, Some(slot)),
+                _ => (1, None),
+            },
+            _ => (2, target.buffer),
+        };
+        if let Some(slot) = slot {
+            let range = slot as u32..slot as u32 + count;
+            for &(ref other, other_binding) in taken[kind].iter() {
+                if range.start < other.end && other.start < range.end {
+                    return Err(super::EntryPointError::ConflictingBindings(
+                        other_binding.clone(),
+                        binding.clone(),
+                    ));
+                }
+            }
+            taken[kind].push((range, binding));
+        }
+        resources.insert(binding.clone(), target.clone());
+    }
+    Ok(resources)
+}
+
 fn needs_array_length(ty: Handle<crate::Type>, arena: &crate::UniqueArena<crate::Type>) -> bool {
     match arena[ty].inner {
         crate::TypeInner::Struct { ref members, .. } => {
@@ -1922,6 +1985,11 @@ impl<W: Write> Writer<W> {
                     self.out.write_str(") < ")?;
                     match length {
                         index::IndexableLength::Known(value) => write!(self.out, "{}", value)?,
+                        index::IndexableLength::Specializable(_) => {
+                            return Err(Error::FeatureNotImplemented(
+                                "specialized array length".to_string(),
+                            ))
+                        }
                         index::IndexableLength::Dynamic => {
                             let global = context
                                 .function
@@ -2082,6 +2150,11 @@ impl<W: Write> Writer<W> {
                 index::IndexableLength::Known(limit) => {
                     write!(self.out, "{}u", limit - 1)?;
                 }
+                index::IndexableLength::Specializable(_) => {
+                    return Err(Error::FeatureNotImplemented(
+                        "specialized array length".to_string(),
+                    ))
+                }
                 index::IndexableLength::Dynamic => {
                     let global = context
                         .function
@@ -2490,6 +2563,7 @@ impl<W: Write> Writer<W> {
                     condition,
                     ref accept,
                     ref reject,
+                    ..
                 } => {
                     write!(self.out, "{}if (", level)?;
                     self.put_expression(condition, &context.expression, true)?;
@@ -2822,6 +2896,15 @@ impl<W: Write> Writer<W> {
             .reset(module, super::keywords::RESERVED, &[], &mut self.names);
         self.struct_member_pads.clear();
 
+        if back::uses_float16(module) {
+            return Err(Error::CapabilityNotSupported(valid::Capabilities::FLOAT16));
+        }
+        if back::uses_specialized_array_lengths(module) {
+            return Err(Error::FeatureNotImplemented(
+                "specialized array length".to_string(),
+            ));
+        }
+
         writeln!(
             self.out,
             "// language: metal{}.{}",
@@ -3324,6 +3407,7 @@ impl<W: Write> Writer<W> {
 
         let mut info = TranslationInfo {
             entry_point_names: Vec::with_capacity(module.entry_points.len()),
+            entry_point_resources: Vec::with_capacity(module.entry_points.len()),
         };
         for (ep_index, ep) in module.entry_points.iter().enumerate() {
             let fun = &ep.function;
@@ -3386,12 +3470,22 @@ impl<W: Write> Writer<W> {
                 }
             }
 
+            let mut resources = super::BindingMap::new();
+            if ep_error.is_none() {
+                match entry_point_resources(module, fun_info, &options.per_stage_map[ep.stage]) {
+                    Ok(map) => resources = map,
+                    Err(err) => ep_error = Some(err),
+                }
+            }
+
             if let Some(err) = ep_error {
                 info.entry_point_names.push(Err(err));
+                info.entry_point_resources.push(resources);
                 continue;
             }
             let fun_name = &self.names[&NameKey::EntryPoint(ep_index as _)];
             info.entry_point_names.push(Ok(fun_name.clone()));
+            info.entry_point_resources.push(resources);
 
             writeln!(self.out)?;
 
@@ -3557,6 +3651,11 @@ impl<W: Write> Writer<W> {
                 None => "void",
             };
 
+            // Forced early fragment tests are an attribute of the function.
+            if ep.early_depth_test == Some(crate::EarlyDepthTest { conservative: None }) {
+                writeln!(self.out, "[[early_fragment_tests]]")?;
+            }
+
             // Write the entry point function's name, and begin its argument list.
             writeln!(self.out, "{} {} {}(", em_str, result_type_name, fun_name)?;
             let mut is_first_argument = true;
@@ -3902,6 +4001,7 @@ fn test_stack_size() {
             condition: nested_expr,
             accept: crate::Block::new(),
             reject: crate::Block::new(),
+            hint: None,
         },
         Default::default(),
     );
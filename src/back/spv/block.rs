@@ -18,6 +18,15 @@ fn get_dimension(type_inner: &crate::TypeInner) -> Dimension {
     }
 }
 
+/// The `OpBranchConditional` weights of the true and false labels for a branch
+/// taken as `hint` says.
+const fn branch_weights(hint: crate::BranchHint) -> [Word; 2] {
+    match hint {
+        crate::BranchHint::Likely => [100, 1],
+        crate::BranchHint::Unlikely => [1, 100],
+    }
+}
+
 /// The results of emitting code for a left-hand-side expression.
 ///
 /// On success, `write_expression_pointer` returns one of these.
@@ -1129,32 +1138,36 @@ impl<'w> BlockContext<'w> {
                     .inner_with(&self.ir_module.types);
                 let object_ty = self.fun_info[accept].ty.inner_with(&self.ir_module.types);
 
-                if let (
-                    &crate::TypeInner::Scalar {
-                        kind: crate::ScalarKind::Bool,
-                        width,
-                    },
-                    &crate::TypeInner::Vector { size, .. },
-                ) = (condition_ty, object_ty)
-                {
-                    self.temp_list.clear();
-                    self.temp_list.resize(size as usize, condition_id);
-
-                    let bool_vector_type_id =
-                        self.get_type_id(LookupType::Local(LocalType::Value {
-                            vector_size: Some(size),
+                // Before SPIR-V 1.4, the condition must have as many
+                // components as the selected vectors.
+                match (condition_ty, object_ty) {
+                    (
+                        &crate::TypeInner::Scalar {
                             kind: crate::ScalarKind::Bool,
                             width,
-                            pointer_space: None,
-                        }));
+                        },
+                        &crate::TypeInner::Vector { size, .. },
+                    ) if self.writer.physical_layout.version < 0x10400 => {
+                        self.temp_list.clear();
+                        self.temp_list.resize(size as usize, condition_id);
+
+                        let bool_vector_type_id =
+                            self.get_type_id(LookupType::Local(LocalType::Value {
+                                vector_size: Some(size),
+                                kind: crate::ScalarKind::Bool,
+                                width,
+                                pointer_space: None,
+                            }));
 
-                    let id = self.gen_id();
-                    block.body.push(Instruction::composite_construct(
-                        bool_vector_type_id,
-                        id,
-                        &self.temp_list,
-                    ));
-                    condition_id = id
+                        let id = self.gen_id();
+                        block.body.push(Instruction::composite_construct(
+                            bool_vector_type_id,
+                            id,
+                            &self.temp_list,
+                        ));
+                        condition_id = id
+                    }
+                    _ => {}
                 }
 
                 let instruction =
@@ -1516,6 +1529,7 @@ impl<'w> BlockContext<'w> {
                     condition,
                     ref accept,
                     ref reject,
+                    hint,
                 } => {
                     let condition_id = self.cached[condition];
 
@@ -1542,6 +1556,7 @@ impl<'w> BlockContext<'w> {
                             condition_id,
                             accept_id.unwrap_or(merge_id),
                             reject_id.unwrap_or(merge_id),
+                            hint.map(branch_weights),
                         ),
                     );
 
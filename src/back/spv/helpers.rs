@@ -20,6 +20,16 @@ pub(super) fn string_to_words(input: &str) -> Vec<Word> {
     words
 }
 
+/// Encode a debug name, dropping the nul characters, which would end the
+/// string early.
+pub(super) fn debug_name_to_words(name: &str) -> Vec<Word> {
+    if name.contains('\0') {
+        string_to_words(&name.replace('\0', ""))
+    } else {
+        string_to_words(name)
+    }
+}
+
 pub(super) const fn map_storage_class(space: crate::AddressSpace) -> spirv::StorageClass {
     match space {
         crate::AddressSpace::Handle => spirv::StorageClass::UniformConstant,
@@ -314,9 +314,14 @@ impl<'w> BlockContext<'w> {
         };
 
         // Convert the index to the coordinate component type, if necessary.
-        let array_index_i32_id = self.cached[array_index];
-        let reconciled_array_index_id = if component_kind == crate::ScalarKind::Sint {
-            array_index_i32_id
+        let array_index_id = self.cached[array_index];
+        let index_kind = self.fun_info[array_index]
+            .ty
+            .inner_with(&self.ir_module.types)
+            .scalar_kind()
+            .unwrap_or(crate::ScalarKind::Sint);
+        let reconciled_array_index_id = if component_kind == index_kind {
+            array_index_id
         } else {
             let component_type_id = self.get_type_id(LookupType::Local(LocalType::Value {
                 vector_size: None,
@@ -324,13 +329,19 @@ impl<'w> BlockContext<'w> {
                 width: 4,
                 pointer_space: None,
             }));
+            let op = match (component_kind, index_kind) {
+                (crate::ScalarKind::Float, crate::ScalarKind::Uint) => spirv::Op::ConvertUToF,
+                (crate::ScalarKind::Float, _) => spirv::Op::ConvertSToF,
+                // Integer coordinates only differ in signedness.
+                _ => spirv::Op::Bitcast,
+            };
 
             let reconciled_id = self.gen_id();
             block.body.push(Instruction::unary(
-                spirv::Op::ConvertUToF,
+                op,
                 component_type_id,
                 reconciled_id,
-                array_index_i32_id,
+                array_index_id,
             ));
             reconciled_id
         };
@@ -391,14 +402,9 @@ impl<'w> BlockContext<'w> {
             None => Ok(one),
             Some(vector_size) => {
                 let ones = [one; 4];
-                let id = self.gen_id();
-                Instruction::constant_composite(
-                    coordinates.type_id,
-                    id,
-                    &ones[..vector_size as usize],
-                )
-                .to_words(&mut self.writer.logical_layout.declarations);
-                Ok(id)
+                Ok(self
+                    .writer
+                    .get_constant_composite(coordinates.type_id, &ones[..vector_size as usize]))
             }
         }
     }
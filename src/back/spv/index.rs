@@ -98,6 +98,10 @@ impl<'w> BlockContext<'w> {
             Ok(crate::proc::IndexableLength::Known(known_length)) => {
                 Ok(MaybeKnown::Known(known_length))
             }
+            Ok(crate::proc::IndexableLength::Specializable(constant)) => {
+                let length_id = self.writer.constant_ids[constant.index()];
+                Ok(MaybeKnown::Computed(length_id))
+            }
             Ok(crate::proc::IndexableLength::Dynamic) => {
                 let length_id = self.write_runtime_array_length(sequence, block)?;
                 Ok(MaybeKnown::Computed(length_id))
@@ -31,7 +31,7 @@ impl super::Instruction {
     pub(super) fn name(target_id: Word, name: &str) -> Self {
         let mut instruction = Self::new(Op::Name);
         instruction.add_operand(target_id);
-        instruction.add_operands(helpers::string_to_words(name));
+        instruction.add_operands(helpers::debug_name_to_words(name));
         instruction
     }
 
@@ -39,7 +39,7 @@ impl super::Instruction {
         let mut instruction = Self::new(Op::MemberName);
         instruction.add_operand(target_id);
         instruction.add_operand(member);
-        instruction.add_operands(helpers::string_to_words(name));
+        instruction.add_operands(helpers::debug_name_to_words(name));
         instruction
     }
 
@@ -343,6 +343,32 @@ impl super::Instruction {
         instruction
     }
 
+    pub(super) fn spec_constant_true(result_type_id: Word, id: Word) -> Self {
+        let mut instruction = Self::new(Op::SpecConstantTrue);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction
+    }
+
+    pub(super) fn spec_constant_false(result_type_id: Word, id: Word) -> Self {
+        let mut instruction = Self::new(Op::SpecConstantFalse);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+        instruction
+    }
+
+    pub(super) fn spec_constant(result_type_id: Word, id: Word, values: &[Word]) -> Self {
+        let mut instruction = Self::new(Op::SpecConstant);
+        instruction.set_type(result_type_id);
+        instruction.set_result(id);
+
+        for value in values {
+            instruction.add_operand(*value);
+        }
+
+        instruction
+    }
+
     pub(super) fn constant_composite(
         result_type_id: Word,
         id: Word,
@@ -871,11 +897,15 @@ impl super::Instruction {
         condition_id: Word,
         true_label: Word,
         false_label: Word,
+        weights: Option<[Word; 2]>,
     ) -> Self {
         let mut instruction = Self::new(Op::BranchConditional);
         instruction.add_operand(condition_id);
         instruction.add_operand(true_label);
         instruction.add_operand(false_label);
+        if let Some(weights) = weights {
+            instruction.add_operands(weights.to_vec());
+        }
         instruction
     }
 
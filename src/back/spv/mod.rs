@@ -582,6 +582,7 @@ pub struct Writer {
     annotations: Vec<Instruction>,
     flags: WriterFlags,
     bounds_check_policies: BoundsCheckPolicies,
+    source_language: (spirv::SourceLanguage, Word),
     void_type: Word,
     //TODO: convert most of these into vectors, addressable by handle indices
     lookup_type: crate::FastHashMap<LookupType, Word>,
@@ -589,6 +590,8 @@ pub struct Writer {
     lookup_function_type: crate::FastHashMap<LookupFunctionType, Word>,
     constant_ids: Vec<Word>,
     cached_constants: crate::FastHashMap<(crate::ScalarValue, crate::Bytes), Word>,
+    /// Unnamed composite constants, by their type and constituents.
+    cached_composite_constants: crate::FastHashMap<(Word, Vec<Word>), Word>,
     global_variables: Vec<GlobalVariable>,
     binding_map: BindingMap,
 
@@ -650,6 +653,12 @@ pub struct Options {
     /// How should generate code handle array, vector, matrix, or image texel
     /// indices that are out of range?
     pub bounds_check_policies: BoundsCheckPolicies,
+
+    /// The language and version the module was written in, recorded with
+    /// `OpSource` when [`WriterFlags::DEBUG`] is set.
+    ///
+    /// If this is `None`, the source language is `Unknown`.
+    pub source_language: Option<(spirv::SourceLanguage, u32)>,
 }
 
 impl Default for Options {
@@ -666,6 +675,7 @@ impl Default for Options {
             binding_map: BindingMap::default(),
             capabilities: None,
             bounds_check_policies: crate::proc::BoundsCheckPolicies::default(),
+            source_language: None,
         }
     }
 }
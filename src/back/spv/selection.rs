@@ -126,7 +126,7 @@ impl<'b, M: MergeTuple> Selection<'b, M> {
         let next_label = ctx.gen_id();
         ctx.function.consume(
             std::mem::replace(self.block, Block::new(next_label)),
-            Instruction::branch_conditional(cond, next_label, merge_label),
+            Instruction::branch_conditional(cond, next_label, merge_label, None),
         );
     }
 
@@ -65,12 +65,16 @@ impl Writer {
             annotations: vec![],
             flags: options.flags,
             bounds_check_policies: options.bounds_check_policies,
+            source_language: options
+                .source_language
+                .unwrap_or((spirv::SourceLanguage::Unknown, 0)),
             void_type,
             lookup_type: crate::FastHashMap::default(),
             lookup_function: crate::FastHashMap::default(),
             lookup_function_type: crate::FastHashMap::default(),
             constant_ids: Vec::new(),
             cached_constants: crate::FastHashMap::default(),
+            cached_composite_constants: crate::FastHashMap::default(),
             global_variables: Vec::new(),
             binding_map: options.binding_map.clone(),
             saved_cached: CachedExpressions::default(),
@@ -102,6 +106,7 @@ impl Writer {
             // Copied from the old Writer:
             flags: self.flags,
             bounds_check_policies: self.bounds_check_policies,
+            source_language: self.source_language,
             capabilities_available: take(&mut self.capabilities_available),
             binding_map: take(&mut self.binding_map),
 
@@ -122,6 +127,7 @@ impl Writer {
             lookup_function_type: take(&mut self.lookup_function_type).recycle(),
             constant_ids: take(&mut self.constant_ids).recycle(),
             cached_constants: take(&mut self.cached_constants).recycle(),
+            cached_composite_constants: take(&mut self.cached_composite_constants).recycle(),
             global_variables: take(&mut self.global_variables).recycle(),
             saved_cached: take(&mut self.saved_cached).recycle(),
             temp_list: take(&mut self.temp_list).recycle(),
@@ -636,6 +642,22 @@ impl Writer {
                         )?;
                     }
                 }
+                if let Some(depth_test) = entry_point.early_depth_test {
+                    self.write_execution_mode(
+                        function_id,
+                        spirv::ExecutionMode::EarlyFragmentTests,
+                    )?;
+                    if let Some(conservative) = depth_test.conservative {
+                        use crate::ConservativeDepth as Cd;
+
+                        let mode = match conservative {
+                            Cd::GreaterEqual => spirv::ExecutionMode::DepthGreater,
+                            Cd::LessEqual => spirv::ExecutionMode::DepthLess,
+                            Cd::Unchanged => spirv::ExecutionMode::DepthUnchanged,
+                        };
+                        self.write_execution_mode(function_id, mode)?;
+                    }
+                }
                 spirv::ExecutionModel::Fragment
             }
             crate::ShaderStage::Compute => {
@@ -688,8 +710,14 @@ impl Writer {
                 Instruction::type_int(id, bits, signedness)
             }
             Sk::Float => {
-                if bits == 64 {
-                    self.capabilities_used.insert(spirv::Capability::Float64);
+                match bits {
+                    16 => {
+                        self.capabilities_used.insert(spirv::Capability::Float16);
+                    }
+                    64 => {
+                        self.capabilities_used.insert(spirv::Capability::Float64);
+                    }
+                    _ => {}
                 }
                 Instruction::type_float(id, bits)
             }
@@ -697,6 +725,58 @@ impl Writer {
         }
     }
 
+    /// Request the capabilities needed to store the 16-bit floats of `ty` in
+    /// the storage class `class`, if any.
+    fn request_float16_storage_capabilities(
+        &mut self,
+        ir_module: &crate::Module,
+        ty: Handle<crate::Type>,
+        class: spirv::StorageClass,
+    ) -> Result<(), Error> {
+        fn contains_float16(types: &UniqueArena<crate::Type>, ty: Handle<crate::Type>) -> bool {
+            match types[ty].inner {
+                crate::TypeInner::Scalar { kind, width }
+                | crate::TypeInner::Vector { kind, width, .. } => {
+                    kind == crate::ScalarKind::Float && width == 2
+                }
+                crate::TypeInner::Matrix { width, .. } => width == 2,
+                crate::TypeInner::Array { base, .. }
+                | crate::TypeInner::BindingArray { base, .. } => contains_float16(types, base),
+                crate::TypeInner::Struct { ref members, .. } => members
+                    .iter()
+                    .any(|member| contains_float16(types, member.ty)),
+                _ => false,
+            }
+        }
+
+        let (what, capability) = match class {
+            spirv::StorageClass::Uniform => (
+                "16-bit floats in uniform buffers",
+                spirv::Capability::UniformAndStorageBuffer16BitAccess,
+            ),
+            spirv::StorageClass::StorageBuffer => (
+                "16-bit floats in storage buffers",
+                spirv::Capability::StorageBuffer16BitAccess,
+            ),
+            spirv::StorageClass::PushConstant => (
+                "16-bit floats in push constants",
+                spirv::Capability::StoragePushConstant16,
+            ),
+            spirv::StorageClass::Input | spirv::StorageClass::Output => (
+                "16-bit floats in shader inputs and outputs",
+                spirv::Capability::StorageInputOutput16,
+            ),
+            _ => return Ok(()),
+        };
+        if contains_float16(&ir_module.types, ty) {
+            self.require_any(what, &[capability])?;
+            if self.physical_layout.version < 0x10300 {
+                self.use_extension("SPV_KHR_16bit_storage");
+            }
+        }
+        Ok(())
+    }
+
     fn request_image_capabilities(&mut self, inner: &crate::TypeInner) -> Result<(), Error> {
         if let crate::TypeInner::Image {
             dim,
@@ -988,7 +1068,7 @@ impl Writer {
             return id;
         }
         let id = self.id_gen.next();
-        self.write_constant_scalar(id, &value, width, None);
+        self.write_constant_scalar(id, &value, width, None, None);
         self.cached_constants.insert((value, width), id);
         id
     }
@@ -999,6 +1079,7 @@ impl Writer {
         value: &crate::ScalarValue,
         width: crate::Bytes,
         debug_name: Option<&String>,
+        specialization: Option<u32>,
     ) {
         if self.flags.contains(WriterFlags::DEBUG) {
             if let Some(name) = debug_name {
@@ -1011,6 +1092,11 @@ impl Writer {
             width,
             pointer_space: None,
         }));
+        let constant = if specialization.is_some() {
+            Instruction::spec_constant
+        } else {
+            Instruction::constant
+        };
         let (solo, pair);
         let instruction = match *value {
             crate::ScalarValue::Sint(val) => {
@@ -1025,7 +1111,7 @@ impl Writer {
                     }
                     _ => unreachable!(),
                 };
-                Instruction::constant(type_id, id, words)
+                constant(type_id, id, words)
             }
             crate::ScalarValue::Uint(val) => {
                 let words = match width {
@@ -1039,7 +1125,7 @@ impl Writer {
                     }
                     _ => unreachable!(),
                 };
-                Instruction::constant(type_id, id, words)
+                constant(type_id, id, words)
             }
             crate::ScalarValue::Float(val) => {
                 let words = match width {
@@ -1054,31 +1140,43 @@ impl Writer {
                     }
                     _ => unreachable!(),
                 };
-                Instruction::constant(type_id, id, words)
+                constant(type_id, id, words)
+            }
+            crate::ScalarValue::Bool(true) if specialization.is_some() => {
+                Instruction::spec_constant_true(type_id, id)
+            }
+            crate::ScalarValue::Bool(false) if specialization.is_some() => {
+                Instruction::spec_constant_false(type_id, id)
             }
             crate::ScalarValue::Bool(true) => Instruction::constant_true(type_id, id),
             crate::ScalarValue::Bool(false) => Instruction::constant_false(type_id, id),
         };
 
         instruction.to_words(&mut self.logical_layout.declarations);
+        if let Some(spec_id) = specialization {
+            self.decorate(id, spirv::Decoration::SpecId, &[spec_id]);
+        }
     }
 
-    fn write_constant_composite(
+    /// Return the id of an unnamed composite constant, writing it only
+    /// the first time its type and constituents are requested.
+    pub(super) fn get_constant_composite(
         &mut self,
-        id: Word,
-        ty: Handle<crate::Type>,
-        components: &[Handle<crate::Constant>],
-    ) -> Result<(), Error> {
-        let mut constituent_ids = Vec::with_capacity(components.len());
-        for constituent in components.iter() {
-            let constituent_id = self.constant_ids[constituent.index()];
-            constituent_ids.push(constituent_id);
+        type_id: Word,
+        constituent_ids: &[Word],
+    ) -> Word {
+        if let Some(&id) = self
+            .cached_composite_constants
+            .get(&(type_id, constituent_ids.to_vec()))
+        {
+            return id;
         }
-
-        let type_id = self.get_type_id(LookupType::Handle(ty));
-        Instruction::constant_composite(type_id, id, constituent_ids.as_slice())
+        let id = self.id_gen.next();
+        Instruction::constant_composite(type_id, id, constituent_ids)
             .to_words(&mut self.logical_layout.declarations);
-        Ok(())
+        self.cached_composite_constants
+            .insert((type_id, constituent_ids.to_vec()), id);
+        id
     }
 
     pub(super) fn write_constant_null(&mut self, type_id: Word) -> Word {
@@ -1115,6 +1213,7 @@ impl Writer {
         ty: Handle<crate::Type>,
         binding: &crate::Binding,
     ) -> Result<Word, Error> {
+        self.request_float16_storage_capabilities(ir_module, ty, class)?;
         let id = self.id_gen.next();
         let pointer_type_id = self.get_pointer_id(&ir_module.types, ty, class)?;
         Instruction::variable(pointer_type_id, id, class, None)
@@ -1186,8 +1285,20 @@ impl Writer {
                     // vertex
                     Bi::BaseInstance => BuiltIn::BaseInstance,
                     Bi::BaseVertex => BuiltIn::BaseVertex,
-                    Bi::ClipDistance => BuiltIn::ClipDistance,
-                    Bi::CullDistance => BuiltIn::CullDistance,
+                    Bi::ClipDistance => {
+                        self.require_any(
+                            "`clip_distance` built-in",
+                            &[spirv::Capability::ClipDistance],
+                        )?;
+                        BuiltIn::ClipDistance
+                    }
+                    Bi::CullDistance => {
+                        self.require_any(
+                            "`cull_distance` built-in",
+                            &[spirv::Capability::CullDistance],
+                        )?;
+                        BuiltIn::CullDistance
+                    }
                     Bi::InstanceIndex => BuiltIn::InstanceIndex,
                     Bi::PointSize => BuiltIn::PointSize,
                     Bi::VertexIndex => BuiltIn::VertexIndex,
@@ -1237,6 +1348,7 @@ impl Writer {
         let class = map_storage_class(global_variable.space);
 
         //self.check(class.required_capabilities())?;
+        self.request_float16_storage_capabilities(ir_module, global_variable.ty, class)?;
 
         if self.flags.contains(WriterFlags::DEBUG) {
             if let Some(ref name) = global_variable.name {
@@ -1471,8 +1583,9 @@ impl Writer {
             .to_words(&mut self.logical_layout.ext_inst_imports);
 
         if self.flags.contains(WriterFlags::DEBUG) {
+            let (source_language, version) = self.source_language;
             self.debugs
-                .push(Instruction::source(spirv::SourceLanguage::GLSL, 450));
+                .push(Instruction::source(source_language, version));
         }
 
         self.constant_ids.resize(ir_module.constants.len(), 0);
@@ -1481,14 +1594,22 @@ impl Writer {
             match constant.inner {
                 crate::ConstantInner::Composite { .. } => continue,
                 crate::ConstantInner::Scalar { width, ref value } => {
-                    self.constant_ids[handle.index()] = match constant.name {
-                        Some(ref name) => {
-                            let id = self.id_gen.next();
-                            self.write_constant_scalar(id, value, width, Some(name));
-                            id
-                        }
-                        None => self.get_constant_scalar(*value, width),
-                    };
+                    // Named and specialized constants keep their own id.
+                    self.constant_ids[handle.index()] =
+                        match (&constant.name, constant.specialization) {
+                            (&None, None) => self.get_constant_scalar(*value, width),
+                            (name, specialization) => {
+                                let id = self.id_gen.next();
+                                self.write_constant_scalar(
+                                    id,
+                                    value,
+                                    width,
+                                    name.as_ref(),
+                                    specialization,
+                                );
+                                id
+                            }
+                        };
                 }
             }
         }
@@ -1503,14 +1624,23 @@ impl Writer {
             match constant.inner {
                 crate::ConstantInner::Scalar { .. } => continue,
                 crate::ConstantInner::Composite { ty, ref components } => {
-                    let id = self.id_gen.next();
-                    self.constant_ids[handle.index()] = id;
-                    if self.flags.contains(WriterFlags::DEBUG) {
-                        if let Some(ref name) = constant.name {
-                            self.debugs.push(Instruction::name(id, name));
+                    let type_id = self.get_type_id(LookupType::Handle(ty));
+                    let constituent_ids: Vec<_> = components
+                        .iter()
+                        .map(|constituent| self.constant_ids[constituent.index()])
+                        .collect();
+                    self.constant_ids[handle.index()] = match constant.name {
+                        Some(ref name) => {
+                            let id = self.id_gen.next();
+                            if self.flags.contains(WriterFlags::DEBUG) {
+                                self.debugs.push(Instruction::name(id, name));
+                            }
+                            Instruction::constant_composite(type_id, id, &constituent_ids)
+                                .to_words(&mut self.logical_layout.declarations);
+                            id
                         }
-                    }
-                    self.write_constant_composite(id, ty, components)?;
+                        None => self.get_constant_composite(type_id, &constituent_ids),
+                    };
                 }
             }
         }
@@ -1636,3 +1766,478 @@ fn test_write_physical_layout() {
     writer.write_physical_layout();
     assert_eq!(writer.physical_layout.bound, 3);
 }
+
+/// Validate and write `module` with `options`, and return the opcode and
+/// operands of each instruction after the header.
+#[cfg(test)]
+fn write_instructions(module: &crate::Module, options: &Options) -> Vec<(spirv::Op, Vec<Word>)> {
+    use num_traits::FromPrimitive;
+
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(module)
+    .unwrap();
+    let mut words = vec![];
+    Writer::new(options)
+        .unwrap()
+        .write(module, &info, None, &mut words)
+        .unwrap();
+
+    let mut instructions = Vec::new();
+    let mut index = 5;
+    while index < words.len() {
+        let count = (words[index] >> 16) as usize;
+        let op = spirv::Op::from_u32(words[index] & 0xffff).unwrap();
+        instructions.push((op, words[index + 1..index + count].to_vec()));
+        index += count;
+    }
+    instructions
+}
+
+#[test]
+fn test_write_source_language() {
+    let module = crate::Module::default();
+    let source_words = |source_language| {
+        let options = Options {
+            flags: WriterFlags::DEBUG,
+            source_language,
+            ..Default::default()
+        };
+        let (_, operands) = write_instructions(&module, &options)
+            .into_iter()
+            .find(|&(op, _)| op == spirv::Op::Source)
+            .unwrap();
+        [operands[0], operands[1]]
+    };
+
+    assert_eq!(
+        source_words(None),
+        [spirv::SourceLanguage::Unknown as u32, 0]
+    );
+    assert_eq!(
+        source_words(Some((spirv::SourceLanguage::ESSL, 310))),
+        [spirv::SourceLanguage::ESSL as u32, 310]
+    );
+}
+
+#[test]
+fn test_write_composite_constants_once() {
+    let mut module = crate::Module::default();
+    let int = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Sint,
+                width: 4,
+            },
+        },
+        Default::default(),
+    );
+    let mut scalar = |value| {
+        module.constants.append(
+            crate::Constant {
+                name: None,
+                specialization: None,
+                inner: crate::ConstantInner::Scalar {
+                    width: 4,
+                    value: crate::ScalarValue::Sint(value),
+                },
+            },
+            Default::default(),
+        )
+    };
+    let components = vec![scalar(1), scalar(2), scalar(1)];
+    let length = scalar(3);
+    let array = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Array {
+                base: int,
+                size: crate::ArraySize::Constant(length),
+                stride: 4,
+            },
+        },
+        Default::default(),
+    );
+    for name in [None, None, None, Some("named".to_string())].iter() {
+        module.constants.append(
+            crate::Constant {
+                name: name.clone(),
+                specialization: None,
+                inner: crate::ConstantInner::Composite {
+                    ty: array,
+                    components: components.clone(),
+                },
+            },
+            Default::default(),
+        );
+    }
+    let composites = write_instructions(&module, &Options::default())
+        .into_iter()
+        .filter(|&(op, _)| op == spirv::Op::ConstantComposite)
+        .count();
+    // The named constant keeps its own id.
+    assert_eq!(composites, 2);
+}
+
+#[test]
+fn test_write_image_type_operands() {
+    let mut module = crate::Module::default();
+    let classes = [
+        crate::ImageClass::Sampled {
+            kind: crate::ScalarKind::Float,
+            multi: false,
+        },
+        crate::ImageClass::Depth { multi: false },
+        crate::ImageClass::Storage {
+            format: crate::StorageFormat::Rgba8Unorm,
+            access: crate::StorageAccess::STORE,
+        },
+    ];
+    for &class in classes.iter() {
+        module.types.insert(
+            crate::Type {
+                name: None,
+                inner: crate::TypeInner::Image {
+                    dim: crate::ImageDimension::D2,
+                    arrayed: false,
+                    class,
+                },
+            },
+            Default::default(),
+        );
+    }
+    let images: Vec<_> = write_instructions(&module, &Options::default())
+        .into_iter()
+        .filter(|&(op, _)| op == spirv::Op::TypeImage)
+        // Skip the result and sampled type ids, and the dimension.
+        .map(|(_, operands)| operands[3..8].to_vec())
+        .collect();
+
+    // The depth, arrayed, multisampled, sampled and format operands.
+    let unknown = spirv::ImageFormat::Unknown as u32;
+    let rgba8 = spirv::ImageFormat::Rgba8 as u32;
+    assert_eq!(
+        images,
+        vec![
+            vec![0, 0, 0, 1, unknown],
+            vec![1, 0, 0, 1, unknown],
+            vec![0, 0, 0, 2, rgba8],
+        ]
+    );
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_select_scalar_condition() {
+    let module = crate::front::wgsl::parse_str(
+        "
+        fn choose(condition: bool, accept: vec3<f32>, reject: vec3<f32>) -> vec3<f32> {
+            return select(reject, accept, condition);
+        }
+        ",
+    )
+    .unwrap();
+
+    // Return the number of composites constructed in the module.
+    let constructs = |lang_version| {
+        let options = Options {
+            lang_version,
+            ..Options::default()
+        };
+        write_instructions(&module, &options)
+            .into_iter()
+            .filter(|&(op, _)| op == spirv::Op::CompositeConstruct)
+            .count()
+    };
+
+    // The condition is splatted to a vector before SPIR-V 1.4 only.
+    assert_eq!(constructs((1, 0)), 1);
+    assert_eq!(constructs((1, 4)), 0);
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_image_store_array() {
+    let module = crate::front::wgsl::parse_str(
+        "
+        @group(0) @binding(0)
+        var image: texture_storage_2d_array<rgba8uint, write>;
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(local_invocation_id) id: vec3<u32>) {
+            textureStore(image, vec2<i32>(id.xy), i32(id.z), vec4<u32>(id, 1u));
+        }
+        ",
+    )
+    .unwrap();
+
+    let instructions = write_instructions(&module, &Options::default());
+    let find = |op: spirv::Op, id_index: usize, id: Word| {
+        instructions
+            .iter()
+            .find(|&&(inst_op, ref operands)| inst_op == op && operands[id_index] == id)
+            .map(|(_, operands)| operands)
+            .unwrap()
+    };
+    // Return the scalar type and the size of the vector type `id`.
+    let vector = |id| {
+        let vector = find(spirv::Op::TypeVector, 0, id);
+        (
+            find(spirv::Op::TypeInt, 0, vector[1])[1..].to_vec(),
+            vector[2],
+        )
+    };
+
+    let (_, write) = instructions
+        .iter()
+        .find(|&&(op, _)| op == spirv::Op::ImageWrite)
+        .unwrap();
+    // The array index is folded into a signed `vec3` coordinate.
+    let coordinate = find(spirv::Op::CompositeConstruct, 1, write[1]);
+    assert_eq!(vector(coordinate[0]), (vec![32, 1], 3));
+    // The texel has the unsigned component type of the format.
+    let texel = find(spirv::Op::CompositeConstruct, 1, write[2]);
+    assert_eq!(vector(texel[0]), (vec![32, 0], 4));
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_debug_names() {
+    let mut module = crate::front::wgsl::parse_str(
+        "
+        struct Light {
+            color: vec3<f32>,
+            intensity: f32,
+        }
+
+        var<private> light: Light;
+
+        fn brightness(scale: f32) -> f32 {
+            return light.intensity * scale;
+        }
+
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(light.color * brightness(2.0), 1.0);
+        }
+        ",
+    )
+    .unwrap();
+    // Names can come from sources that allow any character.
+    module.global_variables.iter_mut().next().unwrap().1.name = Some("li\0ght".to_string());
+
+    let names = |flags| {
+        let options = Options {
+            flags,
+            ..Default::default()
+        };
+        let mut names = Vec::new();
+        for (op, operands) in write_instructions(&module, &options) {
+            let string = match op {
+                spirv::Op::Name => &operands[1..],
+                spirv::Op::MemberName => &operands[2..],
+                _ => continue,
+            };
+            let bytes: Vec<u8> = string.iter().flat_map(|word| word.to_le_bytes()).collect();
+            let end = bytes.iter().position(|&byte| byte == 0).unwrap();
+            names.push(String::from_utf8(bytes[..end].to_vec()).unwrap());
+        }
+        names
+    };
+
+    let debug = names(WriterFlags::DEBUG);
+    for name in [
+        "Light",
+        "color",
+        "intensity",
+        "light",
+        "brightness",
+        "scale",
+        "main",
+    ] {
+        assert!(
+            debug.iter().any(|n| n == name),
+            "Missing {:?} in {:?}",
+            name,
+            debug
+        );
+    }
+    assert!(names(WriterFlags::empty()).is_empty());
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_branch_weights() {
+    let mut module = crate::front::wgsl::parse_str(
+        "
+        @fragment
+        fn main(@location(0) value: f32) -> @location(0) f32 {
+            var x = value;
+            if value > 0.5 {
+                x = 1.0;
+            }
+            if value < 0.1 {
+                x = 0.0;
+            }
+            return x;
+        }
+        ",
+    )
+    .unwrap();
+
+    let mut write = |hint| {
+        for statement in module.entry_points[0].function.body.iter_mut() {
+            if let crate::Statement::If {
+                hint: ref mut h, ..
+            } = *statement
+            {
+                *h = hint;
+                break;
+            }
+        }
+        write_instructions(&module, &Options::default())
+            .into_iter()
+            .filter(|&(op, _)| op == spirv::Op::BranchConditional)
+            // Skip the condition and the two labels.
+            .map(|(_, operands)| operands[3..].to_vec())
+            .collect::<Vec<_>>()
+    };
+
+    // Without hints, no weights are emitted.
+    assert_eq!(write(None), [vec![], vec![]]);
+    assert_eq!(
+        write(Some(crate::BranchHint::Likely)),
+        [vec![100, 1], vec![]]
+    );
+    assert_eq!(
+        write(Some(crate::BranchHint::Unlikely)),
+        [vec![1, 100], vec![]]
+    );
+}
+
+#[test]
+fn test_write_float16_storage() {
+    let mut module = crate::Module::default();
+    let half = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Float,
+                width: 2,
+            },
+        },
+        Default::default(),
+    );
+    let data = module.types.insert(
+        crate::Type {
+            name: Some("Data".to_string()),
+            inner: crate::TypeInner::Struct {
+                members: vec![crate::StructMember {
+                    name: Some("value".to_string()),
+                    ty: half,
+                    binding: None,
+                    offset: 0,
+                }],
+                span: 2,
+            },
+        },
+        Default::default(),
+    );
+    module.global_variables.append(
+        crate::GlobalVariable {
+            name: Some("data".to_string()),
+            space: crate::AddressSpace::Storage {
+                access: crate::StorageAccess::LOAD,
+            },
+            binding: Some(crate::ResourceBinding {
+                group: 0,
+                binding: 0,
+            }),
+            ty: data,
+            init: None,
+        },
+        Default::default(),
+    );
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::empty(),
+        crate::valid::Capabilities::FLOAT16,
+    )
+    .validate(&module)
+    .unwrap();
+
+    let mut writer = Writer::new(&Options::default()).unwrap();
+    writer.write(&module, &info, None, &mut vec![]).unwrap();
+    assert!(writer
+        .get_capabilities_used()
+        .contains(&spirv::Capability::Float16));
+    assert!(writer
+        .get_capabilities_used()
+        .contains(&spirv::Capability::StorageBuffer16BitAccess));
+    assert!(writer.extensions_used.contains("SPV_KHR_16bit_storage"));
+
+    // Without the storage capability, the buffer can't be written.
+    let options = Options {
+        capabilities: Some(
+            [spirv::Capability::Shader, spirv::Capability::Float16]
+                .iter()
+                .cloned()
+                .collect(),
+        ),
+        ..Options::default()
+    };
+    let error = Writer::new(&options)
+        .unwrap()
+        .write(&module, &info, None, &mut vec![])
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::MissingCapabilities(_, ref capabilities)
+            if capabilities[..] == [spirv::Capability::StorageBuffer16BitAccess]
+    ));
+}
+
+#[cfg(feature = "wgsl-in")]
+#[test]
+fn test_write_specialized_array_length() {
+    let module = crate::front::wgsl::parse_str(
+        "
+        @id(3) override SIZE: u32 = 64u;
+        var<workgroup> data: array<f32, SIZE>;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            data[index] = f32(index);
+        }
+        ",
+    )
+    .unwrap();
+    let options = Options {
+        bounds_check_policies: crate::proc::BoundsCheckPolicies {
+            index: crate::proc::BoundsCheckPolicy::Restrict,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let instructions = write_instructions(&module, &options);
+    let find = |op: spirv::Op| {
+        instructions
+            .iter()
+            .find(|&&(inst_op, _)| inst_op == op)
+            .map(|(_, operands)| operands)
+            .unwrap()
+    };
+
+    // The result type and id, and the default value.
+    let constant = find(spirv::Op::SpecConstant);
+    let id = constant[1];
+    assert_eq!(constant[2], 64);
+    assert!(instructions.iter().any(|&(op, ref operands)| {
+        op == spirv::Op::Decorate && operands[..] == [id, spirv::Decoration::SpecId as u32, 3]
+    }));
+    // The array and its bounds check use the specialized length.
+    assert_eq!(find(spirv::Op::TypeArray)[2], id);
+    assert_eq!(find(spirv::Op::ISub)[2], id);
+}
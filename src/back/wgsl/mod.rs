@@ -18,6 +18,8 @@ pub enum Error {
     Custom(String),
     #[error("{0}")]
     Unimplemented(String), // TODO: Error used only during development
+    #[error("A scalar with an unsupported width was requested: {0:?} {1:?}")]
+    UnsupportedScalar(crate::ScalarKind, crate::Bytes),
     #[error("Unsupported math function: {0:?}")]
     UnsupportedMathFunction(crate::MathFunction),
     #[error("Unsupported relational function: {0:?}")]
@@ -50,3 +52,44 @@ impl crate::AtomicFunction {
         }
     }
 }
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_write_entry_point_attributes() {
+    let write = |source: &str| {
+        let module = crate::front::wgsl::parse_str(source).unwrap();
+        let info = crate::valid::Validator::new(
+            crate::valid::ValidationFlags::all(),
+            crate::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .unwrap();
+        write_string(&module, &info, WriterFlags::empty()).unwrap()
+    };
+
+    let output = write(
+        "
+        @compute @workgroup_size(8, 8, 1)
+        fn cs_main() {}
+
+        @vertex
+        fn vs_main() -> @builtin(position) vec4<f32> {
+            return vec4<f32>(0.0);
+        }
+
+        @fragment
+        fn fs_main() {}
+        ",
+    );
+    let attributes: Vec<_> = output
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| line.starts_with('@'))
+        .collect();
+    assert_eq!(
+        attributes,
+        ["@compute @workgroup_size(8, 8, 1)", "@vertex", "@fragment"]
+    );
+    // The output reads back into the same module.
+    assert_eq!(write(&output), output);
+}
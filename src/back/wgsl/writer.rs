@@ -96,6 +96,13 @@ impl<W: Write> Writer<W> {
     pub fn write(&mut self, module: &Module, info: &valid::ModuleInfo) -> BackendResult {
         self.reset(module);
 
+        if back::uses_float16(module) {
+            return Err(Error::UnsupportedScalar(crate::ScalarKind::Float, 2));
+        }
+        if back::uses_specialized_array_lengths(module) {
+            return Err(Error::Unimplemented("specialized array length".to_string()));
+        }
+
         // Save all ep result types
         for (_, ep) in module.entry_points.iter().enumerate() {
             if let Some(ref result) = ep.function.result {
@@ -103,14 +110,17 @@ impl<W: Write> Writer<W> {
             }
         }
 
+        // Struct member offsets are implied by the member types, so compute
+        // the default layouts to know where `@size` is needed.
+        let mut layouter = proc::Layouter::default();
+        layouter
+            .update(&module.types, &module.constants)
+            .map_err(|e| Error::Custom(e.to_string()))?;
+
         // Write all structs
         for (handle, ty) in module.types.iter() {
-            if let TypeInner::Struct {
-                ref members,
-                span: _,
-            } = ty.inner
-            {
-                self.write_struct(module, handle, members)?;
+            if let TypeInner::Struct { ref members, span } = ty.inner {
+                self.write_struct(module, handle, members, span, &layouter)?;
                 writeln!(self.out)?;
             }
         }
@@ -395,11 +405,18 @@ impl<W: Write> Writer<W> {
         module: &Module,
         handle: Handle<crate::Type>,
         members: &[crate::StructMember],
+        span: u32,
+        layouter: &proc::Layouter,
     ) -> BackendResult {
         write!(self.out, "struct ")?;
         self.write_struct_name(module, handle)?;
         write!(self.out, " {{")?;
         writeln!(self.out)?;
+        let struct_alignment = (0..members.len())
+            .filter_map(|index| explicit_alignment(members, index, span, layouter))
+            .fold(layouter[handle].alignment, |max, alignment| {
+                max.max(alignment)
+            });
         for (index, member) in members.iter().enumerate() {
             // Skip struct member with unsupported built in
             if let Some(crate::Binding::BuiltIn(built_in)) = member.binding {
@@ -417,6 +434,29 @@ impl<W: Write> Writer<W> {
                     module.types[member.ty].inner.scalar_kind(),
                 ))?;
             }
+            // Align the member with `@align` if its offset is a multiple of a
+            // larger alignment than the one of its type, and extend it with
+            // `@size` if the next member, or the end of the struct, is further
+            // away than that. Interface structs aren't laid out in memory, so
+            // skip them.
+            if member.binding.is_none() {
+                if let Some(alignment) = explicit_alignment(members, index, span, layouter) {
+                    write!(self.out, "@align({}) ", alignment)?;
+                }
+                let (next_offset, next_alignment) = match members.get(index + 1) {
+                    Some(next) => (
+                        next.offset,
+                        explicit_alignment(members, index + 1, span, layouter)
+                            .unwrap_or(layouter[next.ty].alignment),
+                    ),
+                    None => (span, struct_alignment),
+                };
+                let size = layouter[member.ty].size;
+                let default_end = proc::Layouter::round_up(next_alignment, member.offset + size);
+                if next_offset > default_end {
+                    write!(self.out, "@size({}) ", next_offset - member.offset)?;
+                }
+            }
             // Write struct member name and type
             let member_name = &self.names[&NameKey::StructMember(handle, index as u32)];
             write!(self.out, "{}: ", member_name)?;
@@ -711,6 +751,7 @@ impl<W: Write> Writer<W> {
                 condition,
                 ref accept,
                 ref reject,
+                ..
             } => {
                 write!(self.out, "{}", level)?;
                 write!(self.out, "if ")?;
@@ -1131,9 +1172,17 @@ impl<W: Write> Writer<W> {
                 // All of them are not supported by WGSL.
 
                 // We need to copy components to another vec because we don't know which of them we should write.
+                let members = match module.types[ty].inner {
+                    TypeInner::Struct { ref members, .. } => Some(members),
+                    _ => None,
+                };
                 let mut components_to_write = Vec::with_capacity(components.len());
-                for component in components {
-                    let mut skip_component = false;
+                for (index, component) in components.iter().enumerate() {
+                    // The struct itself skips members with unsupported builtins.
+                    let mut skip_component = matches!(
+                        members.and_then(|members| members.get(index)?.binding.as_ref()),
+                        Some(&crate::Binding::BuiltIn(built_in)) if builtin_str(built_in).is_none()
+                    );
                     if let Expression::Load { pointer } = func_ctx.expressions[*component] {
                         if let Expression::AccessIndex { base, index } =
                             func_ctx.expressions[pointer]
@@ -1257,14 +1306,32 @@ impl<W: Write> Writer<W> {
                     self.write_expr(module, depth_ref, func_ctx)?;
                 }
 
+                // Depth textures take an integer level, unlike the IR.
+                let depth_image = match *func_ctx.info[image].ty.inner_with(&module.types) {
+                    TypeInner::Image {
+                        class: crate::ImageClass::Depth { .. },
+                        ..
+                    } => true,
+                    _ => false,
+                };
+
                 match level {
                     Sl::Auto => {}
                     Sl::Zero => {
                         // Level 0 is implied for depth comparison
                         if depth_ref.is_none() {
-                            write!(self.out, ", 0.0")?;
+                            if depth_image {
+                                write!(self.out, ", 0")?;
+                            } else {
+                                write!(self.out, ", 0.0")?;
+                            }
                         }
                     }
+                    Sl::Exact(expr) if depth_image => {
+                        write!(self.out, ", i32(")?;
+                        self.write_expr(module, expr, func_ctx)?;
+                        write!(self.out, ")")?;
+                    }
                     Sl::Exact(expr) => {
                         write!(self.out, ", ")?;
                         self.write_expr(module, expr, func_ctx)?;
@@ -1794,7 +1861,7 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
-    /// Helper method used to write global constants
+    /// Helper method used to write global constants as `const` declarations
     ///
     /// # Notes
     /// Ends in a newline
@@ -1811,7 +1878,7 @@ impl<W: Write> Writer<W> {
             } => {
                 let name = &self.names[&NameKey::Constant(handle)];
                 // First write only constant name
-                write!(self.out, "let {}: ", name)?;
+                write!(self.out, "const {}: ", name)?;
                 // Next write constant type and value
                 match *value {
                     crate::ScalarValue::Sint(value) => {
@@ -1835,7 +1902,7 @@ impl<W: Write> Writer<W> {
             crate::ConstantInner::Composite { ty, ref components } => {
                 let name = &self.names[&NameKey::Constant(handle)];
                 // First write only constant name
-                write!(self.out, "let {}: ", name)?;
+                write!(self.out, "const {}: ", name)?;
                 // Next write constant type
                 self.write_type(module, ty)?;
 
@@ -1995,6 +2062,36 @@ const fn address_space_str(
     )
 }
 
+/// Return the alignment placing the member `index` of a struct of size
+/// `span` at its offset right after the previous member, if the alignment of
+/// its type doesn't.
+fn explicit_alignment(
+    members: &[crate::StructMember],
+    index: usize,
+    span: u32,
+    layouter: &proc::Layouter,
+) -> Option<proc::Alignment> {
+    let member = &members[index];
+    let mut alignment = layouter[member.ty].alignment;
+    if member.offset % alignment.get() != 0 {
+        // The member is less aligned than its type.
+        return proc::Alignment::new(1 << member.offset.trailing_zeros());
+    }
+    let previous = &members[index.checked_sub(1)?];
+    let end = previous.offset + layouter[previous.ty].size;
+    if member.offset <= proc::Layouter::round_up(alignment, end) {
+        return None;
+    }
+    // The alignments of the members also align the struct.
+    while alignment.get() < member.offset && span % (alignment.get() * 2) == 0 {
+        alignment = proc::Alignment::new(alignment.get() * 2)?;
+        if proc::Layouter::round_up(alignment, end) == member.offset {
+            return Some(alignment);
+        }
+    }
+    None
+}
+
 fn map_binding_to_attribute(
     binding: &crate::Binding,
     scalar_kind: Option<crate::ScalarKind>,
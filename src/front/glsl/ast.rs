@@ -381,4 +381,6 @@ impl ParameterQualifier {
 pub enum Profile {
     /// The `core` profile, default when no profile is specified.
     Core,
+    /// The `es` profile, used by OpenGL ES shaders.
+    Es,
 }
@@ -306,6 +306,41 @@ pub fn inject_builtin(
                 f,
             )
         }
+        "textureQueryLevels" | "textureSamples" => {
+            let samples = "textureSamples" == name;
+            let f = |kind, dim, arrayed, multi, shadow| {
+                // Multisampled images have a single level and only they have
+                // more than one sample
+                if multi != samples {
+                    return;
+                }
+
+                let class = match shadow {
+                    true => ImageClass::Depth { multi },
+                    false => ImageClass::Sampled { kind, multi },
+                };
+
+                let image = TypeInner::Image {
+                    dim,
+                    arrayed,
+                    class,
+                };
+
+                let query = match samples {
+                    true => ImageQuery::NumSamples,
+                    false => ImageQuery::NumLevels,
+                };
+
+                declaration
+                    .overloads
+                    .push(module.add_builtin(vec![image], MacroCall::ImageQuery(query)))
+            };
+
+            texture_args_generator(
+                TextureArgsOptions::SHADOW | TextureArgsOptions::MULTI | variations.into(),
+                f,
+            )
+        }
         "texelFetch" | "texelFetchOffset" => {
             let offset = "texelFetchOffset" == name;
             let f = |kind, dim, arrayed, multi, _shadow| {
@@ -1590,6 +1625,7 @@ pub enum MacroCall {
         multi: bool,
     },
     ImageStore,
+    ImageQuery(ImageQuery),
     MathFunction(MathFunction),
     FindLsbUint,
     FindMsbUint,
@@ -1814,6 +1850,14 @@ impl MacroCall {
 
                 expr
             }
+            MacroCall::ImageQuery(query) => ctx.add_expression(
+                Expression::ImageQuery {
+                    image: args[0],
+                    query,
+                },
+                Span::default(),
+                body,
+            ),
             MacroCall::ImageLoad { multi } => {
                 let comps =
                     parser.coordinate_components(ctx, args[0], args[1], None, meta, body)?;
@@ -2228,6 +2272,19 @@ impl Parser {
     }
 }
 
+/// Returns the name of the builtin `name` if it exists in glsl but can't be
+/// mapped to naga yet, so calls to it can be reported as such instead of as
+/// calls to an unknown function.
+pub fn unsupported_builtin(name: &str) -> Option<&'static str> {
+    const UNSUPPORTED: &[&str] = &[
+        "textureGather",
+        "textureGatherOffset",
+        "textureGatherOffsets",
+        "textureQueryLod",
+    ];
+    UNSUPPORTED.iter().copied().find(|&builtin| builtin == name)
+}
+
 /// Helper function to cast a expression holding a sampled image to a
 /// depth image.
 pub fn sampled_to_depth(
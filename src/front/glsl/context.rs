@@ -11,7 +11,7 @@ use crate::{
     front::{Emitter, Typifier},
     AddressSpace, Arena, BinaryOperator, Block, Constant, Expression, FastHashMap,
     FunctionArgument, Handle, LocalVariable, RelationalFunction, ScalarKind, ScalarValue, Span,
-    Statement, Type, TypeInner, VectorSize,
+    Statement, Type, TypeInner, UnaryOperator, VectorSize,
 };
 use std::{convert::TryFrom, ops::Index};
 
@@ -1033,7 +1033,35 @@ impl Context {
                     .lower_expect_inner(stmt, parser, expr, ExprPos::Rhs, body)?
                     .0;
 
-                self.add_expression(Expression::Unary { op, expr }, meta, body)
+                let kind = self
+                    .expr_scalar_components(parser, expr, meta)?
+                    .map(|(kind, _)| kind);
+                if op == UnaryOperator::Negate && kind == Some(ScalarKind::Uint) {
+                    // The IR only negates signed values, so negate the bits as a
+                    // signed integer, which wraps around like GLSL does.
+                    let signed = self.add_expression(
+                        Expression::As {
+                            expr,
+                            kind: ScalarKind::Sint,
+                            convert: None,
+                        },
+                        meta,
+                        body,
+                    );
+                    let negated =
+                        self.add_expression(Expression::Unary { op, expr: signed }, meta, body);
+                    self.add_expression(
+                        Expression::As {
+                            expr: negated,
+                            kind: ScalarKind::Uint,
+                            convert: None,
+                        },
+                        meta,
+                        body,
+                    )
+                } else {
+                    self.add_expression(Expression::Unary { op, expr }, meta, body)
+                }
             }
             HirExprKind::Variable(ref var) => match pos {
                 ExprPos::Lhs => {
@@ -1220,6 +1248,7 @@ impl Context {
                         condition,
                         accept: accept_body,
                         reject: reject_body,
+                        hint: None,
                     },
                     meta,
                 );
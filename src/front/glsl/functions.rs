@@ -1,6 +1,6 @@
 use super::{
     ast::*,
-    builtins::{inject_builtin, sampled_to_depth},
+    builtins::{inject_builtin, sampled_to_depth, unsupported_builtin},
     context::{Context, ExprPos, StmtContext},
     error::{Error, ErrorKind},
     types::scalar_components,
@@ -829,7 +829,10 @@ impl Parser {
         }
 
         let overload = maybe_overload.ok_or_else(|| Error {
-            kind: ErrorKind::SemanticError(format!("Unknown function '{}'", name).into()),
+            kind: match unsupported_builtin(&name) {
+                Some(name) => ErrorKind::NotImplemented(name),
+                None => ErrorKind::SemanticError(format!("Unknown function '{}'", name).into()),
+            },
             meta,
         })?;
 
@@ -1402,6 +1405,17 @@ impl Parser {
             )
         }
 
+        if self.meta.stage == crate::ShaderStage::Vertex
+            && !members.iter().any(|member| {
+                matches!(
+                    member.binding,
+                    Some(crate::Binding::BuiltIn(crate::BuiltIn::Position { .. }))
+                )
+            })
+        {
+            log::warn!("Vertex shader doesn't write to gl_Position");
+        }
+
         let (ty, value) = if !components.is_empty() {
             let ty = self.module.types.insert(
                 Type {
@@ -89,6 +89,11 @@ pub struct ShaderMetadata {
     /// Whether or not early fragment tests where requested by the shader.
     /// Defaults to `false`.
     pub early_fragment_tests: bool,
+    /// The default precisions of `float` and `int` declarations, set with
+    /// `precision` statements. Defaults to `None` for both.
+    ///
+    /// The IR has no notion of precision, so these are only recorded.
+    pub default_precisions: DefaultPrecisions,
 
     /// The shader can request extensions via the
     /// `#extension` preprocessor directive, in the directive a behavior
@@ -100,6 +105,15 @@ pub struct ShaderMetadata {
     pub extensions: FastHashSet<String>,
 }
 
+/// The default precisions set by `precision` statements.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DefaultPrecisions {
+    /// The precision of `float` based declarations.
+    pub float: Option<Precision>,
+    /// The precision of `int` based declarations.
+    pub int: Option<Precision>,
+}
+
 impl ShaderMetadata {
     fn reset(&mut self, stage: ShaderStage) {
         self.version = 0;
@@ -107,6 +121,7 @@ impl ShaderMetadata {
         self.stage = stage;
         self.workgroup_size = [if stage == ShaderStage::Compute { 1 } else { 0 }; 3];
         self.early_fragment_tests = false;
+        self.default_precisions = DefaultPrecisions::default();
         self.extensions.clear();
     }
 }
@@ -119,6 +134,7 @@ impl Default for ShaderMetadata {
             stage: ShaderStage::Vertex,
             workgroup_size: [0; 3],
             early_fragment_tests: false,
+            default_precisions: DefaultPrecisions::default(),
             extensions: FastHashSet::default(),
         }
     }
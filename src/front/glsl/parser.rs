@@ -256,7 +256,7 @@ impl Parser {
                         value: PPTokenValue::Integer(int),
                         location,
                     }) => match int.value {
-                        440 | 450 | 460 => self.meta.version = int.value as u16,
+                        300 | 310 | 320 | 440 | 450 | 460 => self.meta.version = int.value as u16,
                         _ => self.errors.push(Error {
                             kind: ErrorKind::InvalidVersion(int.value),
                             meta: location.into(),
@@ -280,6 +280,7 @@ impl Parser {
                         location,
                     }) => match name.as_str() {
                         "core" => self.meta.profile = Profile::Core,
+                        "es" => self.meta.profile = Profile::Es,
                         _ => self.errors.push(Error {
                             kind: ErrorKind::InvalidProfile(name),
                             meta: location.into(),
@@ -302,6 +303,15 @@ impl Parser {
                         meta: location.into(),
                     })
                 }
+
+                // The ES versions don't overlap with the desktop ones.
+                let is_es_version = matches!(self.meta.version, 300 | 310 | 320);
+                if self.meta.version != 0 && is_es_version != (self.meta.profile == Profile::Es) {
+                    self.errors.push(Error {
+                        kind: ErrorKind::InvalidVersion(self.meta.version.into()),
+                        meta,
+                    })
+                }
             }
             DirectiveKind::Extension => {
                 // TODO: Proper extension handling
@@ -1,7 +1,7 @@
 use crate::{
     front::glsl::{
         ast::{
-            GlobalLookup, GlobalLookupKind, Precision, QualifierKey, QualifierValue,
+            GlobalLookup, GlobalLookupKind, Precision, Profile, QualifierKey, QualifierValue,
             StorageQualifier, StructLayout, TypeQualifiers,
         },
         context::{Context, ExprPos},
@@ -12,8 +12,8 @@ use crate::{
         variables::{GlobalOrConstant, VarDeclaration},
         Error, ErrorKind, Parser, Span,
     },
-    AddressSpace, Block, Expression, FunctionResult, Handle, ScalarKind, Statement, StructMember,
-    Type, TypeInner,
+    AddressSpace, Block, Expression, FunctionResult, Handle, ScalarKind, ShaderStage, Statement,
+    StructMember, Type, TypeInner,
 };
 
 use super::{DeclarationContext, ParsingContext, Result};
@@ -268,6 +268,11 @@ impl<'source> ParsingContext<'source> {
                             // This branch handles function definition and prototypes
                             self.bump(parser)?;
 
+                            if let Some(ty) = ty {
+                                let precision = qualifiers.precision.is_some();
+                                check_float_precision(parser, ty, precision, meta);
+                            }
+
                             let result = ty.map(|ty| FunctionResult { ty, binding: None });
                             let mut body = Block::new();
 
@@ -337,6 +342,8 @@ impl<'source> ParsingContext<'source> {
                 // init_declarator_list
                 // token_falltrough will have a token that was already bumped
                 if let Some(ty) = ty {
+                    check_float_precision(parser, ty, qualifiers.precision.is_some(), meta);
+
                     let mut ctx = DeclarationContext {
                         qualifiers,
                         external,
@@ -430,7 +437,7 @@ impl<'source> ParsingContext<'source> {
                     self.bump(parser)?;
 
                     let token = self.bump(parser)?;
-                    let _ = match token.value {
+                    let precision = match token.value {
                         TokenValue::PrecisionQualifier(p) => p,
                         _ => {
                             return Err(Error {
@@ -451,9 +458,13 @@ impl<'source> ParsingContext<'source> {
 
                     match parser.module.types[ty].inner {
                         TypeInner::Scalar {
-                            kind: ScalarKind::Float | ScalarKind::Sint,
+                            kind: ScalarKind::Float,
                             ..
-                        } => {}
+                        } => parser.meta.default_precisions.float = Some(precision),
+                        TypeInner::Scalar {
+                            kind: ScalarKind::Sint,
+                            ..
+                        } => parser.meta.default_precisions.int = Some(precision),
                         _ => parser.errors.push(Error {
                             kind: ErrorKind::SemanticError(
                                 "Precision statement can only work on floats and ints".into(),
@@ -498,6 +509,25 @@ impl<'source> ParsingContext<'source> {
         let span = self.parse_struct_declaration_list(parser, &mut members, layout)?;
         self.expect(parser, TokenValue::RightBrace)?;
 
+        // An anonymous redeclaration of the built-in `gl_PerVertex` block only
+        // selects which of its members are used, and those are looked up as
+        // built-in variables when referenced, so there is nothing to declare.
+        if ty_name == "gl_PerVertex" && self.bump_if(parser, TokenValue::Semicolon).is_some() {
+            for member in members.iter() {
+                let inner = &parser.module.types[member.ty].inner;
+                let name = member.name.as_deref().unwrap_or_default();
+                if !is_per_vertex_member(name, inner, &parser.module.types) {
+                    parser.errors.push(Error {
+                        kind: ErrorKind::SemanticError(
+                            format!("'{}' isn't a member of gl_PerVertex", name).into(),
+                        ),
+                        meta,
+                    });
+                }
+            }
+            return Ok(meta);
+        }
+
         let mut ty = parser.module.types.insert(
             Type {
                 name: Some(ty_name),
@@ -615,3 +645,64 @@ impl<'source> ParsingContext<'source> {
         Ok(span)
     }
 }
+
+/// Report float declarations, parameters and return types without a
+/// precision in ES fragment shaders.
+///
+/// Unlike the other stages, those have no default precision for floats, so
+/// it has to be given by a qualifier or a `precision` statement.
+pub(super) fn check_float_precision(
+    parser: &mut Parser,
+    mut ty: Handle<Type>,
+    has_precision: bool,
+    meta: Span,
+) {
+    if parser.meta.profile != Profile::Es
+        || parser.meta.stage != ShaderStage::Fragment
+        || parser.meta.default_precisions.float.is_some()
+        || has_precision
+    {
+        return;
+    }
+    while let TypeInner::Array { base, .. } = parser.module.types[ty].inner {
+        ty = base;
+    }
+    if let Some((ScalarKind::Float, _)) = scalar_components(&parser.module.types[ty].inner) {
+        parser.errors.push(Error {
+            kind: ErrorKind::SemanticError(
+                "No precision specified for float, and fragment shaders have no default".into(),
+            ),
+            meta,
+        })
+    }
+}
+
+/// Return whether a member `name` of type `inner` matches one of the members
+/// of the built-in `gl_PerVertex` block.
+fn is_per_vertex_member(name: &str, inner: &TypeInner, types: &crate::UniqueArena<Type>) -> bool {
+    let is_float = |inner: &TypeInner| {
+        matches!(
+            *inner,
+            TypeInner::Scalar {
+                kind: ScalarKind::Float,
+                ..
+            }
+        )
+    };
+    match name {
+        "gl_Position" => matches!(
+            *inner,
+            TypeInner::Vector {
+                size: crate::VectorSize::Quad,
+                kind: ScalarKind::Float,
+                ..
+            }
+        ),
+        "gl_PointSize" => is_float(inner),
+        "gl_ClipDistance" | "gl_CullDistance" => match *inner {
+            TypeInner::Array { base, .. } => is_float(&types[base].inner),
+            _ => false,
+        },
+        _ => false,
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     front::glsl::{
         ast::ParameterQualifier,
         context::Context,
-        parser::ParsingContext,
+        parser::{declarations::check_float_precision, ParsingContext},
         token::{Token, TokenValue},
         variables::VarDeclaration,
         Error, ErrorKind, Parser, Result,
@@ -20,6 +20,12 @@ impl<'source> ParsingContext<'source> {
         })
     }
 
+    pub fn peek_precision_qualifier(&mut self, parser: &mut Parser) -> bool {
+        self.peek(parser).map_or(false, |t| {
+            matches!(t.value, TokenValue::PrecisionQualifier(_))
+        })
+    }
+
     /// Returns the parsed `ParameterQualifier` or `ParameterQualifier::In`
     pub fn parse_parameter_qualifier(&mut self, parser: &mut Parser) -> ParameterQualifier {
         if self.peek_parameter_qualifier(parser) {
@@ -154,6 +160,7 @@ impl<'source> ParsingContext<'source> {
                         condition,
                         accept,
                         reject,
+                        hint: None,
                     },
                     meta,
                 );
@@ -305,6 +312,7 @@ impl<'source> ParsingContext<'source> {
                         condition,
                         accept: new_break(),
                         reject: Block::new(),
+                        hint: None,
                     },
                     crate::Span::default(),
                 );
@@ -335,45 +343,114 @@ impl<'source> ParsingContext<'source> {
                 let mut terminator = None;
                 self.parse_statement(parser, ctx, &mut loop_body, &mut terminator)?;
 
+                // A `continue` must still evaluate the condition, which can't
+                // be done by a check at the end of the body. In that case the
+                // condition is evaluated in the continuing block instead, and
+                // checked at the start of the next iteration.
+                let continues = contains_continue(&loop_body);
+                let mut continuing = Block::new();
+
                 let mut stmt = ctx.stmt_ctx();
 
                 self.expect(parser, TokenValue::While)?;
                 self.expect(parser, TokenValue::LeftParen)?;
-                let root = self.parse_expression(parser, ctx, &mut stmt, &mut loop_body)?;
+                let condition_body = if continues {
+                    &mut continuing
+                } else {
+                    &mut loop_body
+                };
+                let root = self.parse_expression(parser, ctx, &mut stmt, condition_body)?;
                 let end_meta = self.expect(parser, TokenValue::RightParen)?.meta;
 
                 meta.subsume(end_meta);
 
-                let (expr, expr_meta) =
-                    ctx.lower_expect(stmt, parser, root, ExprPos::Rhs, &mut loop_body)?;
+                let (mut expr, expr_meta) =
+                    ctx.lower_expect(stmt, parser, root, ExprPos::Rhs, condition_body)?;
+
+                let mut check = Block::new();
+                if continues {
+                    let ty = parser.module.types.insert(
+                        crate::Type {
+                            name: None,
+                            inner: crate::TypeInner::Scalar {
+                                kind: crate::ScalarKind::Bool,
+                                width: crate::BOOL_WIDTH,
+                            },
+                        },
+                        Span::default(),
+                    );
+                    let init = parser.module.constants.fetch_or_append(
+                        crate::Constant {
+                            name: None,
+                            specialization: None,
+                            inner: ConstantInner::Scalar {
+                                width: crate::BOOL_WIDTH,
+                                value: ScalarValue::Bool(true),
+                            },
+                        },
+                        Span::default(),
+                    );
+                    let local = ctx.locals.append(
+                        crate::LocalVariable {
+                            name: None,
+                            ty,
+                            init: Some(init),
+                        },
+                        expr_meta,
+                    );
+
+                    ctx.emit_restart(&mut continuing);
+
+                    let pointer = ctx.add_expression(
+                        Expression::LocalVariable(local),
+                        expr_meta,
+                        &mut continuing,
+                    );
+                    continuing.push(
+                        Statement::Store {
+                            pointer,
+                            value: expr,
+                        },
+                        expr_meta,
+                    );
+
+                    expr = ctx.add_expression(Expression::Load { pointer }, expr_meta, &mut check);
+                }
+
                 let condition = ctx.add_expression(
                     Expression::Unary {
                         op: UnaryOperator::Not,
                         expr,
                     },
                     expr_meta,
-                    &mut loop_body,
+                    &mut check,
                 );
 
-                ctx.emit_restart(&mut loop_body);
+                ctx.emit_restart(&mut check);
 
-                loop_body.push(
+                check.push(
                     Statement::If {
                         condition,
                         accept: new_break(),
                         reject: Block::new(),
+                        hint: None,
                     },
                     crate::Span::default(),
                 );
 
-                if let Some(idx) = terminator {
-                    loop_body.cull(idx..)
+                if continues {
+                    loop_body.splice(..0, check);
+                } else {
+                    loop_body.extend_block(check);
+                    if let Some(idx) = terminator {
+                        loop_body.cull(idx..)
+                    }
                 }
 
                 body.push(
                     Statement::Loop {
                         body: loop_body,
-                        continuing: Block::new(),
+                        continuing,
                     },
                     meta,
                 );
@@ -449,6 +526,7 @@ impl<'source> ParsingContext<'source> {
                             condition,
                             accept: new_break(),
                             reject: Block::new(),
+                            hint: None,
                         },
                         crate::Span::default(),
                     );
@@ -566,9 +644,17 @@ impl<'source> ParsingContext<'source> {
         }
 
         loop {
-            if self.peek_type_name(parser) || self.peek_parameter_qualifier(parser) {
+            if self.peek_type_name(parser)
+                || self.peek_parameter_qualifier(parser)
+                || self.peek_precision_qualifier(parser)
+            {
                 let qualifier = self.parse_parameter_qualifier(parser);
-                let mut ty = self.parse_type_non_void(parser)?.0;
+                let precision = self.peek_precision_qualifier(parser);
+                if precision {
+                    self.bump(parser)?;
+                }
+                let (mut ty, meta) = self.parse_type_non_void(parser)?;
+                check_float_precision(parser, ty, precision, meta);
 
                 match self.expect_peek(parser)?.value {
                     TokenValue::Comma => {
@@ -598,3 +684,21 @@ impl<'source> ParsingContext<'source> {
         Ok(())
     }
 }
+
+/// Returns whether `block` contains a `continue` of the loop it belongs to
+fn contains_continue(block: &Block) -> bool {
+    block.iter().any(|stmt| match *stmt {
+        Statement::Continue => true,
+        Statement::Block(ref block) => contains_continue(block),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => contains_continue(accept) || contains_continue(reject),
+        Statement::Switch { ref cases, .. } => {
+            cases.iter().any(|case| contains_continue(&case.body))
+        }
+        // The `continue`s of nested loops belong to them
+        _ => false,
+    })
+}
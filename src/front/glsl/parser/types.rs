@@ -82,7 +82,12 @@ impl<'source> ParsingContext<'source> {
             TokenValue::TypeName(ty) => parser.module.types.insert(ty, token.meta),
             TokenValue::Struct => {
                 let mut meta = token.meta;
-                let ty_name = self.expect_ident(parser)?.0;
+                // Anonymous structs can only be used by the declaration
+                // they're part of, so they aren't registered as a type name.
+                let ty_name = match self.expect_peek(parser)?.value {
+                    TokenValue::Identifier(_) => Some(self.expect_ident(parser)?.0),
+                    _ => None,
+                };
                 self.expect(parser, TokenValue::LeftBrace)?;
                 let mut members = Vec::new();
                 let span =
@@ -91,12 +96,14 @@ impl<'source> ParsingContext<'source> {
                 meta.subsume(end_meta);
                 let ty = parser.module.types.insert(
                     Type {
-                        name: Some(ty_name.clone()),
+                        name: ty_name.clone(),
                         inner: TypeInner::Struct { members, span },
                     },
                     meta,
                 );
-                parser.lookup_type.insert(ty_name, ty);
+                if let Some(ty_name) = ty_name {
+                    parser.lookup_type.insert(ty_name, ty);
+                }
                 ty
             }
             TokenValue::Identifier(ident) => match parser.lookup_type.get(&ident) {
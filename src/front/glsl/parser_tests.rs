@@ -111,6 +111,144 @@ fn version() {
         (parser.metadata().version, parser.metadata().profile),
         (450, Profile::Core)
     );
+
+    parser
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            "#version 310 es\nvoid main() {}",
+        )
+        .unwrap();
+    assert_eq!(
+        (parser.metadata().version, parser.metadata().profile),
+        (310, Profile::Es)
+    );
+
+    // ES versions need the ES profile and the other way around
+    for source in [
+        "#version 310\nvoid main() {}",
+        "#version 450 es\nvoid main() {}",
+    ]
+    .iter()
+    {
+        let errors = parser
+            .parse(&Options::from(ShaderStage::Vertex), source)
+            .err()
+            .unwrap();
+        assert!(matches!(
+            errors[..],
+            [Error {
+                kind: ErrorKind::InvalidVersion(310 | 450),
+                ..
+            }]
+        ));
+    }
+}
+
+#[test]
+fn precision() {
+    let mut parser = Parser::default();
+
+    // ES fragment shaders have no default float precision
+    let errors = parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 300 es
+        layout(location = 0) out vec4 color;
+
+        void main() {
+            color = vec4(1.0);
+        }
+        "#,
+        )
+        .err()
+        .unwrap();
+    assert!(matches!(
+        errors[..],
+        [Error {
+            kind: ErrorKind::SemanticError(_),
+            ..
+        }]
+    ));
+
+    // Locals, parameters and return types need a precision too
+    let errors = parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 300 es
+        layout(location = 0) out highp vec4 color;
+
+        float scaled(vec2 v) {
+            float scale = 0.5;
+            return scale;
+        }
+
+        void main() {
+            color = vec4(scaled(vec2(1.0)));
+        }
+        "#,
+        )
+        .err()
+        .unwrap();
+    assert_eq!(errors.len(), 3);
+
+    parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 300 es
+        layout(location = 0) out highp vec4 color;
+
+        mediump float scaled(in lowp vec2 v) {
+            return v.x;
+        }
+
+        void main() {
+            int count = 2;
+            mediump float scale = 0.5;
+            color = vec4(scaled(vec2(scale)) * float(count));
+        }
+        "#,
+        )
+        .unwrap();
+
+    parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 300 es
+        precision mediump float;
+        precision highp int;
+        layout(location = 0) out vec4 color;
+
+        void main() {
+            color = vec4(1.0);
+        }
+        "#,
+        )
+        .unwrap();
+    assert_eq!(
+        parser.metadata().default_precisions,
+        super::DefaultPrecisions {
+            float: Some(super::Precision::Medium),
+            int: Some(super::Precision::High),
+        }
+    );
+
+    // Other stages default to `highp`, and desktop GLSL ignores precisions
+    parser
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            "#version 300 es\nvoid main() { gl_Position = vec4(1.0); }",
+        )
+        .unwrap();
+    parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            "#version 450\nlayout(location = 0) out vec4 color;\nvoid main() {}",
+        )
+        .unwrap();
 }
 
 #[test]
@@ -206,6 +344,111 @@ fn control_flow() {
         .unwrap();
 }
 
+#[test]
+fn loops() {
+    use crate::{Block, Statement};
+
+    /// Returns whether `stmt` is a `if (cond) break;`
+    fn is_break_if(stmt: &Statement) -> bool {
+        match *stmt {
+            Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => matches!(accept[..], [Statement::Break]) && reject.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// Returns the statements of `block` which aren't `Emit`s
+    fn statements(block: &Block) -> Vec<&Statement> {
+        block
+            .iter()
+            .filter(|stmt| !matches!(**stmt, Statement::Emit(_)))
+            .collect()
+    }
+
+    let mut parser = Parser::default();
+
+    let module = parser
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            r#"
+        #  version 450
+        void main() {
+            int x = 0;
+            for(int i = 0; i < 10; i++) {
+                x = x + 2;
+            }
+            while(x < 5) {
+                x = x + 1;
+            }
+            do {
+                x = x - 1;
+            } while(x >= 4);
+            do {
+                if (x == 2) {
+                    continue;
+                }
+                x = x - 1;
+            } while(x-- >= 0);
+        }
+        "#,
+        )
+        .unwrap();
+
+    let (_, main) = module
+        .functions
+        .iter()
+        .find(|&(_, function)| function.name.as_deref() == Some("main"))
+        .unwrap();
+    let loops: Vec<_> = main
+        .body
+        .iter()
+        .filter_map(|stmt| match *stmt {
+            Statement::Loop {
+                ref body,
+                ref continuing,
+            } => Some((statements(body), statements(continuing))),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(loops.len(), 4);
+
+    // `for`: the condition is checked at the start and the step is done
+    // in the continuing block
+    let (ref body, ref continuing) = loops[0];
+    assert!(is_break_if(body[0]));
+    assert!(matches!(continuing[..], [Statement::Store { .. }]));
+
+    // `while`: the condition is checked at the start
+    let (ref body, ref continuing) = loops[1];
+    assert!(is_break_if(body[0]));
+    assert!(continuing.is_empty());
+
+    // `do-while`: the condition is checked at the end
+    let (ref body, ref continuing) = loops[2];
+    assert!(is_break_if(body[body.len() - 1]));
+    assert!(continuing.is_empty());
+
+    // `do-while` with a `continue`: the condition is evaluated in the
+    // continuing block and checked at the start of the next iteration
+    let (ref body, ref continuing) = loops[3];
+    assert!(is_break_if(body[0]));
+    assert!(matches!(
+        continuing[..],
+        [Statement::Store { .. }, Statement::Store { .. }]
+    ));
+
+    #[cfg(feature = "validate")]
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
+
 #[test]
 fn declarations() {
     let mut parser = Parser::default();
@@ -344,6 +587,90 @@ fn textures() {
         .unwrap();
 }
 
+#[test]
+fn texture_builtins() {
+    use crate::{Expression, ImageQuery, SampleLevel};
+
+    let mut parser = Parser::default();
+
+    let module = parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 450
+        layout(location = 0) in vec2 v_uv;
+        layout(location = 0) out vec4 o_color;
+        layout(set = 1, binding = 1) uniform texture2D tex;
+        layout(set = 1, binding = 2) uniform texture2DMS tex_ms;
+        layout(set = 1, binding = 3) uniform sampler tex_sampler;
+        void main() {
+            o_color = texture(sampler2D(tex, tex_sampler), v_uv);
+            o_color += textureLod(sampler2D(tex, tex_sampler), v_uv, 1.0);
+            o_color += textureGrad(sampler2D(tex, tex_sampler), v_uv, vec2(1.0), vec2(1.0));
+            o_color += textureProj(sampler2D(tex, tex_sampler), vec3(v_uv, 2.0));
+            o_color += texelFetch(sampler2D(tex, tex_sampler), ivec2(0), 0);
+            o_color += texelFetch(sampler2DMS(tex_ms, tex_sampler), ivec2(0), 1);
+            o_color.x += float(textureQueryLevels(sampler2D(tex, tex_sampler)));
+            o_color.x += float(textureSamples(sampler2DMS(tex_ms, tex_sampler)));
+        }
+        "#,
+        )
+        .unwrap();
+
+    let (_, main) = module.functions.iter().next().unwrap();
+    let mut samples = Vec::new();
+    let mut loads = Vec::new();
+    let mut queries = Vec::new();
+    for (_, expr) in main.expressions.iter() {
+        match *expr {
+            Expression::ImageSample { level, .. } => samples.push(level),
+            Expression::ImageLoad { sample, level, .. } => {
+                loads.push((sample.is_some(), level.is_some()))
+            }
+            Expression::ImageQuery { query, .. } => queries.push(query),
+            _ => {}
+        }
+    }
+    assert!(matches!(
+        samples[..],
+        [
+            SampleLevel::Auto,
+            SampleLevel::Exact(_),
+            SampleLevel::Gradient { .. },
+            SampleLevel::Auto,
+        ]
+    ));
+    assert_eq!(loads, [(false, true), (true, false)]);
+    assert_eq!(queries, [ImageQuery::NumLevels, ImageQuery::NumSamples]);
+
+    #[cfg(feature = "validate")]
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    // Builtins that can't be mapped yet are reported as such
+    let errors = parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #version 450
+        layout(location = 0) in vec2 v_uv;
+        layout(location = 0) out vec4 o_color;
+        layout(set = 1, binding = 1) uniform texture2D tex;
+        layout(set = 1, binding = 3) uniform sampler tex_sampler;
+        void main() {
+            o_color = textureGather(sampler2D(tex, tex_sampler), v_uv);
+        }
+        "#,
+        )
+        .err()
+        .unwrap();
+    assert_eq!(errors[0].kind, ErrorKind::NotImplemented("textureGather"));
+}
+
 #[test]
 fn functions() {
     let mut parser = Parser::default();
@@ -543,6 +870,57 @@ fn constants() {
     assert!(constants.next().is_none());
 }
 
+#[test]
+fn constant_array_sizes() {
+    use crate::{ArraySize, ConstantInner, ScalarValue, TypeInner};
+    let mut parser = Parser::default();
+
+    let module = parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #  version 450
+        const int N = 4;
+        const int M = N * 2 + 1;
+        const float PI = 3.0;
+        const float TABLE[2] = float[2](PI, PI * 2.0);
+        float data[M];
+
+        void main() {}
+        "#,
+        )
+        .unwrap();
+
+    let (_, data) = module.global_variables.iter().next().unwrap();
+    match module.types[data.ty].inner {
+        TypeInner::Array {
+            size: ArraySize::Constant(size),
+            ..
+        } => assert_eq!(module.constants[size].to_array_length(), Some(9)),
+        ref other => panic!("Unexpected type {:?}", other),
+    }
+    assert!(module.constants.iter().any(|(_, constant)| constant.inner
+        == ConstantInner::Scalar {
+            width: 4,
+            value: ScalarValue::Float(6.0)
+        }));
+
+    // Global constants need constant initializers.
+    let errors = parser
+        .parse(
+            &Options::from(ShaderStage::Fragment),
+            r#"
+        #  version 450
+        layout(set = 0, binding = 0) uniform Data { float value; };
+        const float SCALED = value * 2.0;
+
+        void main() {}
+        "#,
+        )
+        .unwrap_err();
+    assert!(matches!(errors[0].kind, ErrorKind::SemanticError(_)));
+}
+
 #[test]
 fn function_overloading() {
     let mut parser = Parser::default();
@@ -720,6 +1098,71 @@ fn structs() {
         "#,
         )
         .unwrap_err();
+
+    parser
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            r#"
+        #  version 450
+        struct Inner {
+            float a;
+            vec2 b;
+        };
+        struct Outer {
+            Inner inner;
+            Inner list[2];
+        };
+
+        Outer make(float x) {
+            Outer o;
+            o.inner.a = x;
+            o.list[1].b.y = x;
+            return o;
+        }
+
+        float get(Outer o) {
+            return o.inner.b.x + o.list[1].a;
+        }
+
+        void main() {
+            struct {
+                Outer outer;
+            } anon;
+            anon.outer = make(1.0);
+            Inner arr[3];
+            arr[0] = anon.outer.inner;
+            gl_Position = vec4(get(anon.outer), arr[0].b, 1.0);
+        }
+        "#,
+        )
+        .unwrap();
+
+    let errors = parser
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            r#"
+        #  version 450
+        struct Inner {
+            float a;
+        };
+        struct Outer {
+            Inner inner;
+        };
+
+        void main() {
+            Outer o;
+            gl_Position = vec4(o.inner.b);
+        }
+        "#,
+        )
+        .unwrap_err();
+    assert_eq!(
+        errors
+            .into_iter()
+            .map(|error| error.kind)
+            .collect::<Vec<_>>(),
+        [ErrorKind::UnknownField("b".into())]
+    );
 }
 
 #[test]
@@ -819,3 +1262,82 @@ fn expressions() {
         )
         .unwrap();
 }
+
+#[test]
+fn vertex_builtin_outputs() {
+    use crate::{Binding, BuiltIn, TypeInner};
+
+    let sources = [
+        r#"
+        #version 450
+        void main() {
+            gl_Position = vec4(1.0);
+            gl_PointSize = 2.0;
+        }
+        "#,
+        r#"
+        #version 450
+        out gl_PerVertex {
+            vec4 gl_Position;
+            float gl_PointSize;
+        };
+
+        void main() {
+            gl_Position = vec4(1.0);
+            gl_PointSize = 2.0;
+        }
+        "#,
+    ];
+
+    for source in sources {
+        let mut parser = Parser::default();
+        let module = parser
+            .parse(&Options::from(ShaderStage::Vertex), source)
+            .unwrap();
+
+        let result = module.entry_points[0].function.result.as_ref().unwrap();
+        let bindings: Vec<_> = match module.types[result.ty].inner {
+            TypeInner::Struct { ref members, .. } => members
+                .iter()
+                .map(|member| member.binding.clone().unwrap())
+                .collect(),
+            _ => panic!("Expected the outputs to be a struct"),
+        };
+        assert_eq!(
+            bindings,
+            [
+                Binding::BuiltIn(BuiltIn::Position { invariant: false }),
+                Binding::BuiltIn(BuiltIn::PointSize),
+            ]
+        );
+    }
+}
+
+#[test]
+fn per_vertex_redeclaration() {
+    let mut parser = Parser::default();
+    let errors = parser
+        .parse(
+            &Options::from(ShaderStage::Vertex),
+            r#"
+        #version 450
+        out gl_PerVertex {
+            vec3 gl_Position;
+            float gl_Size;
+            float gl_ClipDistance[];
+        };
+
+        void main() {}
+        "#,
+        )
+        .unwrap_err();
+
+    let kinds: Vec<_> = errors.into_iter().map(|error| error.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            ErrorKind::SemanticError("'gl_Position' isn't a member of gl_PerVertex".into()),
+            ErrorKind::SemanticError("'gl_Size' isn't a member of gl_PerVertex".into()),
+        ]
+    );
+}
@@ -193,8 +193,8 @@ impl Parser {
                         stride: 4,
                     },
                     builtin: match name {
-                        "gl_ClipDistance" => BuiltIn::PointSize,
-                        "gl_CullDistance" => BuiltIn::FragDepth,
+                        "gl_ClipDistance" => BuiltIn::ClipDistance,
+                        "gl_CullDistance" => BuiltIn::CullDistance,
                         _ => unreachable!(),
                     },
                     mutable: self.meta.stage == ShaderStage::Vertex,
@@ -38,10 +38,10 @@ impl Emitter {
         arena: &Arena<crate::Expression>,
     ) -> Option<(crate::Statement, crate::span::Span)> {
         let start_len = self.start_len.take().unwrap();
-        if start_len != arena.len() {
+        let range = arena.range_from(start_len);
+        if !range.is_empty() {
             #[allow(unused_mut)]
             let mut span = crate::span::Span::default();
-            let range = arena.range_from(start_len);
             #[cfg(feature = "span")]
             for handle in range.clone() {
                 span.subsume(arena.get_span(handle))
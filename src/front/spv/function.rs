@@ -14,6 +14,29 @@ pub struct MergeInstruction {
     pub continue_block_id: Option<BlockId>,
 }
 
+/// Returns the base type of a pointer argument of type `ty` if naga functions
+/// can't take such pointers directly.
+///
+/// SPIR-V lets functions take pointers into uniform and storage buffers, but
+/// naga only allows pointer arguments in the function, private and workgroup
+/// address spaces. Such arguments get copied into a function-local temporary
+/// around the call, and the callee takes a pointer to that instead.
+pub(super) fn spilled_argument_base(
+    ty: Handle<crate::Type>,
+    types: &crate::UniqueArena<crate::Type>,
+) -> Option<Handle<crate::Type>> {
+    match types[ty].inner {
+        crate::TypeInner::Pointer {
+            base,
+            space:
+                crate::AddressSpace::Uniform
+                | crate::AddressSpace::Storage { .. }
+                | crate::AddressSpace::PushConstant,
+        } if !types[base].inner.is_dynamically_sized(types) => Some(base),
+        _ => None,
+    }
+}
+
 impl<I: Iterator<Item = u32>> super::Parser<I> {
     // Registers a function call. It will generate a dummy handle to call, which
     // gets resolved after all the functions are processed.
@@ -101,7 +124,21 @@ impl<I: Iterator<Item = u32>> super::Parser<I> {
                     {
                         return Err(Error::WrongFunctionArgumentType(type_id));
                     }
-                    let ty = self.lookup_type.lookup(type_id)?.handle;
+                    let mut ty = self.lookup_type.lookup(type_id)?.handle;
+                    // Callers spill pointers naga can't pass by reference into
+                    // function-local temporaries, so take those instead.
+                    if let Some(base) = spilled_argument_base(ty, &module.types) {
+                        ty = module.types.insert(
+                            crate::Type {
+                                name: None,
+                                inner: crate::TypeInner::Pointer {
+                                    base,
+                                    space: crate::AddressSpace::Function,
+                                },
+                            },
+                            self.span_from(start),
+                        );
+                    }
                     let decor = self.future_decor.remove(&id).unwrap_or_default();
                     fun.arguments.push(crate::FunctionArgument {
                         name: decor.name,
@@ -522,6 +559,26 @@ impl<I: Iterator<Item = u32>> super::Parser<I> {
 }
 
 impl<'function> BlockContext<'function> {
+    /// Returns true if the memory `pointer` refers to may be written through it.
+    pub(super) fn is_writable_pointer(&self, mut pointer: Handle<crate::Expression>) -> bool {
+        loop {
+            match self.expressions[pointer] {
+                crate::Expression::Access { base, .. }
+                | crate::Expression::AccessIndex { base, .. } => pointer = base,
+                crate::Expression::GlobalVariable(handle) => {
+                    break self.global_arena[handle]
+                        .space
+                        .access()
+                        .contains(crate::StorageAccess::STORE)
+                }
+                crate::Expression::LocalVariable(_) | crate::Expression::FunctionArgument(_) => {
+                    break true
+                }
+                _ => break false,
+            }
+        }
+    }
+
     /// Consumes the `BlockContext` producing a Ir [`Block`](crate::Block)
     fn lower(mut self) -> crate::Block {
         fn lower_impl(
@@ -547,6 +604,7 @@ impl<'function> BlockContext<'function> {
                                 condition,
                                 accept,
                                 reject,
+                                hint: None,
                             },
                             crate::Span::default(),
                         )
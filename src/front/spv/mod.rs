@@ -887,27 +887,46 @@ impl<I: Iterator<Item = u32>> Parser<I> {
             .inner
             .scalar_kind()
             .unwrap();
+        let p1_lookup_ty = self.lookup_type.lookup(p1_lexp.type_id)?;
+        let p1_kind = ctx.type_arena[p1_lookup_ty.handle].inner.scalar_kind();
 
+        // The IR only negates signed values, so unsigned results are negated
+        // as signed integers and cast back.
+        let operand_kind = match (op, kind) {
+            (crate::UnaryOperator::Negate, crate::ScalarKind::Uint) => crate::ScalarKind::Sint,
+            _ => kind,
+        };
         let expr = crate::Expression::Unary {
             op,
-            expr: if p1_lexp.type_id == result_type_id {
+            expr: if p1_kind == Some(operand_kind) {
                 left
             } else {
                 ctx.expressions.append(
                     crate::Expression::As {
                         expr: left,
-                        kind,
+                        kind: operand_kind,
                         convert: None,
                     },
                     span,
                 )
             },
         };
+        let mut handle = ctx.expressions.append(expr, span);
+        if operand_kind != kind {
+            handle = ctx.expressions.append(
+                crate::Expression::As {
+                    expr: handle,
+                    kind,
+                    convert: None,
+                },
+                span,
+            );
+        }
 
         self.lookup_expression.insert(
             result_id,
             LookupExpression {
-                handle: ctx.expressions.append(expr, span),
+                handle,
                 type_id: result_type_id,
                 block_id,
             },
@@ -2738,7 +2757,6 @@ impl<I: Iterator<Item = u32>> Parser<I> {
                 }
                 Op::FunctionCall => {
                     inst.expect_at_least(4)?;
-                    block.extend(emitter.finish(ctx.expressions));
 
                     let result_type_id = self.next()?;
                     let result_id = self.next()?;
@@ -2748,8 +2766,52 @@ impl<I: Iterator<Item = u32>> Parser<I> {
                     for _ in 0..arguments.capacity() {
                         let arg_id = self.next()?;
                         let lexp = self.lookup_expression.lookup(arg_id)?;
-                        arguments.push(get_expr_handle!(arg_id, lexp));
+                        let arg_handle = get_expr_handle!(arg_id, lexp);
+                        let arg_ty = self.lookup_type.lookup(lexp.type_id)?.handle;
+                        // Naga can't pass some pointers by reference, so load
+                        // the value to pass a pointer to a local copy instead.
+                        let spill = spilled_argument_base(arg_ty, ctx.type_arena).map(|base| {
+                            let local = ctx.local_arena.append(
+                                crate::LocalVariable {
+                                    name: None,
+                                    ty: base,
+                                    init: None,
+                                },
+                                span,
+                            );
+                            let value = ctx.expressions.append(
+                                crate::Expression::Load {
+                                    pointer: arg_handle,
+                                },
+                                span,
+                            );
+                            (local, value)
+                        });
+                        arguments.push((arg_handle, spill));
                     }
+                    block.extend(emitter.finish(ctx.expressions));
+
+                    let mut spills = Vec::new();
+                    let arguments = arguments
+                        .into_iter()
+                        .map(|(arg_handle, spill)| match spill {
+                            Some((local, value)) => {
+                                let temp = ctx
+                                    .expressions
+                                    .append(crate::Expression::LocalVariable(local), span);
+                                block.push(
+                                    crate::Statement::Store {
+                                        pointer: temp,
+                                        value,
+                                    },
+                                    span,
+                                );
+                                spills.push((arg_handle, temp));
+                                temp
+                            }
+                            None => arg_handle,
+                        })
+                        .collect();
 
                     // We just need an unique handle here, nothing more.
                     let function = self.add_call(ctx.function_id, func_id);
@@ -2778,6 +2840,20 @@ impl<I: Iterator<Item = u32>> Parser<I> {
                         },
                         span,
                     );
+
+                    // Copy the spilled values back, if the callee could have
+                    // written to them.
+                    for (pointer, temp) in spills {
+                        if !ctx.is_writable_pointer(pointer) {
+                            continue;
+                        }
+                        emitter.start(ctx.expressions);
+                        let value = ctx
+                            .expressions
+                            .append(crate::Expression::Load { pointer: temp }, span);
+                        block.extend(emitter.finish(ctx.expressions));
+                        block.push(crate::Statement::Store { pointer, value }, span);
+                    }
                     emitter.start(ctx.expressions);
                 }
                 Op::ExtInst => {
@@ -3520,6 +3596,7 @@ impl<I: Iterator<Item = u32>> Parser<I> {
                     condition: _,
                     ref mut accept,
                     ref mut reject,
+                    ..
                 } => {
                     self.patch_statements(reject, expressions, fun_parameter_sampling)?;
                     self.patch_statements(accept, expressions, fun_parameter_sampling)?;
@@ -161,6 +161,8 @@ pub enum Error<'a> {
     UnknownStorageFormat(Span),
     UnknownConservativeDepth(Span),
     ZeroSizeOrAlign(Span),
+    NonPowerOfTwoAlignment(Span),
+    SizeAttributeTooLow(Span, u32),
     InconsistentBinding(Span),
     UnknownLocalFunction(Span),
     TypeNotConstructible(Span),
@@ -427,6 +429,16 @@ impl<'a> Error<'a> {
                 labels: vec![(bad_span.clone(), "struct member size or alignment must not be 0".into())],
                 notes: vec![],
             },
+            Error::NonPowerOfTwoAlignment(ref bad_span) => ParseError {
+                message: "struct member alignment must be a power of 2".to_string(),
+                labels: vec![(bad_span.clone(), "must be a power of 2".into())],
+                notes: vec![],
+            },
+            Error::SizeAttributeTooLow(ref bad_span, min_size) => ParseError {
+                message: format!("struct member size `{}` is smaller than the size of its type", &source[bad_span.clone()]),
+                labels: vec![(bad_span.clone(), format!("must be at least {}", min_size).into())],
+                notes: vec![],
+            },
             Error::InconsistentBinding(ref span) => ParseError {
                 message: "input/output binding is not consistent".to_string(),
                 labels: vec![(span.clone(), "input/output binding is not consistent".into())],
@@ -793,6 +805,11 @@ impl<'a> StringValueLookup<'a> for FastHashMap<&'a str, TypedExpression> {
 
 struct StatementContext<'input, 'temp, 'out> {
     lookup_ident: &'temp mut FastHashMap<&'input str, TypedExpression>,
+    /// The identifiers declared in the innermost block, with their spans.
+    block_idents: &'temp mut FastHashMap<&'input str, Span>,
+    /// The identifiers declared in the enclosing blocks, each with the
+    /// definition it shadows, so that `leave_block` can restore them.
+    declared_idents: &'temp mut Vec<(&'input str, Option<TypedExpression>)>,
     typifier: &'temp mut super::Typifier,
     variables: &'out mut Arena<crate::LocalVariable>,
     expressions: &'out mut Arena<crate::Expression>,
@@ -808,6 +825,8 @@ impl<'a, 'temp> StatementContext<'a, 'temp, '_> {
     fn reborrow(&mut self) -> StatementContext<'a, '_, '_> {
         StatementContext {
             lookup_ident: self.lookup_ident,
+            block_idents: self.block_idents,
+            declared_idents: self.declared_idents,
             typifier: self.typifier,
             variables: self.variables,
             expressions: self.expressions,
@@ -820,6 +839,44 @@ impl<'a, 'temp> StatementContext<'a, 'temp, '_> {
         }
     }
 
+    /// Start a block, in which identifiers can shadow the outer ones.
+    fn enter_block(&mut self) -> OuterBlock<'a> {
+        OuterBlock {
+            declared_count: self.declared_idents.len(),
+            block_idents: std::mem::take(self.block_idents),
+        }
+    }
+
+    /// Finish the block started by `enter_block`, forgetting the identifiers
+    /// declared in it.
+    fn leave_block(&mut self, outer: OuterBlock<'a>) {
+        for (name, shadowed) in self.declared_idents.drain(outer.declared_count..).rev() {
+            match shadowed {
+                Some(expression) => self.lookup_ident.insert(name, expression),
+                None => self.lookup_ident.remove(name),
+            };
+        }
+        *self.block_idents = outer.block_idents;
+    }
+
+    /// Declare the identifier `name` in the innermost block.
+    fn declare(
+        &mut self,
+        name: &'a str,
+        name_span: Span,
+        expression: TypedExpression,
+    ) -> Result<(), Error<'a>> {
+        if let Some(previous) = self.block_idents.insert(name, name_span.clone()) {
+            return Err(Error::Redefinition {
+                previous,
+                current: name_span,
+            });
+        }
+        let shadowed = self.lookup_ident.insert(name, expression);
+        self.declared_idents.push((name, shadowed));
+        Ok(())
+    }
+
     fn as_expression<'t>(
         &'t mut self,
         block: &'t mut crate::Block,
@@ -844,9 +901,17 @@ impl<'a, 'temp> StatementContext<'a, 'temp, '_> {
     }
 }
 
+/// The identifiers of the enclosing block, saved by
+/// [`StatementContext::enter_block`].
+struct OuterBlock<'input> {
+    declared_count: usize,
+    block_idents: FastHashMap<&'input str, Span>,
+}
+
 struct SamplingContext {
     image: Handle<crate::Expression>,
     arrayed: bool,
+    depth: bool,
 }
 
 struct ExpressionContext<'input, 'temp, 'out> {
@@ -903,12 +968,13 @@ impl<'a> ExpressionContext<'a, '_, '_> {
         image: Handle<crate::Expression>,
         span: Span,
     ) -> Result<SamplingContext, Error<'a>> {
-        Ok(SamplingContext {
-            image,
-            arrayed: match *self.resolve_type(image)? {
-                crate::TypeInner::Image { arrayed, .. } => arrayed,
-                _ => return Err(Error::BadTexture(span)),
+        Ok(match *self.resolve_type(image)? {
+            crate::TypeInner::Image { arrayed, class, .. } => SamplingContext {
+                image,
+                arrayed,
+                depth: matches!(class, crate::ImageClass::Depth { .. }),
             },
+            _ => return Err(Error::BadTexture(span)),
         })
     }
 
@@ -1624,14 +1690,30 @@ impl Parser {
                     let expr = self.parse_general_expression(lexer, ctx.reborrow())?;
                     lexer.close_arguments()?;
 
+                    // The bits are reinterpreted, so both types must have the
+                    // same number of components of the same width.
+                    let from = match *ctx.resolve_type(expr)? {
+                        crate::TypeInner::Scalar { width, .. } => Some((None, width)),
+                        crate::TypeInner::Vector { size, width, .. } => Some((Some(size), width)),
+                        _ => None,
+                    };
                     let kind = match ctx.types[ty].inner {
-                        crate::TypeInner::Scalar { kind, .. } => kind,
-                        crate::TypeInner::Vector { kind, .. } => kind,
+                        crate::TypeInner::Scalar { kind, width } if from == Some((None, width)) => {
+                            kind
+                        }
+                        crate::TypeInner::Vector { kind, size, width }
+                            if from == Some((Some(size), width)) =>
+                        {
+                            kind
+                        }
                         _ => {
                             return Err(Error::BadTypeCast {
-                                from_type: format!("{:?}", ctx.resolve_type(expr)?),
+                                from_type: ctx
+                                    .typifier
+                                    .get(expr, ctx.types)
+                                    .to_wgsl(ctx.types, ctx.constants),
                                 span: type_span,
-                                to_type: format!("{:?}", ctx.types[ty].inner),
+                                to_type: ctx.types[ty].inner.to_wgsl(ctx.types, ctx.constants),
                             })
                         }
                     };
@@ -1843,7 +1925,19 @@ impl Parser {
                         None
                     };
                     lexer.expect(Token::Separator(','))?;
-                    let level = self.parse_general_expression(lexer, ctx.reborrow())?;
+                    let (mut level, level_span) =
+                        self.parse_general_expression_with_span(lexer, ctx.reborrow())?;
+                    // Depth textures take an integer level, but the IR expects a float.
+                    if sc.depth {
+                        level = ctx.expressions.append(
+                            crate::Expression::As {
+                                expr: level,
+                                kind: crate::ScalarKind::Float,
+                                convert: Some(4),
+                            },
+                            NagaSpan::from(level_span),
+                        );
+                    }
                     let offset = if lexer.skip(Token::Separator(',')) {
                         Some(self.parse_const_expression(lexer, ctx.types, ctx.constants)?)
                     } else {
@@ -2854,6 +2948,7 @@ impl Parser {
                 ));
             }
             let (mut size, mut align) = (None, None);
+            let mut size_span = Span::default();
             self.push_scope(Scope::Attribute, lexer);
             let mut bind_parser = BindingParser::default();
             while lexer.skip(Token::Attribute) {
@@ -2863,14 +2958,22 @@ impl Parser {
                         let (value, span) = lexer
                             .capture_span(|lexer| parse_non_negative_sint_literal(lexer, 4))?;
                         lexer.expect(Token::Paren(')'))?;
-                        size = Some(NonZeroU32::new(value).ok_or(Error::ZeroSizeOrAlign(span))?);
+                        size = Some(
+                            NonZeroU32::new(value).ok_or(Error::ZeroSizeOrAlign(span.clone()))?,
+                        );
+                        size_span = span;
                     }
                     ("align", _) => {
                         lexer.expect(Token::Paren('('))?;
                         let (value, span) = lexer
                             .capture_span(|lexer| parse_non_negative_sint_literal(lexer, 4))?;
                         lexer.expect(Token::Paren(')'))?;
-                        align = Some(NonZeroU32::new(value).ok_or(Error::ZeroSizeOrAlign(span))?);
+                        let value =
+                            NonZeroU32::new(value).ok_or(Error::ZeroSizeOrAlign(span.clone()))?;
+                        if !value.get().is_power_of_two() {
+                            return Err(Error::NonPowerOfTwoAlignment(span));
+                        }
+                        align = Some(value);
                     }
                     (word, word_span) => bind_parser.parse(lexer, word, word_span)?,
                 }
@@ -2889,6 +2992,12 @@ impl Parser {
             ready = lexer.skip(Token::Separator(','));
 
             self.layouter.update(type_arena, const_arena).unwrap();
+            if let Some(size) = size {
+                let min_size = self.layouter[ty].size;
+                if size.get() < min_size {
+                    return Err(Error::SizeAttributeTooLow(size_span, min_size));
+                }
+            }
 
             let (range, align) = self.layouter.member_placement(offset, ty, align, size);
             alignment = alignment.max(align);
@@ -3293,7 +3402,7 @@ impl Parser {
     ) -> Result<(), Error<'a>> {
         use crate::BinaryOperator as Bo;
 
-        let span_start = lexer.current_byte_offset();
+        let span_start = lexer.peek().1.start;
         context.emitter.start(context.expressions);
         let reference = self.parse_unary_expression(lexer, context.reborrow())?;
         // The left hand side of an assignment must be a reference.
@@ -3440,6 +3549,7 @@ impl Parser {
     ) -> Result<(bool, crate::Block), Error<'a>> {
         let mut body = crate::Block::new();
         lexer.expect(Token::Paren('{'))?;
+        let outer = context.enter_block();
         let fall_through = loop {
             // default statements
             if lexer.skip(Token::Word("fallthrough")) {
@@ -3452,6 +3562,7 @@ impl Parser {
             }
             self.parse_statement(lexer, context.reborrow(), &mut body, false)?;
         };
+        context.leave_block(outer);
 
         Ok((fall_through, body))
     }
@@ -3474,6 +3585,7 @@ impl Parser {
                 self.push_scope(Scope::Block, lexer);
                 let _ = lexer.next();
                 let mut statements = crate::Block::new();
+                let outer = context.enter_block();
                 while !lexer.skip(Token::Paren('}')) {
                     self.parse_statement(
                         lexer,
@@ -3482,6 +3594,7 @@ impl Parser {
                         is_uniform_control_flow,
                     )?;
                 }
+                context.leave_block(outer);
                 self.pop_scope(lexer);
                 let span = NagaSpan::from(self.pop_scope(lexer));
                 block.push(crate::Statement::Block(statements), span);
@@ -3546,13 +3659,14 @@ impl Parser {
                             }
                         }
                         block.extend(emitter.finish(context.expressions));
-                        context.lookup_ident.insert(
+                        context.declare(
                             name,
+                            name_span,
                             TypedExpression {
                                 handle: expr_id,
                                 is_reference: false,
                             },
-                        );
+                        )?;
                         context
                             .named_expressions
                             .insert(expr_id, String::from(name));
@@ -3655,20 +3769,21 @@ impl Parser {
                                     _ => None,
                                 },
                             },
-                            NagaSpan::from(name_span),
+                            NagaSpan::from(name_span.clone()),
                         );
 
                         // Doesn't make sense to assign a span to cached lookup
                         let expr_id = context
                             .expressions
                             .append(crate::Expression::LocalVariable(var_id), Default::default());
-                        context.lookup_ident.insert(
+                        context.declare(
                             name,
+                            name_span,
                             TypedExpression {
                                 handle: expr_id,
                                 is_reference: true,
                             },
-                        );
+                        )?;
 
                         if let Init::Variable(value) = init {
                             Some(crate::Statement::Store {
@@ -3747,6 +3862,7 @@ impl Parser {
                                 condition: other_cond,
                                 accept: other_block,
                                 reject,
+                                hint: None,
                             };
                             reject = crate::Block::new();
                             reject.extend(other_emit);
@@ -3757,6 +3873,7 @@ impl Parser {
                             condition,
                             accept,
                             reject,
+                            hint: None,
                         })
                     }
                     "switch" => {
@@ -3830,6 +3947,8 @@ impl Parser {
                         let mut body = crate::Block::new();
                         let mut continuing = crate::Block::new();
                         lexer.expect(Token::Paren('{'))?;
+                        // The continuing block sees the declarations of the body.
+                        let outer = context.enter_block();
 
                         loop {
                             if lexer.skip(Token::Word("continuing")) {
@@ -3842,6 +3961,7 @@ impl Parser {
                             }
                             self.parse_statement(lexer, context.reborrow(), &mut body, false)?;
                         }
+                        context.leave_block(outer);
 
                         Some(crate::Statement::Loop { body, continuing })
                     }
@@ -3866,13 +3986,16 @@ impl Parser {
                                 condition,
                                 accept: crate::Block::new(),
                                 reject,
+                                hint: None,
                             },
                             NagaSpan::from(span),
                         );
 
+                        let outer = context.enter_block();
                         while !lexer.skip(Token::Paren('}')) {
                             self.parse_statement(lexer, context.reborrow(), &mut body, false)?;
                         }
+                        context.leave_block(outer);
 
                         Some(crate::Statement::Loop {
                             body,
@@ -3882,6 +4005,8 @@ impl Parser {
                     "for" => {
                         let _ = lexer.next();
                         lexer.expect(Token::Paren('('))?;
+                        // The initializer is only visible in the loop.
+                        let outer = context.enter_block();
                         if !lexer.skip(Token::Separator(';')) {
                             let num_statements = block.len();
                             let (_, span) = lexer.capture_span(|lexer| {
@@ -3921,6 +4046,7 @@ impl Parser {
                                     condition,
                                     accept: crate::Block::new(),
                                     reject,
+                                    hint: None,
                                 },
                                 NagaSpan::from(span),
                             );
@@ -3945,9 +4071,12 @@ impl Parser {
                         }
                         lexer.expect(Token::Paren('{'))?;
 
+                        let body_outer = context.enter_block();
                         while !lexer.skip(Token::Paren('}')) {
                             self.parse_statement(lexer, context.reborrow(), &mut body, false)?;
                         }
+                        context.leave_block(body_outer);
+                        context.leave_block(outer);
 
                         Some(crate::Statement::Loop { body, continuing })
                     }
@@ -4064,6 +4193,7 @@ impl Parser {
         self.push_scope(Scope::Block, lexer);
         lexer.expect(Token::Paren('{'))?;
         let mut block = crate::Block::new();
+        let outer = context.enter_block();
         while !lexer.skip(Token::Paren('}')) {
             self.parse_statement(
                 lexer,
@@ -4072,6 +4202,7 @@ impl Parser {
                 is_uniform_control_flow,
             )?;
         }
+        context.leave_block(outer);
         self.pop_scope(lexer);
         Ok(block)
     }
@@ -4203,6 +4334,8 @@ impl Parser {
             lexer,
             StatementContext {
                 lookup_ident: &mut lookup_ident,
+                block_idents: &mut FastHashMap::default(),
+                declared_idents: &mut Vec::new(),
                 typifier: &mut typifier,
                 variables: &mut fun.local_variables,
                 expressions: &mut fun.expressions,
@@ -4238,6 +4371,7 @@ impl Parser {
         let mut workgroup_size = [0u32; 3];
         let mut early_depth_test = None;
         let (mut bind_index, mut bind_group) = (None, None);
+        let mut override_id = None;
 
         self.push_scope(Scope::Attribute, lexer);
         while lexer.skip(Token::Attribute) {
@@ -4252,6 +4386,11 @@ impl Parser {
                     bind_group = Some(parse_non_negative_sint_literal(lexer, 4)?);
                     lexer.expect(Token::Paren(')'))?;
                 }
+                ("id", _) => {
+                    lexer.expect(Token::Paren('('))?;
+                    override_id = Some(parse_non_negative_sint_literal(lexer, 4)?);
+                    lexer.expect(Token::Paren(')'))?;
+                }
                 ("vertex", _) => {
                     stage = Some(crate::ShaderStage::Vertex);
                 }
@@ -4263,6 +4402,8 @@ impl Parser {
                 }
                 ("workgroup_size", _) => {
                     lexer.expect(Token::Paren('('))?;
+                    // Omitted dimensions default to 1.
+                    workgroup_size = [1; 3];
                     for (i, size) in workgroup_size.iter_mut().enumerate() {
                         *size = parse_generic_non_negative_int_literal(lexer, 4)?;
                         match lexer.next() {
@@ -4276,11 +4417,6 @@ impl Parser {
                             }
                         }
                     }
-                    for size in workgroup_size.iter_mut() {
-                        if *size == 0 {
-                            *size = 1;
-                        }
-                    }
                 }
                 ("early_depth_test", _) => {
                     let conservative = if lexer.skip(Token::Paren('(')) {
@@ -4343,7 +4479,8 @@ impl Parser {
                 self.lookup_type.insert(name.to_owned(), ty);
                 lexer.expect(Token::Separator(';'))?;
             }
-            (Token::Word("let"), _) => {
+            (Token::Word(keyword @ ("let" | "const" | "override")), _) => {
+                let is_override = keyword == "override";
                 let (name, name_span) = lexer.next_ident_with_span()?;
                 if crate::keywords::wgsl::RESERVED.contains(&name) {
                     return Err(Error::ReservedKeyword(name_span));
@@ -4371,6 +4508,18 @@ impl Parser {
 
                 lexer.expect(Token::Operation('='))?;
                 let first_token_span = lexer.next();
+                // Overrides are represented as specialization constants, so
+                // they need an identifier for the pipeline to refer to them,
+                // and a scalar default value of their own.
+                if is_override {
+                    if override_id.is_none() {
+                        return Err(Error::MissingAttribute("id", name_span));
+                    }
+                    match first_token_span {
+                        (Token::Number { .. } | Token::Word("true" | "false"), _) => {}
+                        other => return Err(Error::Unexpected(other, ExpectedToken::Constant)),
+                    }
+                }
                 let const_handle = self.parse_const_expression_impl(
                     first_token_span,
                     lexer,
@@ -4378,6 +4527,9 @@ impl Parser {
                     &mut module.types,
                     &mut module.constants,
                 )?;
+                if is_override {
+                    module.constants.get_mut(const_handle).specialization = override_id;
+                }
 
                 if let Some(explicit_ty) = given_ty {
                     let con = &module.constants[const_handle];
@@ -153,6 +153,71 @@ fn parse_type_cast() {
     .is_err());
 }
 
+#[test]
+fn parse_bitcast() {
+    parse_str(
+        "
+        fn main() {
+            let f: f32 = 1.0;
+            let u: u32 = bitcast<u32>(f);
+            let v: vec2<i32> = bitcast<vec2<i32>>(vec2<u32>(u, u));
+        }
+    ",
+    )
+    .unwrap();
+    // The number of components must match.
+    assert!(parse_str(
+        "
+        fn main() {
+            let v: vec2<u32> = bitcast<vec2<u32>>(1.0);
+        }
+    ",
+    )
+    .is_err());
+}
+
+#[test]
+fn parse_builtins() {
+    let module = parse_str(
+        "
+        struct FragmentOutput {
+            @location(0) color: vec4<f32>,
+            @builtin(sample_mask) mask: u32,
+        }
+
+        @fragment
+        fn main(
+            @builtin(primitive_index) primitive: u32,
+            @builtin(sample_mask) mask: u32,
+        ) -> FragmentOutput {
+            return FragmentOutput(vec4<f32>(f32(primitive)), mask);
+        }
+    ",
+    )
+    .unwrap();
+    let function = &module.entry_points[0].function;
+    let bindings: Vec<_> = function
+        .arguments
+        .iter()
+        .map(|arg| arg.binding.clone())
+        .collect();
+    assert_eq!(
+        bindings,
+        vec![
+            Some(crate::Binding::BuiltIn(crate::BuiltIn::PrimitiveIndex)),
+            Some(crate::Binding::BuiltIn(crate::BuiltIn::SampleMask)),
+        ]
+    );
+    let result_ty = function.result.as_ref().unwrap().ty;
+    match module.types[result_ty].inner {
+        crate::TypeInner::Struct { ref members, .. } => assert_eq!(
+            members[1].binding,
+            Some(crate::Binding::BuiltIn(crate::BuiltIn::SampleMask))
+        ),
+        ref other => panic!("Unexpected result type {:?}", other),
+    }
+}
+
 #[test]
 fn parse_struct() {
     parse_str(
@@ -526,3 +591,67 @@ fn parse_storage_buffers() {
     )
     .unwrap();
 }
+
+#[test]
+fn parse_overrides() {
+    let module = parse_str(
+        "
+        @id(0) override SIZE: u32 = 64u;
+        @id(7) override SCALE = 1.5;
+        var<workgroup> data: array<f32, SIZE>;
+    ",
+    )
+    .unwrap();
+    let specializations: Vec<_> = module
+        .constants
+        .iter()
+        .filter(|&(_, constant)| constant.name.is_some())
+        .map(|(_, constant)| constant.specialization)
+        .collect();
+    assert_eq!(specializations, [Some(0), Some(7)]);
+
+    let (_, data) = module.global_variables.iter().next().unwrap();
+    match module.types[data.ty].inner {
+        crate::TypeInner::Array {
+            size: crate::ArraySize::Constant(size),
+            ..
+        } => assert_eq!(module.constants[size].specialization, Some(0)),
+        ref other => panic!("Unexpected type {:?}", other),
+    }
+
+    // The pipeline refers to overrides by their identifier.
+    assert!(parse_str("override SIZE: u32 = 64u;").is_err());
+    // The default value has to be a literal.
+    assert!(parse_str("let A = 1u; @id(0) override SIZE = A;").is_err());
+}
+
+#[test]
+fn parse_block_scopes() {
+    // Inner declarations shadow the outer ones until the end of their block.
+    parse_str(
+        "
+        fn f() -> i32 {
+            let x = 1;
+            {
+                let x = 2.0;
+                {
+                    var x = vec2<f32>(x, x);
+                }
+                let y: f32 = x;
+            }
+            for (var i = 0; i < x; i = i + 1) {
+                let x = true;
+            }
+            let i: i32 = x;
+            return x;
+        }
+    ",
+    )
+    .unwrap();
+    assert!(parse_str("fn f() { let x = 1; { let x = 2.0; } let y: f32 = x; }").is_err());
+    assert!(
+        parse_str("fn f() { let x = 1; loop { let x = 2; continuing { let y = x; } } }").is_ok()
+    );
+    // The same block can't declare a name twice.
+    assert!(parse_str("fn f() { let x = 1; var x = 2; }").is_err());
+}
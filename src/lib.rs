@@ -241,6 +241,7 @@ pub(crate) type NamedExpressions = FastHashMap<Handle<Expression>, String>;
 /// To use in a shader:
 ///   - GLSL: `layout(early_fragment_tests) in;`
 ///   - HLSL: `Attribute earlydepthstencil`
+///   - MSL: `[[early_fragment_tests]]`
 ///   - SPIR-V: `ExecutionMode EarlyFragmentTests`
 ///
 /// For more, see:
@@ -1352,6 +1353,13 @@ pub enum Expression {
     /// Result of calling another function.
     CallResult(Handle<Function>),
     /// Result of an atomic operation.
+    ///
+    /// This is a scalar holding the original value of the atomic, unless
+    /// `comparison` is true. The result of an [`Exchange`] with a `compare`
+    /// operand is instead a two-component vector, holding the original value
+    /// and whether the exchange took place (as zero or one).
+    ///
+    /// [`Exchange`]: AtomicFunction::Exchange
     AtomicResult {
         kind: ScalarKind,
         width: Bytes,
@@ -1394,6 +1402,18 @@ pub struct SwitchCase {
     pub fall_through: bool,
 }
 
+/// A hint about which way a conditional branch usually goes.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "deserialize", derive(Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(Arbitrary))]
+pub enum BranchHint {
+    /// The condition is usually true.
+    Likely,
+    /// The condition is usually false.
+    Unlikely,
+}
+
 //TODO: consider removing `Clone`. It's not valid to clone `Statement::Emit` anyway.
 /// Instructions which make up an executable block.
 // Clone is used only for error reporting and is not intended for end users
@@ -1411,10 +1431,16 @@ pub enum Statement {
     /// A block containing more statements, to be executed sequentially.
     Block(Block),
     /// Conditionally executes one of two blocks, based on the value of the condition.
+    ///
+    /// Backends may use the `hint`, if any, to lay out the code for the
+    /// expected outcome.
     If {
         condition: Handle<Expression>, //bool
         accept: Block,
         reject: Block,
+        #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+        #[cfg_attr(feature = "deserialize", serde(default))]
+        hint: Option<BranchHint>,
     },
     /// Conditionally executes one of multiple blocks, based on the value of the selector.
     Switch {
@@ -0,0 +1,103 @@
+use super::strip::{remap_type_inner, remap_types};
+use crate::{ArraySize, Constant, ConstantInner, ScalarValue, TypeInner};
+
+/// Make the constant array sizes of the module refer to plain literals.
+///
+/// The sizes of `Array` and `BindingArray` types that refer to a named or
+/// signed integer constant are replaced with an unnamed `u32` constant of the
+/// same value, so backends can write them out as literals. Sizes given by
+/// specialization constants may be overridden by the pipeline, so they are
+/// left as they are, and so are runtime-sized arrays.
+///
+/// Types that only differed by the constant of their size are merged.
+pub fn resolve_array_sizes(module: &mut crate::Module) {
+    let old_types = std::mem::take(&mut module.types);
+    let mut type_map = Vec::with_capacity(old_types.len());
+    for (handle, ty) in old_types.iter() {
+        let mut inner = remap_type_inner(&ty.inner, &type_map);
+        match inner {
+            TypeInner::Array { ref mut size, .. }
+            | TypeInner::BindingArray { ref mut size, .. } => {
+                if let ArraySize::Constant(ref mut constant) = *size {
+                    let resolved = &module.constants[*constant];
+                    if let (None, Some(length)) =
+                        (resolved.specialization, resolved.to_array_length())
+                    {
+                        *constant = module.constants.fetch_or_append(
+                            Constant {
+                                name: None,
+                                specialization: None,
+                                inner: ConstantInner::Scalar {
+                                    width: 4,
+                                    value: ScalarValue::Uint(length as u64),
+                                },
+                            },
+                            module.constants.get_span(*constant),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+        let new_handle = module.types.insert(
+            crate::Type {
+                name: ty.name.clone(),
+                inner,
+            },
+            old_types.get_span(handle),
+        );
+        type_map.push(new_handle);
+    }
+    remap_types(module, &type_map);
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_resolve_array_sizes() {
+    let source = "
+        let SIZE: i32 = 4;
+
+        var<private> named: array<f32, SIZE>;
+        var<private> literal: array<f32, 4>;
+        var<private> large: array<f32, 6u>;
+
+        fn main() -> f32 {
+            return named[1] + literal[2];
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    let type_count = module.types.len();
+    resolve_array_sizes(&mut module);
+
+    let sizes: Vec<_> = module
+        .global_variables
+        .iter()
+        .map(|(_, var)| match module.types[var.ty].inner {
+            TypeInner::Array {
+                size: ArraySize::Constant(constant),
+                ..
+            } => {
+                let constant = &module.constants[constant];
+                assert!(constant.name.is_none());
+                match constant.inner {
+                    ConstantInner::Scalar {
+                        value: ScalarValue::Uint(length),
+                        ..
+                    } => length,
+                    ref other => panic!("Unexpected array size {:?}", other),
+                }
+            }
+            ref other => panic!("Unexpected type {:?}", other),
+        })
+        .collect();
+    assert_eq!(sizes, [4, 4, 6]);
+    // `array<f32, SIZE>` and `array<f32, 4>` are the same type now.
+    assert_eq!(module.types.len(), type_count - 1);
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
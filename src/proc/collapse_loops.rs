@@ -0,0 +1,182 @@
+use crate::{Block, Statement};
+
+/// Replace the loops of the function that never iterate more than once with
+/// their body.
+///
+/// A loop is collapsed when every path through its body ends with a `Break`,
+/// a `Return` or a `Kill`, the body has no other `Break` or `Continue` of
+/// that loop, and its `continuing` block is empty. The loop then runs its
+/// body exactly once, so the body is spliced in place of the loop, without
+/// the trailing `Break` statements. Inner loops are processed first, so nested
+/// once-loops are collapsed all at once.
+///
+/// The function needs to be validated again afterwards.
+pub fn collapse_single_iteration_loops(fun: &mut crate::Function) {
+    collapse_block(&mut fun.body);
+}
+
+fn collapse_block(block: &mut Block) {
+    let mut index = 0;
+    while index < block.len() {
+        let collapsed = match block[index] {
+            Statement::Block(ref mut b) => {
+                collapse_block(b);
+                None
+            }
+            Statement::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                collapse_block(accept);
+                collapse_block(reject);
+                None
+            }
+            Statement::Switch { ref mut cases, .. } => {
+                for case in cases.iter_mut() {
+                    collapse_block(&mut case.body);
+                }
+                None
+            }
+            Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                collapse_block(body);
+                collapse_block(continuing);
+                if continuing.is_empty() && !has_continue(body) {
+                    let mut straight = body.clone();
+                    if strip_tail_breaks(&mut straight) && !has_break(&straight) {
+                        Some(straight)
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        match collapsed {
+            Some(body) => {
+                let len = body.len();
+                block.splice(index..index + 1, body);
+                index += len;
+            }
+            None => index += 1,
+        }
+    }
+}
+
+/// Remove the `Break` statements ending the paths through `block`, returning
+/// whether all of them end with a `Break`, a `Return` or a `Kill`.
+fn strip_tail_breaks(block: &mut Block) -> bool {
+    let last = match block.len().checked_sub(1) {
+        Some(last) => last,
+        None => return false,
+    };
+    match block[last] {
+        Statement::Break => {
+            block.cull(last..);
+            true
+        }
+        Statement::Return { .. } | Statement::Kill => true,
+        Statement::Block(ref mut b) => strip_tail_breaks(b),
+        Statement::If {
+            ref mut accept,
+            ref mut reject,
+            ..
+        } => strip_tail_breaks(accept) && strip_tail_breaks(reject),
+        _ => false,
+    }
+}
+
+/// Return whether `block` breaks out of the loop it belongs to.
+fn has_break(block: &Block) -> bool {
+    block.iter().any(|statement| match *statement {
+        Statement::Break => true,
+        Statement::Block(ref b) => has_break(b),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => has_break(accept) || has_break(reject),
+        // Breaks in switches and inner loops leave those instead.
+        _ => false,
+    })
+}
+
+/// Return whether `block` continues the loop it belongs to.
+fn has_continue(block: &Block) -> bool {
+    block.iter().any(|statement| match *statement {
+        Statement::Continue => true,
+        Statement::Block(ref b) => has_continue(b),
+        Statement::If {
+            ref accept,
+            ref reject,
+            ..
+        } => has_continue(accept) || has_continue(reject),
+        Statement::Switch { ref cases, .. } => cases.iter().any(|case| has_continue(&case.body)),
+        // Continues in inner loops continue those instead.
+        _ => false,
+    })
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_collapse_single_iteration_loops() {
+    let mut module = crate::front::wgsl::parse_str(
+        "
+        fn main(value: f32) -> f32 {
+            var x: f32 = 0.0;
+            loop {
+                x = value;
+                break;
+            }
+            loop {
+                if x > 1.0 {
+                    x = 1.0;
+                    break;
+                } else {
+                    return x;
+                }
+            }
+            loop {
+                if x > 2.0 {
+                    break;
+                }
+                x = x + 1.0;
+                break;
+            }
+            loop {
+                if x > 3.0 {
+                    continue;
+                }
+                break;
+            }
+            return x;
+        }
+        ",
+    )
+    .unwrap();
+
+    let loops = |block: &Block| {
+        block
+            .iter()
+            .filter(|statement| matches!(**statement, Statement::Loop { .. }))
+            .count()
+    };
+    let (_, main) = module.functions.iter_mut().next().unwrap();
+    assert_eq!(loops(&main.body), 4);
+    collapse_single_iteration_loops(main);
+    // The last two loops break early or may iterate again.
+    assert_eq!(loops(&main.body), 2);
+    assert!(!has_break(&main.body));
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
@@ -0,0 +1,574 @@
+use crate::arena::{Handle, Range};
+use crate::{Block, Expression, FastHashMap, Statement};
+
+/// Deduplicate structurally identical pure expressions in the function.
+///
+/// An expression is replaced with an earlier one if it has the same operation
+/// and the same operands, and the earlier expression has been evaluated on
+/// every path that reaches it, e.g. in the same block or in an enclosing
+/// one. Expressions that read memory, depend on the control flow, or are
+/// produced by calls and atomics are never merged.
+///
+/// The duplicates are left in the arena, but they are no longer emitted or
+/// referenced. The function needs to be validated again afterwards.
+pub fn eliminate_common_subexpressions(fun: &mut crate::Function) {
+    let mut remap: Vec<Handle<Expression>> = fun.expressions.iter().map(|(h, _)| h).collect();
+
+    // Pure expressions that don't need to be emitted are available everywhere.
+    let mut root = FastHashMap::default();
+    for (handle, expr) in fun.expressions.iter() {
+        if needs_emit(expr) {
+            continue;
+        }
+        if let Some(key) = ExpressionKey::new(expr) {
+            match root.get(&key) {
+                Some(&existing) => remap[handle.index()] = existing,
+                None => {
+                    root.insert(key, handle);
+                }
+            }
+        }
+    }
+
+    let mut deduplicator = Deduplicator {
+        expressions: &fun.expressions,
+        remap,
+        scopes: vec![root],
+    };
+    deduplicator.block(&mut fun.body);
+    let remap = deduplicator.remap;
+
+    for (_, expr) in fun.expressions.iter_mut() {
+        remap_expression(expr, &remap);
+    }
+    remap_block(&mut fun.body, &remap);
+
+    let old_names = std::mem::take(&mut fun.named_expressions);
+    let (kept, merged): (Vec<_>, Vec<_>) = old_names
+        .into_iter()
+        .partition(|&(handle, _)| remap[handle.index()] == handle);
+    fun.named_expressions.extend(kept);
+    for (handle, name) in merged {
+        fun.named_expressions
+            .entry(remap[handle.index()])
+            .or_insert(name);
+    }
+}
+
+struct Deduplicator<'a> {
+    expressions: &'a crate::Arena<Expression>,
+    /// The expression each handle should be replaced with.
+    remap: Vec<Handle<Expression>>,
+    /// The expressions known to be evaluated, for each level of nesting.
+    scopes: Vec<FastHashMap<ExpressionKey, Handle<Expression>>>,
+}
+
+/// The operation and operands of a pure expression, to find structurally
+/// identical ones.
+#[derive(Hash, Eq, PartialEq)]
+enum ExpressionKey {
+    Access(Handle<Expression>, Handle<Expression>),
+    AccessIndex(Handle<Expression>, u32),
+    Constant(Handle<crate::Constant>),
+    Splat(crate::VectorSize, Handle<Expression>),
+    Swizzle(crate::VectorSize, Handle<Expression>, [u8; 4]),
+    Compose(Handle<crate::Type>, Vec<Handle<Expression>>),
+    FunctionArgument(u32),
+    GlobalVariable(Handle<crate::GlobalVariable>),
+    LocalVariable(Handle<crate::LocalVariable>),
+    /// The image, the index of the query kind, and the level of size queries.
+    ImageQuery(Handle<Expression>, u8, Option<Handle<Expression>>),
+    Unary(crate::UnaryOperator, Handle<Expression>),
+    Binary(
+        crate::BinaryOperator,
+        Handle<Expression>,
+        Handle<Expression>,
+    ),
+    Select(Handle<Expression>, Handle<Expression>, Handle<Expression>),
+    Relational(crate::RelationalFunction, Handle<Expression>),
+    Math(
+        crate::MathFunction,
+        Handle<Expression>,
+        [Option<Handle<Expression>>; 3],
+    ),
+    As(Handle<Expression>, crate::ScalarKind, Option<crate::Bytes>),
+    ArrayLength(Handle<Expression>),
+}
+
+impl ExpressionKey {
+    /// Return the key of `expr`, or `None` if it isn't pure.
+    fn new(expr: &Expression) -> Option<Self> {
+        Some(match *expr {
+            Expression::Access { base, index } => Self::Access(base, index),
+            Expression::AccessIndex { base, index } => Self::AccessIndex(base, index),
+            Expression::Constant(constant) => Self::Constant(constant),
+            Expression::Splat { size, value } => Self::Splat(size, value),
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => Self::Swizzle(size, vector, pattern.map(|component| component as u8)),
+            Expression::Compose { ty, ref components } => Self::Compose(ty, components.clone()),
+            Expression::FunctionArgument(index) => Self::FunctionArgument(index),
+            Expression::GlobalVariable(var) => Self::GlobalVariable(var),
+            Expression::LocalVariable(var) => Self::LocalVariable(var),
+            Expression::ImageQuery { image, query } => match query {
+                crate::ImageQuery::Size { level } => Self::ImageQuery(image, 0, level),
+                crate::ImageQuery::NumLevels => Self::ImageQuery(image, 1, None),
+                crate::ImageQuery::NumLayers => Self::ImageQuery(image, 2, None),
+                crate::ImageQuery::NumSamples => Self::ImageQuery(image, 3, None),
+            },
+            Expression::Unary { op, expr } => Self::Unary(op, expr),
+            Expression::Binary { op, left, right } => Self::Binary(op, left, right),
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => Self::Select(condition, accept, reject),
+            Expression::Relational { fun, argument } => Self::Relational(fun, argument),
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => Self::Math(fun, arg, [arg1, arg2, arg3]),
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => Self::As(expr, kind, convert),
+            Expression::ArrayLength(expr) => Self::ArrayLength(expr),
+            Expression::Load { .. }
+            | Expression::ImageSample { .. }
+            | Expression::ImageLoad { .. }
+            | Expression::Derivative { .. }
+            | Expression::CallResult(_)
+            | Expression::AtomicResult { .. } => return None,
+        })
+    }
+}
+
+impl Deduplicator<'_> {
+    fn block(&mut self, block: &mut Block) {
+        self.scopes.push(FastHashMap::default());
+
+        let mut index = 0;
+        while index < block.len() {
+            match block[index] {
+                Statement::Emit(ref range) => {
+                    let runs = self.emit(range.clone());
+                    let count = runs.len();
+                    let span = block.span_iter().nth(index).map(|(_, &span)| span);
+                    let mut replacement = Block::with_capacity(count);
+                    for run in runs {
+                        replacement.push(Statement::Emit(run), span.unwrap_or_default());
+                    }
+                    block.splice(index..index + 1, replacement);
+                    index += count;
+                    continue;
+                }
+                Statement::Block(ref mut b) => self.block(b),
+                Statement::If {
+                    ref mut accept,
+                    ref mut reject,
+                    ..
+                } => {
+                    self.block(accept);
+                    self.block(reject);
+                }
+                Statement::Switch { ref mut cases, .. } => {
+                    // Cases can be entered directly, even when the previous
+                    // one falls through.
+                    for case in cases.iter_mut() {
+                        self.block(&mut case.body);
+                    }
+                }
+                Statement::Loop {
+                    ref mut body,
+                    ref mut continuing,
+                } => {
+                    // The continuing block can be reached by a `continue`
+                    // before the rest of the body is evaluated.
+                    self.block(body);
+                    self.block(continuing);
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+
+        self.scopes.pop();
+    }
+
+    /// Deduplicate the expressions in `range`, and return the ranges of the
+    /// ones that still need to be emitted.
+    fn emit(&mut self, range: Range<Expression>) -> Vec<Range<Expression>> {
+        let mut runs = Vec::new();
+        let mut run = None;
+        for handle in range {
+            if self.deduplicate(handle) {
+                if let Some((first, last)) = run.take() {
+                    runs.push(Range::new_from_bounds(first, last));
+                }
+            } else {
+                run = Some(match run {
+                    Some((first, _)) => (first, handle),
+                    None => (handle, handle),
+                });
+            }
+        }
+        if let Some((first, last)) = run {
+            runs.push(Range::new_from_bounds(first, last));
+        }
+        runs
+    }
+
+    /// Return true if `handle` is a duplicate of an available expression.
+    fn deduplicate(&mut self, handle: Handle<Expression>) -> bool {
+        let mut expr = self.expressions[handle].clone();
+        remap_expression(&mut expr, &self.remap);
+        let key = match ExpressionKey::new(&expr) {
+            Some(key) => key,
+            None => return false,
+        };
+
+        let existing = self.scopes.iter().rev().find_map(|scope| scope.get(&key));
+        match existing {
+            Some(&existing) if existing.index() < handle.index() => {
+                self.remap[handle.index()] = existing;
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.scopes.last_mut().unwrap().insert(key, handle);
+                false
+            }
+        }
+    }
+}
+
+/// Return true if the expression needs to be covered by an `Emit` statement.
+pub(super) const fn needs_emit(expr: &Expression) -> bool {
+    !matches!(
+        *expr,
+        Expression::Constant(_)
+            | Expression::FunctionArgument(_)
+            | Expression::GlobalVariable(_)
+            | Expression::LocalVariable(_)
+            | Expression::CallResult(_)
+            | Expression::AtomicResult { .. }
+    )
+}
+
+/// Return true if evaluating the expression twice always gives the same
+/// result, regardless of what happened in between.
+pub(super) const fn is_pure(expr: &Expression) -> bool {
+    match *expr {
+        Expression::Access { .. }
+        | Expression::AccessIndex { .. }
+        | Expression::Constant(_)
+        | Expression::Splat { .. }
+        | Expression::Swizzle { .. }
+        | Expression::Compose { .. }
+        | Expression::FunctionArgument(_)
+        | Expression::GlobalVariable(_)
+        | Expression::LocalVariable(_)
+        | Expression::ImageQuery { .. }
+        | Expression::Unary { .. }
+        | Expression::Binary { .. }
+        | Expression::Select { .. }
+        | Expression::Relational { .. }
+        | Expression::Math { .. }
+        | Expression::As { .. }
+        | Expression::ArrayLength(_) => true,
+        // Loads and atomics depend on the memory state, while sampling with
+        // implicit derivatives and derivatives depend on the control flow.
+        Expression::Load { .. }
+        | Expression::ImageSample { .. }
+        | Expression::ImageLoad { .. }
+        | Expression::Derivative { .. }
+        | Expression::CallResult(_)
+        | Expression::AtomicResult { .. } => false,
+    }
+}
+
+pub(super) fn remap_expression(expr: &mut Expression, remap: &[Handle<Expression>]) {
+    for_each_operand_mut(expr, |handle| *handle = remap[handle.index()]);
+}
+
+/// Call `adjust` on every expression handle that `expr` refers to.
+pub(super) fn for_each_operand_mut(
+    expr: &mut Expression,
+    mut adjust: impl FnMut(&mut Handle<Expression>),
+) {
+    match *expr {
+        Expression::Access {
+            ref mut base,
+            ref mut index,
+        } => {
+            adjust(base);
+            adjust(index);
+        }
+        Expression::AccessIndex { ref mut base, .. } => adjust(base),
+        Expression::Splat { ref mut value, .. } => adjust(value),
+        Expression::Swizzle { ref mut vector, .. } => adjust(vector),
+        Expression::Compose {
+            ref mut components, ..
+        } => components.iter_mut().for_each(&mut adjust),
+        Expression::Load { ref mut pointer } => adjust(pointer),
+        Expression::ImageSample {
+            ref mut image,
+            ref mut sampler,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut level,
+            ref mut depth_ref,
+            ..
+        } => {
+            adjust(image);
+            adjust(sampler);
+            adjust(coordinate);
+            array_index.iter_mut().for_each(&mut adjust);
+            match *level {
+                crate::SampleLevel::Auto | crate::SampleLevel::Zero => {}
+                crate::SampleLevel::Exact(ref mut expr)
+                | crate::SampleLevel::Bias(ref mut expr) => adjust(expr),
+                crate::SampleLevel::Gradient {
+                    ref mut x,
+                    ref mut y,
+                } => {
+                    adjust(x);
+                    adjust(y);
+                }
+            }
+            depth_ref.iter_mut().for_each(&mut adjust);
+        }
+        Expression::ImageLoad {
+            ref mut image,
+            ref mut coordinate,
+            ref mut array_index,
+            ref mut sample,
+            ref mut level,
+        } => {
+            adjust(image);
+            adjust(coordinate);
+            array_index.iter_mut().for_each(&mut adjust);
+            sample.iter_mut().for_each(&mut adjust);
+            level.iter_mut().for_each(&mut adjust);
+        }
+        Expression::ImageQuery {
+            ref mut image,
+            ref mut query,
+        } => {
+            adjust(image);
+            if let crate::ImageQuery::Size {
+                level: Some(ref mut level),
+            } = *query
+            {
+                adjust(level);
+            }
+        }
+        Expression::Unary { ref mut expr, .. }
+        | Expression::Derivative { ref mut expr, .. }
+        | Expression::As { ref mut expr, .. } => adjust(expr),
+        Expression::Binary {
+            ref mut left,
+            ref mut right,
+            ..
+        } => {
+            adjust(left);
+            adjust(right);
+        }
+        Expression::Select {
+            ref mut condition,
+            ref mut accept,
+            ref mut reject,
+        } => {
+            adjust(condition);
+            adjust(accept);
+            adjust(reject);
+        }
+        Expression::Relational {
+            ref mut argument, ..
+        } => adjust(argument),
+        Expression::Math {
+            ref mut arg,
+            ref mut arg1,
+            ref mut arg2,
+            ref mut arg3,
+            ..
+        } => {
+            adjust(arg);
+            arg1.iter_mut().for_each(&mut adjust);
+            arg2.iter_mut().for_each(&mut adjust);
+            arg3.iter_mut().for_each(&mut adjust);
+        }
+        Expression::ArrayLength(ref mut expr) => adjust(expr),
+        Expression::Constant(_)
+        | Expression::FunctionArgument(_)
+        | Expression::GlobalVariable(_)
+        | Expression::LocalVariable(_)
+        | Expression::CallResult(_)
+        | Expression::AtomicResult { .. } => {}
+    }
+}
+
+pub(super) fn remap_block(block: &mut Block, remap: &[Handle<Expression>]) {
+    let adjust = |handle: &mut Handle<Expression>| *handle = remap[handle.index()];
+    for statement in block.iter_mut() {
+        match *statement {
+            Statement::Emit(_)
+            | Statement::Break
+            | Statement::Continue
+            | Statement::Kill
+            | Statement::Barrier(_) => {}
+            Statement::Block(ref mut b) => remap_block(b, remap),
+            Statement::If {
+                ref mut condition,
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                adjust(condition);
+                remap_block(accept, remap);
+                remap_block(reject, remap);
+            }
+            Statement::Switch {
+                ref mut selector,
+                ref mut cases,
+            } => {
+                adjust(selector);
+                for case in cases.iter_mut() {
+                    remap_block(&mut case.body, remap);
+                }
+            }
+            Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                remap_block(body, remap);
+                remap_block(continuing, remap);
+            }
+            Statement::Return { ref mut value } => value.iter_mut().for_each(adjust),
+            Statement::Store {
+                ref mut pointer,
+                ref mut value,
+            } => {
+                adjust(pointer);
+                adjust(value);
+            }
+            Statement::ImageStore {
+                ref mut image,
+                ref mut coordinate,
+                ref mut array_index,
+                ref mut value,
+            } => {
+                adjust(image);
+                adjust(coordinate);
+                array_index.iter_mut().for_each(adjust);
+                adjust(value);
+            }
+            Statement::Atomic {
+                ref mut pointer,
+                ref mut fun,
+                ref mut value,
+                ref mut result,
+            } => {
+                adjust(pointer);
+                if let crate::AtomicFunction::Exchange {
+                    compare: Some(ref mut compare),
+                } = *fun
+                {
+                    adjust(compare);
+                }
+                adjust(value);
+                adjust(result);
+            }
+            Statement::Call {
+                ref mut arguments,
+                ref mut result,
+                ..
+            } => {
+                arguments.iter_mut().for_each(adjust);
+                result.iter_mut().for_each(adjust);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_eliminate_common_subexpressions() {
+    fn count_emitted(block: &Block, fun: &crate::Function, count: &mut usize) {
+        for statement in block.iter() {
+            match *statement {
+                Statement::Emit(ref range) => {
+                    *count += range
+                        .clone()
+                        .filter(|&h| matches!(fun.expressions[h], Expression::Binary { .. }))
+                        .count();
+                }
+                Statement::Block(ref b) => count_emitted(b, fun, count),
+                Statement::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    count_emitted(accept, fun, count);
+                    count_emitted(reject, fun, count);
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    count_emitted(body, fun, count);
+                    count_emitted(continuing, fun, count);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let source = "
+        @group(0) @binding(0)
+        var<storage, read_write> buffer: array<f32, 4>;
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            let a = index * 2u;
+            let b = index * 2u;
+            buffer[a] = buffer[a] + 1.0;
+            if index > 1u {
+                // Available from the enclosing block.
+                let c = index * 2u;
+                buffer[c] = buffer[c + 1u] + 2.0;
+            } else {
+                buffer[index + 1u] = 3.0;
+            }
+            // Not available: only evaluated in the branches.
+            let d = index + 1u;
+            buffer[b] = buffer[d];
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    let ep = &mut module.entry_points[0];
+
+    let mut before = 0;
+    count_emitted(&ep.function.body, &ep.function, &mut before);
+    eliminate_common_subexpressions(&mut ep.function);
+    let mut after = 0;
+    count_emitted(&ep.function.body, &ep.function, &mut after);
+
+    // `b` and `c` duplicate `a`, but `d` has to stay. The additions to the
+    // loaded values are different expressions, since loads never merge.
+    assert_eq!(before, 9);
+    assert_eq!(after, 7);
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
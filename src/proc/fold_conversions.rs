@@ -0,0 +1,168 @@
+use super::cse::{remap_block, remap_expression};
+use super::hoist::remove_emitted;
+use crate::arena::Handle;
+use crate::front::Typifier;
+use crate::{Expression, TypeInner};
+use bit_set::BitSet;
+
+/// Fold the `As` expressions of the module that have no effect.
+///
+/// A conversion or bitcast to the kind and width its operand already has is
+/// replaced with the operand, and so is a bitcast of a bitcast back to the
+/// kind of the original value. Value conversions otherwise change the value
+/// they convert in general, e.g. `f32(i32(x))` rounds `x` towards zero, so
+/// chains of them are left as they are.
+///
+/// The folded expressions are left in the arenas, but they are no longer
+/// emitted or referenced. The module needs to be validated again afterwards.
+pub fn fold_conversions(module: &mut crate::Module) {
+    let handles: Vec<_> = module.functions.iter().map(|(handle, _)| handle).collect();
+    for handle in handles {
+        if let Some(typifier) = resolve_types(module, &module.functions[handle]) {
+            fold_function(&mut module.functions[handle], &module.types, &typifier);
+        }
+    }
+    for index in 0..module.entry_points.len() {
+        let fun = &module.entry_points[index].function;
+        if let Some(typifier) = resolve_types(module, fun) {
+            fold_function(
+                &mut module.entry_points[index].function,
+                &module.types,
+                &typifier,
+            );
+        }
+    }
+}
+
+/// Resolve the expression types of `fun`, if it has any `As` expressions.
+fn resolve_types(module: &crate::Module, fun: &crate::Function) -> Option<Typifier> {
+    super::resolve_types(module, fun, |expr| matches!(*expr, Expression::As { .. }))
+}
+
+fn fold_function(
+    fun: &mut crate::Function,
+    types: &crate::UniqueArena<crate::Type>,
+    typifier: &Typifier,
+) {
+    let scalar = |handle: Handle<Expression>| match *typifier.get(handle, types) {
+        TypeInner::Scalar { kind, width } | TypeInner::Vector { kind, width, .. } => {
+            Some((kind, width))
+        }
+        _ => None,
+    };
+
+    let mut remap: Vec<Handle<Expression>> = fun.expressions.iter().map(|(h, _)| h).collect();
+    let mut folded = BitSet::new();
+    // Operands come before the expressions using them, so the chains are
+    // folded from the inside out in a single pass.
+    for (handle, expr) in fun.expressions.iter() {
+        let (expr, kind, convert) = match *expr {
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => (remap[expr.index()], kind, convert),
+            _ => continue,
+        };
+        let (operand_kind, operand_width) = match scalar(expr) {
+            Some(scalar) => scalar,
+            None => continue,
+        };
+
+        let replacement = if kind == operand_kind && convert.map_or(true, |w| w == operand_width) {
+            Some(expr)
+        } else {
+            match fun.expressions[expr] {
+                Expression::As {
+                    expr: inner,
+                    convert: None,
+                    ..
+                } if convert.is_none() && scalar(inner) == Some((kind, operand_width)) => {
+                    Some(remap[inner.index()])
+                }
+                _ => None,
+            }
+        };
+        if let Some(replacement) = replacement {
+            remap[handle.index()] = replacement;
+            folded.insert(handle.index());
+        }
+    }
+
+    if folded.is_empty() {
+        return;
+    }
+    for (_, expr) in fun.expressions.iter_mut() {
+        remap_expression(expr, &remap);
+    }
+    remap_block(&mut fun.body, &remap);
+    remove_emitted(&mut fun.body, &folded);
+
+    let old_names = std::mem::take(&mut fun.named_expressions);
+    let (kept, renamed): (Vec<_>, Vec<_>) = old_names
+        .into_iter()
+        .partition(|&(handle, _)| remap[handle.index()] == handle);
+    fun.named_expressions.extend(kept);
+    for (handle, name) in renamed {
+        fun.named_expressions
+            .entry(remap[handle.index()])
+            .or_insert(name);
+    }
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_fold_conversions() {
+    let source = "
+        fn main(x: u32, y: f32) -> vec4<f32> {
+            let a = bitcast<u32>(bitcast<f32>(x));
+            let c = f32(i32(y));
+            let d = bitcast<vec2<i32>>(bitcast<vec2<u32>>(vec2<i32>(1, 2)));
+            return vec4<f32>(f32(a), y, c, f32(d.x));
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    fold_conversions(&mut module);
+
+    let (_, fun) = module.functions.iter().next().unwrap();
+    let mut converts = Vec::new();
+    for statement in fun.body.iter() {
+        if let crate::Statement::Emit(ref range) = *statement {
+            for handle in range.clone() {
+                if let Expression::As { kind, convert, .. } = fun.expressions[handle] {
+                    converts.push((kind, convert));
+                }
+            }
+        }
+    }
+    // The outer bitcasts are gone, the inner ones are left unreferenced, and
+    // the round trip through `i32` is kept.
+    use crate::ScalarKind as Sk;
+    assert_eq!(
+        converts,
+        vec![
+            (Sk::Float, None),
+            (Sk::Sint, Some(4)),
+            (Sk::Float, Some(4)),
+            (Sk::Uint, None),
+            (Sk::Float, Some(4)),
+            (Sk::Float, Some(4)),
+        ]
+    );
+    // `f32(a)` converts `x` directly.
+    assert!(fun.expressions.iter().any(|(_, expr)| match *expr {
+        Expression::As {
+            expr,
+            kind: Sk::Float,
+            convert: Some(4),
+        } => matches!(fun.expressions[expr], Expression::FunctionArgument(0)),
+        _ => false,
+    }));
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
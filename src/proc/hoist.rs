@@ -0,0 +1,372 @@
+use super::cse::{for_each_operand_mut, is_pure, needs_emit};
+use crate::arena::{Handle, Range};
+use crate::{Arena, Block, Expression, Statement};
+use bit_set::BitSet;
+
+/// Move loop-invariant expressions out of the loops of the function.
+///
+/// An expression evaluated inside a `Loop` is loop-invariant if it is pure and
+/// all its operands are either evaluated before the loop, or loop-invariant
+/// themselves. Such expressions are emitted right before the loop instead.
+/// Loads never move, so the values that the loop modifies are never assumed
+/// to be invariant. Divisions never move either, since an integer division
+/// by zero guarded by a condition in the loop would otherwise be evaluated
+/// unconditionally. Inner loops are processed first, so an expression can be
+/// hoisted out of several nested loops at once.
+///
+/// The function needs to be validated again afterwards.
+pub fn hoist_loop_invariants(fun: &mut crate::Function) {
+    let hoister = Hoister {
+        expressions: &fun.expressions,
+    };
+    hoister.block(&mut fun.body);
+}
+
+struct Hoister<'a> {
+    expressions: &'a Arena<Expression>,
+}
+
+impl Hoister<'_> {
+    fn block(&self, block: &mut Block) {
+        let mut index = 0;
+        while index < block.len() {
+            let span = block.span_iter().nth(index).map(|(_, &span)| span);
+            let hoisted = match block[index] {
+                Statement::Block(ref mut b) => {
+                    self.block(b);
+                    Vec::new()
+                }
+                Statement::If {
+                    ref mut accept,
+                    ref mut reject,
+                    ..
+                } => {
+                    self.block(accept);
+                    self.block(reject);
+                    Vec::new()
+                }
+                Statement::Switch { ref mut cases, .. } => {
+                    for case in cases.iter_mut() {
+                        self.block(&mut case.body);
+                    }
+                    Vec::new()
+                }
+                Statement::Loop {
+                    ref mut body,
+                    ref mut continuing,
+                } => {
+                    self.block(body);
+                    self.block(continuing);
+                    self.hoist(body, continuing)
+                }
+                _ => Vec::new(),
+            };
+
+            let count = hoisted.len();
+            if count != 0 {
+                let mut emits = Block::with_capacity(count);
+                for range in hoisted {
+                    emits.push(Statement::Emit(range), span.unwrap_or_default());
+                }
+                block.splice(index..index, emits);
+            }
+            index += count + 1;
+        }
+    }
+
+    /// Remove the loop-invariant expressions from the `body` and `continuing`
+    /// blocks of a loop, and return the ranges to emit before it.
+    fn hoist(&self, body: &mut Block, continuing: &mut Block) -> Vec<Range<Expression>> {
+        let mut variant = BitSet::with_capacity(self.expressions.len());
+        collect_defined(body, &mut variant);
+        collect_defined(continuing, &mut variant);
+
+        // Operands always come before the expressions using them, so a
+        // single pass in handle order is enough.
+        let mut invariant = Vec::new();
+        for (handle, expr) in self.expressions.iter() {
+            if !variant.contains(handle.index())
+                || !needs_emit(expr)
+                || !is_pure(expr)
+                || can_trap(expr)
+            {
+                continue;
+            }
+            let mut available = true;
+            for_each_operand_mut(&mut expr.clone(), |operand| {
+                available &= !variant.contains(operand.index());
+            });
+            if available {
+                variant.remove(handle.index());
+                invariant.push(handle);
+            }
+        }
+
+        if invariant.is_empty() {
+            return Vec::new();
+        }
+        let removed = invariant.iter().map(|handle| handle.index()).collect();
+        remove_emitted(body, &removed);
+        remove_emitted(continuing, &removed);
+        runs(invariant.into_iter())
+    }
+}
+
+/// Return true if evaluating the expression may trap or be undefined.
+///
+/// The types aren't known here, so all the divisions are assumed to be
+/// integer divisions, which may divide by zero.
+const fn can_trap(expr: &Expression) -> bool {
+    matches!(
+        *expr,
+        Expression::Binary {
+            op: crate::BinaryOperator::Divide | crate::BinaryOperator::Modulo,
+            ..
+        }
+    )
+}
+
+/// Add the expressions evaluated by the statements of `block` to `defined`.
+fn collect_defined(block: &Block, defined: &mut BitSet) {
+    for statement in block.iter() {
+        match *statement {
+            Statement::Emit(ref range) => {
+                for handle in range.clone() {
+                    defined.insert(handle.index());
+                }
+            }
+            Statement::Block(ref b) => collect_defined(b, defined),
+            Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => {
+                collect_defined(accept, defined);
+                collect_defined(reject, defined);
+            }
+            Statement::Switch { ref cases, .. } => {
+                for case in cases.iter() {
+                    collect_defined(&case.body, defined);
+                }
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+            } => {
+                collect_defined(body, defined);
+                collect_defined(continuing, defined);
+            }
+            Statement::Call {
+                result: Some(result),
+                ..
+            }
+            | Statement::Atomic { result, .. } => {
+                defined.insert(result.index());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Remove the expressions in `removed` from the `Emit` statements of `block`.
+pub(super) fn remove_emitted(block: &mut Block, removed: &BitSet) {
+    let mut index = 0;
+    while index < block.len() {
+        match block[index] {
+            Statement::Emit(ref range) => {
+                let kept = runs(
+                    range
+                        .clone()
+                        .filter(|handle| !removed.contains(handle.index())),
+                );
+                let count = kept.len();
+                let span = block.span_iter().nth(index).map(|(_, &span)| span);
+                let mut replacement = Block::with_capacity(count);
+                for run in kept {
+                    replacement.push(Statement::Emit(run), span.unwrap_or_default());
+                }
+                block.splice(index..index + 1, replacement);
+                index += count;
+                continue;
+            }
+            Statement::Block(ref mut b) => remove_emitted(b, removed),
+            Statement::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                remove_emitted(accept, removed);
+                remove_emitted(reject, removed);
+            }
+            Statement::Switch { ref mut cases, .. } => {
+                for case in cases.iter_mut() {
+                    remove_emitted(&mut case.body, removed);
+                }
+            }
+            Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                remove_emitted(body, removed);
+                remove_emitted(continuing, removed);
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+}
+
+/// Group increasing expression handles into ranges of consecutive ones.
+fn runs(handles: impl Iterator<Item = Handle<Expression>>) -> Vec<Range<Expression>> {
+    let mut runs = Vec::new();
+    let mut run: Option<(Handle<Expression>, Handle<Expression>)> = None;
+    for handle in handles {
+        run = match run {
+            Some((first, last)) if last.index() + 1 == handle.index() => Some((first, handle)),
+            Some((first, last)) => {
+                runs.push(Range::new_from_bounds(first, last));
+                Some((handle, handle))
+            }
+            None => Some((handle, handle)),
+        };
+    }
+    if let Some((first, last)) = run {
+        runs.push(Range::new_from_bounds(first, last));
+    }
+    runs
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_hoist_loop_invariants() {
+    /// Count the binary operations emitted at each loop nesting depth.
+    fn count_emitted(block: &Block, fun: &crate::Function, depth: usize, counts: &mut Vec<usize>) {
+        for statement in block.iter() {
+            match *statement {
+                Statement::Emit(ref range) => {
+                    if counts.len() <= depth {
+                        counts.resize(depth + 1, 0);
+                    }
+                    counts[depth] += range
+                        .clone()
+                        .filter(|&h| matches!(fun.expressions[h], Expression::Binary { .. }))
+                        .count();
+                }
+                Statement::Block(ref b) => count_emitted(b, fun, depth, counts),
+                Statement::If {
+                    ref accept,
+                    ref reject,
+                    ..
+                } => {
+                    count_emitted(accept, fun, depth, counts);
+                    count_emitted(reject, fun, depth, counts);
+                }
+                Statement::Loop {
+                    ref body,
+                    ref continuing,
+                } => {
+                    count_emitted(body, fun, depth + 1, counts);
+                    count_emitted(continuing, fun, depth + 1, counts);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let source = "
+        @group(0) @binding(0)
+        var<storage, read_write> buffer: array<f32, 64>;
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            var i = 0u;
+            loop {
+                if i >= 4u {
+                    break;
+                }
+                let row = i;
+                var j = 0u;
+                loop {
+                    if j >= 4u {
+                        break;
+                    }
+                    // Invariant in both loops.
+                    let base = index * 16u;
+                    // Invariant in the inner loop only.
+                    let offset = base + row * 4u;
+                    buffer[offset + j] = 1.0;
+                    j = j + 1u;
+                }
+                i = i + 1u;
+            }
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    let ep = &mut module.entry_points[0];
+
+    let mut before = Vec::new();
+    count_emitted(&ep.function.body, &ep.function, 0, &mut before);
+    hoist_loop_invariants(&mut ep.function);
+    let mut after = Vec::new();
+    count_emitted(&ep.function.body, &ep.function, 0, &mut after);
+
+    // The loop conditions, the increments and `offset + j` depend on values
+    // loaded in the loops, so they stay.
+    assert_eq!(before, vec![0, 2, 6]);
+    assert_eq!(after, vec![1, 4, 3]);
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_hoist_keeps_divisions() {
+    let source = "
+        @group(0) @binding(0)
+        var<storage, read_write> buffer: array<i32, 4>;
+
+        @compute @workgroup_size(1)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            let d = i32(index);
+            loop {
+                if d == 0 {
+                    break;
+                }
+                buffer[0] = 12 / d + 12 % d;
+                break;
+            }
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    let ep = &mut module.entry_points[0];
+    hoist_loop_invariants(&mut ep.function);
+
+    // The division and the remainder are invariant, but they stay guarded by
+    // the condition, while the condition itself is hoisted.
+    let mut hoisted = Vec::new();
+    for statement in ep.function.body.iter() {
+        if let Statement::Emit(ref range) = *statement {
+            hoisted.extend(range.clone().map(|h| &ep.function.expressions[h]));
+        }
+    }
+    assert!(hoisted.iter().any(|expr| matches!(
+        **expr,
+        Expression::Binary {
+            op: crate::BinaryOperator::Equal,
+            ..
+        }
+    )));
+    assert!(!hoisted.iter().any(|expr| can_trap(expr)));
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
@@ -409,6 +409,10 @@ pub enum IndexableLength {
     /// Values of this type always have the given number of elements.
     Known(u32),
 
+    /// The number of elements is the value of the given specialization
+    /// constant, which the pipeline may override.
+    Specializable(Handle<crate::Constant>),
+
     /// The number of elements is determined at runtime.
     Dynamic,
 }
@@ -422,9 +426,7 @@ impl crate::ArraySize {
             Self::Constant(k) => {
                 let constant = &module.constants[k];
                 if constant.specialization.is_some() {
-                    // Specializable constants are not supported as array lengths.
-                    // See valid::TypeError::UnsupportedSpecializedArrayLength.
-                    return Err(IndexableLengthError::InvalidArrayLength(k));
+                    return Ok(IndexableLength::Specializable(k));
                 }
                 let length = constant
                     .to_array_length()
@@ -0,0 +1,145 @@
+use crate::{Binding, Function, Type, TypeInner, UniqueArena};
+
+/// Where a binding of an entry point input or output is declared.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct InterfaceIndex {
+    /// The index of the function argument, or zero for the result.
+    pub index: u32,
+    /// The index of the struct member carrying the binding, if the argument
+    /// or result is a struct.
+    pub member: Option<u32>,
+}
+
+/// The inputs and outputs of an entry point, in a canonical order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterfaceOrder {
+    /// The bindings of the arguments.
+    pub inputs: Vec<InterfaceIndex>,
+    /// The bindings of the result.
+    pub outputs: Vec<InterfaceIndex>,
+}
+
+/// Return the inputs and outputs of the entry point `function` sorted by their
+/// bindings, for backends that want to emit them in a deterministic order.
+///
+/// Built-ins come first, in the order they are declared in, followed by the
+/// user-defined inputs or outputs by increasing location. Struct arguments and
+/// results are flattened into their members. The module isn't changed.
+pub fn sort_interface(function: &Function, types: &UniqueArena<Type>) -> InterfaceOrder {
+    let mut inputs = Vec::new();
+    for (index, argument) in function.arguments.iter().enumerate() {
+        collect(
+            index as u32,
+            argument.binding.as_ref(),
+            argument.ty,
+            types,
+            &mut inputs,
+        );
+    }
+    let mut outputs = Vec::new();
+    if let Some(ref result) = function.result {
+        collect(0, result.binding.as_ref(), result.ty, types, &mut outputs);
+    }
+
+    // The sort is stable, so built-ins keep their declaration order.
+    let sorted = |mut list: Vec<(InterfaceIndex, SortKey)>| {
+        list.sort_by_key(|&(_, key)| key);
+        list.into_iter().map(|(index, _)| index).collect()
+    };
+    InterfaceOrder {
+        inputs: sorted(inputs),
+        outputs: sorted(outputs),
+    }
+}
+
+/// The key sorting built-ins before locations, and locations by number.
+type SortKey = (bool, u32);
+
+const fn sort_key(binding: &Binding) -> SortKey {
+    match *binding {
+        Binding::BuiltIn(_) => (false, 0),
+        Binding::Location { location, .. } => (true, location),
+    }
+}
+
+fn collect(
+    index: u32,
+    binding: Option<&Binding>,
+    ty: crate::Handle<Type>,
+    types: &UniqueArena<Type>,
+    list: &mut Vec<(InterfaceIndex, SortKey)>,
+) {
+    match binding {
+        Some(binding) => list.push((
+            InterfaceIndex {
+                index,
+                member: None,
+            },
+            sort_key(binding),
+        )),
+        None => {
+            if let TypeInner::Struct { ref members, .. } = types[ty].inner {
+                for (member_index, member) in members.iter().enumerate() {
+                    if let Some(ref binding) = member.binding {
+                        list.push((
+                            InterfaceIndex {
+                                index,
+                                member: Some(member_index as u32),
+                            },
+                            sort_key(binding),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "wgsl-in"))]
+#[test]
+fn test_sort_interface() {
+    let module = crate::front::wgsl::parse_str(
+        "
+        struct VertexInput {
+            @location(3) color: vec4<f32>,
+            @builtin(instance_index) instance: u32,
+            @location(1) normal: vec3<f32>,
+        }
+
+        struct VertexOutput {
+            @location(2) uv: vec2<f32>,
+            @location(0) color: vec4<f32>,
+            @builtin(position) position: vec4<f32>,
+        }
+
+        @vertex
+        fn main(
+            @location(2) uv: vec2<f32>,
+            input: VertexInput,
+            @builtin(vertex_index) vertex: u32,
+            @location(0) position: vec3<f32>,
+        ) -> VertexOutput {
+            return VertexOutput(uv, input.color, vec4<f32>(position, 1.0));
+        }
+        ",
+    )
+    .unwrap();
+    let order = sort_interface(&module.entry_points[0].function, &module.types);
+
+    let index = |index, member| InterfaceIndex { index, member };
+    assert_eq!(
+        order.inputs,
+        [
+            index(1, Some(1)),
+            index(2, None),
+            index(3, None),
+            index(1, Some(2)),
+            index(0, None),
+            index(1, Some(0)),
+        ]
+    );
+    assert_eq!(
+        order.outputs,
+        [index(0, Some(2)), index(0, Some(1)), index(0, Some(0))]
+    );
+}
@@ -49,6 +49,8 @@ pub enum LayoutErrorInner {
     InvalidArrayElementType(Handle<crate::Type>),
     #[error("Struct member[{0}] type {1:?} doesn't exist")]
     InvalidStructMemberType(u32, Handle<crate::Type>),
+    #[error("Type contains itself by value, so its size is infinite")]
+    RecursiveType,
     #[error("Zero width is not supported")]
     ZeroWidth,
     #[error("Array size is a bad handle")]
@@ -180,6 +182,8 @@ impl Layouter {
                     size,
                     alignment: if base < ty_handle {
                         self[base].alignment
+                    } else if contains_by_value(types, base, ty_handle) {
+                        return Err(LayoutErrorInner::RecursiveType.with(ty_handle));
                     } else {
                         return Err(LayoutErrorInner::InvalidArrayElementType(base).with(ty_handle));
                     },
@@ -189,6 +193,8 @@ impl Layouter {
                     for (index, member) in members.iter().enumerate() {
                         alignment = if member.ty < ty_handle {
                             alignment.max(self[member.ty].alignment)
+                        } else if contains_by_value(types, member.ty, ty_handle) {
+                            return Err(LayoutErrorInner::RecursiveType.with(ty_handle));
                         } else {
                             return Err(LayoutErrorInner::InvalidStructMemberType(
                                 index as u32,
@@ -214,3 +220,34 @@ impl Layouter {
         Ok(())
     }
 }
+
+/// Return true if `ty` is `target`, or contains it by value.
+///
+/// This follows array element and struct member types, but not pointers,
+/// which have a fixed size regardless of what they point to.
+fn contains_by_value(
+    types: &UniqueArena<crate::Type>,
+    ty: Handle<crate::Type>,
+    target: Handle<crate::Type>,
+) -> bool {
+    let mut visited = crate::FastHashSet::default();
+    let mut stack = vec![ty];
+    while let Some(handle) = stack.pop() {
+        if handle == target {
+            return true;
+        }
+        if !visited.insert(handle) {
+            continue;
+        }
+        if let Ok(ty) = types.get_handle(handle) {
+            match ty.inner {
+                crate::TypeInner::Array { base, .. } => stack.push(base),
+                crate::TypeInner::Struct { ref members, .. } => {
+                    stack.extend(members.iter().map(|member| member.ty))
+                }
+                _ => {}
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,334 @@
+use crate::arena::{Arena, Handle};
+use crate::front::Typifier;
+use crate::valid::ShaderStages;
+use crate::{
+    BinaryOperator, Block, Expression, FastHashSet, ImageDimension, MathFunction, ScalarKind, Span,
+    Statement, TypeInner,
+};
+
+/// Rewrite the depth comparison samples of the entry points of `stages` into
+/// texel loads and explicit comparisons, for backends that can't sample with
+/// a comparison in these stages.
+///
+/// `compare` is the comparison function of the samplers: a sample passes, and
+/// evaluates to `1.0`, if `depth_ref compare texel` holds. The texel is the
+/// one of level zero containing the sampled coordinates, clamped to the edges
+/// of the image, so the filtering and addressing modes of the sampler are
+/// ignored. Only 2D depth images can be loaded, so comparisons on cube images
+/// are left as they are.
+///
+/// The functions called by these entry points are rewritten as well, even if
+/// other stages call them too. The expression arenas of the affected
+/// functions are rebuilt, so handles to their expressions held outside of the
+/// module are invalidated. The module needs to be validated again afterwards.
+pub fn lower_depth_comparisons(
+    module: &mut crate::Module,
+    stages: ShaderStages,
+    compare: BinaryOperator,
+) {
+    let mut entry_points = Vec::new();
+    let mut pending = Vec::new();
+    for (index, ep) in module.entry_points.iter().enumerate() {
+        let stage = match ep.stage {
+            crate::ShaderStage::Vertex => ShaderStages::VERTEX,
+            crate::ShaderStage::Fragment => ShaderStages::FRAGMENT,
+            crate::ShaderStage::Compute => ShaderStages::COMPUTE,
+        };
+        if stages.contains(stage) {
+            entry_points.push(index);
+            collect_calls(&ep.function.body, &mut pending);
+        }
+    }
+    let mut functions = FastHashSet::default();
+    while let Some(handle) = pending.pop() {
+        if functions.insert(handle) {
+            collect_calls(&module.functions[handle].body, &mut pending);
+        }
+    }
+
+    let handles: Vec<_> = module.functions.iter().map(|(handle, _)| handle).collect();
+    for handle in handles {
+        if !functions.contains(&handle) {
+            continue;
+        }
+        if let Some(typifier) = resolve_types(module, &module.functions[handle]) {
+            lower_function(
+                &mut module.functions[handle],
+                &module.types,
+                &mut module.constants,
+                &typifier,
+                compare,
+            );
+        }
+    }
+    for index in entry_points {
+        let fun = &module.entry_points[index].function;
+        if let Some(typifier) = resolve_types(module, fun) {
+            lower_function(
+                &mut module.entry_points[index].function,
+                &module.types,
+                &mut module.constants,
+                &typifier,
+                compare,
+            );
+        }
+    }
+}
+
+/// Add the functions called by the statements of `block` to `calls`.
+fn collect_calls(block: &Block, calls: &mut Vec<Handle<crate::Function>>) {
+    for statement in block.iter() {
+        match *statement {
+            Statement::Call { function, .. } => calls.push(function),
+            Statement::Block(ref b) => collect_calls(b, calls),
+            Statement::If {
+                ref accept,
+                ref reject,
+                ..
+            } => {
+                collect_calls(accept, calls);
+                collect_calls(reject, calls);
+            }
+            Statement::Switch { ref cases, .. } => {
+                for case in cases.iter() {
+                    collect_calls(&case.body, calls);
+                }
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+            } => {
+                collect_calls(body, calls);
+                collect_calls(continuing, calls);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Resolve the expression types of `fun`, if it samples with a comparison.
+fn resolve_types(module: &crate::Module, fun: &crate::Function) -> Option<Typifier> {
+    super::resolve_types(module, fun, |expr| {
+        matches!(
+            *expr,
+            Expression::ImageSample {
+                depth_ref: Some(_),
+                gather: None,
+                ..
+            }
+        )
+    })
+}
+
+fn lower_function(
+    fun: &mut crate::Function,
+    types: &crate::UniqueArena<crate::Type>,
+    constants: &mut Arena<crate::Constant>,
+    typifier: &Typifier,
+    compare: BinaryOperator,
+) {
+    let is_lowered = |expr: &Expression| match *expr {
+        Expression::ImageSample {
+            image,
+            depth_ref: Some(_),
+            gather: None,
+            ..
+        } => matches!(
+            *typifier.get(image, types),
+            TypeInner::Image {
+                dim: ImageDimension::D2,
+                ..
+            }
+        ),
+        _ => false,
+    };
+    if !fun.expressions.iter().any(|(_, expr)| is_lowered(expr)) {
+        return;
+    }
+
+    let mut expressions = Arena::new();
+
+    // Constants don't need to be emitted, so they are created up front to
+    // keep them out of the emitted ranges.
+    let mut constant = |value| {
+        let constant = constants.fetch_or_append(
+            crate::Constant {
+                name: None,
+                specialization: None,
+                inner: crate::ConstantInner::Scalar { width: 4, value },
+            },
+            Span::default(),
+        );
+        expressions.append(Expression::Constant(constant), Span::default())
+    };
+    let int_zero = constant(crate::ScalarValue::Sint(0));
+    let int_one = constant(crate::ScalarValue::Sint(1));
+    let float_zero = constant(crate::ScalarValue::Float(0.0));
+    let float_one = constant(crate::ScalarValue::Float(1.0));
+    let mut offsets = crate::FastHashMap::default();
+    for (_, expr) in fun.expressions.iter() {
+        if let Expression::ImageSample {
+            offset: Some(offset),
+            ..
+        } = *expr
+        {
+            if is_lowered(expr) {
+                offsets.entry(offset).or_insert_with(|| {
+                    expressions.append(Expression::Constant(offset), Span::default())
+                });
+            }
+        }
+    }
+
+    super::rebuild_expressions(fun, expressions, |expressions, remap, _, expr, span| {
+        let mut append = |expr| expressions.append(expr, span);
+        match *expr {
+            Expression::ImageSample {
+                image,
+                coordinate,
+                array_index,
+                offset,
+                depth_ref: Some(depth_ref),
+                ..
+            } if is_lowered(expr) => {
+                let image = remap[image.index()];
+                let size = append(Expression::ImageQuery {
+                    image,
+                    query: crate::ImageQuery::Size {
+                        level: Some(int_zero),
+                    },
+                });
+                let float_size = append(Expression::As {
+                    expr: size,
+                    kind: ScalarKind::Float,
+                    convert: Some(4),
+                });
+                let scaled = append(Expression::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: remap[coordinate.index()],
+                    right: float_size,
+                });
+                let floor = append(Expression::Math {
+                    fun: MathFunction::Floor,
+                    arg: scaled,
+                    arg1: None,
+                    arg2: None,
+                    arg3: None,
+                });
+                let mut texel = append(Expression::As {
+                    expr: floor,
+                    kind: ScalarKind::Sint,
+                    convert: Some(4),
+                });
+                if let Some(offset) = offset {
+                    texel = append(Expression::Binary {
+                        op: BinaryOperator::Add,
+                        left: texel,
+                        right: offsets[&offset],
+                    });
+                }
+                let int_size = append(Expression::As {
+                    expr: size,
+                    kind: ScalarKind::Sint,
+                    convert: Some(4),
+                });
+                let vec_one = append(Expression::Splat {
+                    size: crate::VectorSize::Bi,
+                    value: int_one,
+                });
+                let max = append(Expression::Binary {
+                    op: BinaryOperator::Subtract,
+                    left: int_size,
+                    right: vec_one,
+                });
+                let min = append(Expression::Splat {
+                    size: crate::VectorSize::Bi,
+                    value: int_zero,
+                });
+                let clamped = append(Expression::Math {
+                    fun: MathFunction::Clamp,
+                    arg: texel,
+                    arg1: Some(min),
+                    arg2: Some(max),
+                    arg3: None,
+                });
+                let depth = append(Expression::ImageLoad {
+                    image,
+                    coordinate: clamped,
+                    array_index: array_index.map(|index| remap[index.index()]),
+                    sample: None,
+                    level: Some(int_zero),
+                });
+                let condition = append(Expression::Binary {
+                    op: compare,
+                    left: remap[depth_ref.index()],
+                    right: depth,
+                });
+                Some(Expression::Select {
+                    condition,
+                    accept: float_one,
+                    reject: float_zero,
+                })
+            }
+            _ => None,
+        }
+    });
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_lower_depth_comparisons() {
+    let source = "
+        @group(0) @binding(0)
+        var shadow: texture_depth_2d;
+        @group(0) @binding(1)
+        var shadow_array: texture_depth_2d_array;
+        @group(0) @binding(2)
+        var compare: sampler_comparison;
+
+        fn lookup(uv: vec2<f32>, depth: f32) -> f32 {
+            return textureSampleCompareLevel(shadow_array, compare, uv, 1, depth);
+        }
+
+        @vertex
+        fn vs_main(@location(0) uv: vec2<f32>) -> @builtin(position) vec4<f32> {
+            let a = textureSampleCompareLevel(shadow, compare, uv, 0.5);
+            let b = textureSampleCompareLevel(shadow, compare, uv, 0.5, vec2<i32>(1, -1));
+            return vec4<f32>(a, b, lookup(uv, 0.5), 1.0);
+        }
+
+        @fragment
+        fn fs_main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+            return vec4<f32>(textureSampleCompare(shadow, compare, uv, 0.5));
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    lower_depth_comparisons(&mut module, ShaderStages::VERTEX, BinaryOperator::LessEqual);
+
+    let compares = |fun: &crate::Function| {
+        fun.expressions
+            .iter()
+            .filter(|&(_, expr)| {
+                matches!(
+                    *expr,
+                    Expression::ImageSample {
+                        depth_ref: Some(_),
+                        ..
+                    }
+                )
+            })
+            .count()
+    };
+    let (_, lookup) = module.functions.iter().next().unwrap();
+    assert_eq!(compares(lookup), 0);
+    assert_eq!(compares(&module.entry_points[0].function), 0);
+    // The fragment stage is left as it is.
+    assert_eq!(compares(&module.entry_points[1].function), 1);
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
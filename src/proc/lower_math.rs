@@ -0,0 +1,715 @@
+use crate::arena::{Arena, Handle};
+use crate::front::Typifier;
+use crate::{BinaryOperator, Expression, FastHashMap, FastHashSet, MathFunction, Span, TypeInner};
+
+/// Rewrite calls to the `unsupported` math functions into equivalent
+/// expressions, for backends that lack the corresponding intrinsics.
+///
+/// The following functions of floating-point arguments can be lowered:
+///
+/// - `radians(x)` becomes `x * (PI / 180)`, and `degrees(x)` becomes
+///   `x * (180 / PI)`.
+/// - `step(edge, x)` becomes `select(1, 0, x < edge)`.
+/// - `mix(a, b, t)` becomes `a + (b - a) * t`.
+/// - `fma(a, b, c)` becomes `a * b + c`.
+/// - `distance(a, b)` becomes `length(a - b)`.
+/// - `atan2(y, x)` becomes `atan(y / x)`, shifted by `PI` towards the sign of
+///   `y` when `x < 0`.
+/// - `inverse(m)` becomes the adjugate of `m` divided by its determinant,
+///   both expanded by cofactors.
+///
+/// `outer(a, b)` becomes the matrix whose column `i` is `b * a[i]`, and
+/// `sign(x)` of a signed integer becomes `select(select(0, 1, x > 0), -1, x < 0)`.
+///
+/// Other functions are left as they are. The expression arenas of the
+/// affected functions are rebuilt, so handles to their expressions held
+/// outside of the module are invalidated. The module needs to be validated
+/// again afterwards.
+pub fn lower_math_functions(module: &mut crate::Module, unsupported: &FastHashSet<MathFunction>) {
+    let handles: Vec<_> = module.functions.iter().map(|(handle, _)| handle).collect();
+    for handle in handles {
+        if let Some(typifier) = resolve_types(module, &module.functions[handle], unsupported) {
+            let compose_types = add_compose_types(
+                &mut module.types,
+                &module.functions[handle],
+                &typifier,
+                unsupported,
+            );
+            lower_function(
+                &mut module.functions[handle],
+                &module.types,
+                &mut module.constants,
+                &typifier,
+                &compose_types,
+                unsupported,
+            );
+        }
+    }
+    for index in 0..module.entry_points.len() {
+        let fun = &module.entry_points[index].function;
+        if let Some(typifier) = resolve_types(module, fun, unsupported) {
+            let compose_types = add_compose_types(
+                &mut module.types,
+                &module.entry_points[index].function,
+                &typifier,
+                unsupported,
+            );
+            lower_function(
+                &mut module.entry_points[index].function,
+                &module.types,
+                &mut module.constants,
+                &typifier,
+                &compose_types,
+                unsupported,
+            );
+        }
+    }
+}
+
+/// Resolve the expression types of `fun`, if it calls any of the
+/// `unsupported` functions.
+fn resolve_types(
+    module: &crate::Module,
+    fun: &crate::Function,
+    unsupported: &FastHashSet<MathFunction>,
+) -> Option<Typifier> {
+    super::resolve_types(module, fun, |expr| match *expr {
+        Expression::Math { fun, .. } => unsupported.contains(&fun),
+        _ => false,
+    })
+}
+
+/// The matrix type and column type composed by the lowering of a matrix
+/// function, for each old expression calling one.
+type ComposeTypes = FastHashMap<Handle<Expression>, (Handle<crate::Type>, Handle<crate::Type>)>;
+
+/// Add the types of the matrices composed by the lowering of the
+/// `unsupported` matrix functions called in `fun`.
+fn add_compose_types(
+    types: &mut crate::UniqueArena<crate::Type>,
+    fun: &crate::Function,
+    typifier: &Typifier,
+    unsupported: &FastHashSet<MathFunction>,
+) -> ComposeTypes {
+    let mut compose_types = FastHashMap::default();
+    for (handle, expr) in fun.expressions.iter() {
+        match *expr {
+            Expression::Math {
+                fun: math @ MathFunction::Outer,
+                ..
+            }
+            | Expression::Math {
+                fun: math @ MathFunction::Inverse,
+                ..
+            } if unsupported.contains(&math) => {}
+            _ => continue,
+        }
+        let (columns, rows, width) = match *typifier.get(handle, types) {
+            TypeInner::Matrix {
+                columns,
+                rows,
+                width,
+            } => (columns, rows, width),
+            _ => continue,
+        };
+        let span = fun.expressions.get_span(handle);
+        let matrix = types.insert(
+            crate::Type {
+                name: None,
+                inner: TypeInner::Matrix {
+                    columns,
+                    rows,
+                    width,
+                },
+            },
+            span,
+        );
+        let column = types.insert(
+            crate::Type {
+                name: None,
+                inner: TypeInner::Vector {
+                    size: rows,
+                    kind: crate::ScalarKind::Float,
+                    width,
+                },
+            },
+            span,
+        );
+        compose_types.insert(handle, (matrix, column));
+    }
+    compose_types
+}
+
+/// Return the scalar constants the lowering of `fun` of arguments of `kind`
+/// refers to.
+const fn lowering_constants(fun: MathFunction, kind: crate::ScalarKind) -> &'static [f64] {
+    use std::f64::consts::PI;
+    match (fun, kind) {
+        (MathFunction::Radians, _) => &[PI / 180.0],
+        (MathFunction::Degrees, _) => &[180.0 / PI],
+        (MathFunction::Step, _) => &[0.0, 1.0],
+        (MathFunction::Atan2, _) => &[0.0, PI, -PI],
+        (MathFunction::Inverse, _) => &[1.0],
+        (MathFunction::Sign, crate::ScalarKind::Sint) => &[-1.0, 0.0, 1.0],
+        _ => &[],
+    }
+}
+
+fn lower_function(
+    fun: &mut crate::Function,
+    types: &crate::UniqueArena<crate::Type>,
+    constants: &mut Arena<crate::Constant>,
+    typifier: &Typifier,
+    compose_types: &ComposeTypes,
+    unsupported: &FastHashSet<MathFunction>,
+) {
+    let mut expressions = Arena::new();
+    let mut constant_exprs = FastHashMap::default();
+
+    // Constants don't need to be emitted, so they are created up front to
+    // keep them out of the emitted ranges.
+    let mut lowerer = Lowerer {
+        types,
+        typifier,
+        compose_types,
+        expressions: &mut expressions,
+        constants: &mut constant_exprs,
+        remap: &[],
+    };
+    for (handle, expr) in fun.expressions.iter() {
+        if let Expression::Math { fun: math, arg, .. } = *expr {
+            if !unsupported.contains(&math) {
+                continue;
+            }
+            if let Some((kind, width)) = lowerer.scalar_kind(arg) {
+                for &value in lowering_constants(math, kind) {
+                    let span = fun.expressions.get_span(handle);
+                    lowerer.add_constant(constants, kind, value, width, span);
+                }
+            }
+        }
+    }
+
+    super::rebuild_expressions(
+        fun,
+        expressions,
+        |expressions, remap, handle, expr, span| match *expr {
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                ..
+            } if unsupported.contains(&fun) => Lowerer {
+                types,
+                typifier,
+                compose_types,
+                expressions,
+                constants: &mut constant_exprs,
+                remap,
+            }
+            .lower(handle, fun, arg, arg1, arg2, span),
+            _ => None,
+        },
+    );
+}
+
+struct Lowerer<'a> {
+    types: &'a crate::UniqueArena<crate::Type>,
+    /// The types of the old expressions.
+    typifier: &'a Typifier,
+    compose_types: &'a ComposeTypes,
+    expressions: &'a mut Arena<Expression>,
+    /// The constant expressions created for each kind, width and value.
+    constants: &'a mut FastHashMap<(crate::ScalarKind, crate::Bytes, u64), Handle<Expression>>,
+    /// The new expression for each old one.
+    remap: &'a [Handle<Expression>],
+}
+
+impl Lowerer<'_> {
+    /// Return the vector size, kind and width of the scalar or vector type of
+    /// the old expression `handle`, if it has one.
+    fn scalar_type(
+        &self,
+        handle: Handle<Expression>,
+    ) -> Option<(Option<crate::VectorSize>, crate::ScalarKind, crate::Bytes)> {
+        match *self.typifier.get(handle, self.types) {
+            TypeInner::Scalar { kind, width } => Some((None, kind, width)),
+            TypeInner::Vector { size, kind, width } => Some((Some(size), kind, width)),
+            _ => None,
+        }
+    }
+
+    /// Return the scalar kind and width of the type of the old expression
+    /// `handle`, including matrices.
+    fn scalar_kind(&self, handle: Handle<Expression>) -> Option<(crate::ScalarKind, crate::Bytes)> {
+        match *self.typifier.get(handle, self.types) {
+            TypeInner::Matrix { width, .. } => Some((crate::ScalarKind::Float, width)),
+            _ => self
+                .scalar_type(handle)
+                .map(|(_, kind, width)| (kind, width)),
+        }
+    }
+
+    fn add_constant(
+        &mut self,
+        constants: &mut Arena<crate::Constant>,
+        kind: crate::ScalarKind,
+        value: f64,
+        width: crate::Bytes,
+        span: Span,
+    ) {
+        let key = (kind, width, value.to_bits());
+        if self.constants.contains_key(&key) {
+            return;
+        }
+        let value = match kind {
+            crate::ScalarKind::Sint => crate::ScalarValue::Sint(value as i64),
+            _ => crate::ScalarValue::Float(value),
+        };
+        let constant = constants.fetch_or_append(
+            crate::Constant {
+                name: None,
+                specialization: None,
+                inner: crate::ConstantInner::Scalar { width, value },
+            },
+            span,
+        );
+        let handle = self
+            .expressions
+            .append(Expression::Constant(constant), span);
+        self.constants.insert(key, handle);
+    }
+
+    /// Return `value` with the size of the vector type `size`.
+    fn splat(
+        &mut self,
+        kind: crate::ScalarKind,
+        value: f64,
+        size: Option<crate::VectorSize>,
+        width: crate::Bytes,
+        span: Span,
+    ) -> Handle<Expression> {
+        let value = self.constants[&(kind, width, value.to_bits())];
+        match size {
+            Some(size) => self
+                .expressions
+                .append(Expression::Splat { size, value }, span),
+            None => value,
+        }
+    }
+
+    fn binary(
+        &mut self,
+        op: BinaryOperator,
+        left: Handle<Expression>,
+        right: Handle<Expression>,
+        span: Span,
+    ) -> Handle<Expression> {
+        self.expressions
+            .append(Expression::Binary { op, left, right }, span)
+    }
+
+    fn select(
+        &mut self,
+        condition: Handle<Expression>,
+        accept: Handle<Expression>,
+        reject: Handle<Expression>,
+        span: Span,
+    ) -> Handle<Expression> {
+        self.expressions.append(
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            },
+            span,
+        )
+    }
+
+    fn access_index(
+        &mut self,
+        base: Handle<Expression>,
+        index: u32,
+        span: Span,
+    ) -> Handle<Expression> {
+        self.expressions
+            .append(Expression::AccessIndex { base, index }, span)
+    }
+
+    /// Return the expression computing `fun` of the old expressions `arg`,
+    /// `arg1` and `arg2`, after appending its operands. `handle` is the old
+    /// expression calling `fun`.
+    fn lower(
+        &mut self,
+        handle: Handle<Expression>,
+        fun: MathFunction,
+        arg: Handle<Expression>,
+        arg1: Option<Handle<Expression>>,
+        arg2: Option<Handle<Expression>>,
+        span: Span,
+    ) -> Option<Expression> {
+        let a = self.remap[arg.index()];
+        let b = arg1.map(|arg1| self.remap[arg1.index()]);
+        let c = arg2.map(|arg2| self.remap[arg2.index()]);
+        match fun {
+            MathFunction::Outer => return self.lower_outer(handle, a, b?, span),
+            MathFunction::Inverse => return self.lower_inverse(handle, a, span),
+            _ => {}
+        }
+
+        let (size, kind, width) = self.scalar_type(arg)?;
+        let expected_kind = match fun {
+            MathFunction::Sign => crate::ScalarKind::Sint,
+            _ => crate::ScalarKind::Float,
+        };
+        if kind != expected_kind {
+            return None;
+        }
+        Some(match fun {
+            MathFunction::Radians | MathFunction::Degrees => {
+                let factor = lowering_constants(fun, kind)[0];
+                let factor = self.constants[&(kind, width, factor.to_bits())];
+                Expression::Binary {
+                    op: BinaryOperator::Multiply,
+                    left: a,
+                    right: factor,
+                }
+            }
+            MathFunction::Step => {
+                // `edge` and `x` may differ in GLSL, but not in the IR.
+                let x = b?;
+                let condition = self.binary(BinaryOperator::Less, x, a, span);
+                Expression::Select {
+                    condition,
+                    accept: self.splat(kind, 0.0, size, width, span),
+                    reject: self.splat(kind, 1.0, size, width, span),
+                }
+            }
+            MathFunction::Mix => {
+                let (b, t) = (b?, c?);
+                let difference = self.binary(BinaryOperator::Subtract, b, a, span);
+                let scaled = self.binary(BinaryOperator::Multiply, difference, t, span);
+                Expression::Binary {
+                    op: BinaryOperator::Add,
+                    left: a,
+                    right: scaled,
+                }
+            }
+            MathFunction::Fma => {
+                let (b, c) = (b?, c?);
+                let product = self.binary(BinaryOperator::Multiply, a, b, span);
+                Expression::Binary {
+                    op: BinaryOperator::Add,
+                    left: product,
+                    right: c,
+                }
+            }
+            MathFunction::Distance => {
+                let difference = self.binary(BinaryOperator::Subtract, a, b?, span);
+                Expression::Math {
+                    fun: MathFunction::Length,
+                    arg: difference,
+                    arg1: None,
+                    arg2: None,
+                    arg3: None,
+                }
+            }
+            MathFunction::Atan2 => {
+                let (y, x) = (a, b?);
+                let quotient = self.binary(BinaryOperator::Divide, y, x, span);
+                let angle = self.expressions.append(
+                    Expression::Math {
+                        fun: MathFunction::Atan,
+                        arg: quotient,
+                        arg1: None,
+                        arg2: None,
+                        arg3: None,
+                    },
+                    span,
+                );
+                let zero = self.splat(kind, 0.0, size, width, span);
+                let below = self.binary(BinaryOperator::Less, y, zero, span);
+                let down = self.splat(kind, -std::f64::consts::PI, size, width, span);
+                let up = self.splat(kind, std::f64::consts::PI, size, width, span);
+                let offset = self.select(below, down, up, span);
+                let shifted = self.binary(BinaryOperator::Add, angle, offset, span);
+                let left = self.binary(BinaryOperator::Less, x, zero, span);
+                Expression::Select {
+                    condition: left,
+                    accept: shifted,
+                    reject: angle,
+                }
+            }
+            MathFunction::Sign => {
+                let zero = self.splat(kind, 0.0, size, width, span);
+                let positive = self.binary(BinaryOperator::Greater, a, zero, span);
+                let one = self.splat(kind, 1.0, size, width, span);
+                let non_negative = self.select(positive, one, zero, span);
+                let negative = self.binary(BinaryOperator::Less, a, zero, span);
+                Expression::Select {
+                    condition: negative,
+                    accept: self.splat(kind, -1.0, size, width, span),
+                    reject: non_negative,
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    /// Return the number of columns and the width of the matrix type
+    /// composed by the lowering of the old expression `handle`, along with
+    /// that type and its column type.
+    fn compose_type(
+        &self,
+        handle: Handle<Expression>,
+    ) -> Option<(u32, crate::Bytes, Handle<crate::Type>, Handle<crate::Type>)> {
+        let (matrix, column) = *self.compose_types.get(&handle)?;
+        match self.types[matrix].inner {
+            TypeInner::Matrix { columns, width, .. } => {
+                Some((columns as u32, width, matrix, column))
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the matrix whose column `i` is `b * a[i]`.
+    fn lower_outer(
+        &mut self,
+        handle: Handle<Expression>,
+        a: Handle<Expression>,
+        b: Handle<Expression>,
+        span: Span,
+    ) -> Option<Expression> {
+        let (columns, _, ty, _) = self.compose_type(handle)?;
+        let components = (0..columns)
+            .map(|index| {
+                let factor = self.access_index(a, index, span);
+                self.binary(BinaryOperator::Multiply, b, factor, span)
+            })
+            .collect();
+        Some(Expression::Compose { ty, components })
+    }
+
+    /// Return the inverse of the square matrix `m`, as its adjugate divided
+    /// by its determinant.
+    fn lower_inverse(
+        &mut self,
+        handle: Handle<Expression>,
+        m: Handle<Expression>,
+        span: Span,
+    ) -> Option<Expression> {
+        let (size, width, ty, column_ty) = self.compose_type(handle)?;
+        // The elements of `m`, by column and row.
+        let elements: Vec<Vec<_>> = (0..size)
+            .map(|column| {
+                let column = self.access_index(m, column, span);
+                (0..size)
+                    .map(|row| self.access_index(column, row, span))
+                    .collect()
+            })
+            .collect();
+
+        // Column `c` of the adjugate holds the cofactors of row `c` of `m`.
+        let size = size as usize;
+        let all: Vec<usize> = (0..size).collect();
+        let mut cofactors = Vec::with_capacity(size);
+        for c in 0..size {
+            let rows: Vec<_> = all.iter().cloned().filter(|&row| row != c).collect();
+            let column: Vec<_> = (0..size)
+                .map(|r| {
+                    let columns: Vec<_> =
+                        all.iter().cloned().filter(|&column| column != r).collect();
+                    let minor = self.determinant(&elements, &rows, &columns, span);
+                    if (c + r) % 2 == 0 {
+                        minor
+                    } else {
+                        self.expressions.append(
+                            Expression::Unary {
+                                op: crate::UnaryOperator::Negate,
+                                expr: minor,
+                            },
+                            span,
+                        )
+                    }
+                })
+                .collect();
+            cofactors.push(column);
+        }
+
+        // Expand the determinant along the first row.
+        let mut determinant = None;
+        for (column, &cofactor) in cofactors[0].iter().enumerate() {
+            let term = self.binary(
+                BinaryOperator::Multiply,
+                elements[column][0],
+                cofactor,
+                span,
+            );
+            determinant = Some(match determinant {
+                Some(sum) => self.binary(BinaryOperator::Add, sum, term, span),
+                None => term,
+            });
+        }
+
+        let components = cofactors
+            .into_iter()
+            .map(|components| {
+                self.expressions.append(
+                    Expression::Compose {
+                        ty: column_ty,
+                        components,
+                    },
+                    span,
+                )
+            })
+            .collect();
+        let adjugate = self
+            .expressions
+            .append(Expression::Compose { ty, components }, span);
+        let one = self.constants[&(crate::ScalarKind::Float, width, 1.0f64.to_bits())];
+        let factor = self.binary(BinaryOperator::Divide, one, determinant?, span);
+        Some(Expression::Binary {
+            op: BinaryOperator::Multiply,
+            left: adjugate,
+            right: factor,
+        })
+    }
+
+    /// Return the determinant of the submatrix of `elements` made of `rows`
+    /// and `columns`, expanded along its first row.
+    fn determinant(
+        &mut self,
+        elements: &[Vec<Handle<Expression>>],
+        rows: &[usize],
+        columns: &[usize],
+        span: Span,
+    ) -> Handle<Expression> {
+        let row = rows[0];
+        if let [column] = *columns {
+            return elements[column][row];
+        }
+        let mut result = None;
+        for (index, &column) in columns.iter().enumerate() {
+            let others: Vec<_> = columns.iter().cloned().filter(|&c| c != column).collect();
+            let minor = self.determinant(elements, &rows[1..], &others, span);
+            let term = self.binary(BinaryOperator::Multiply, elements[column][row], minor, span);
+            result = Some(match result {
+                None => term,
+                Some(sum) if index % 2 == 0 => self.binary(BinaryOperator::Add, sum, term, span),
+                Some(sum) => self.binary(BinaryOperator::Subtract, sum, term, span),
+            });
+        }
+        result.unwrap()
+    }
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_lower_math_functions() {
+    let source = "
+        fn angles(x: vec3<f32>) -> vec3<f32> {
+            let r = radians(x);
+            return degrees(r) + step(vec3<f32>(0.5), x);
+        }
+
+        fn products(a: vec2<f32>, b: vec3<f32>, n: vec2<i32>) -> mat2x3<f32> {
+            let s = vec2<f32>(sign(n)) + atan2(a, vec2<f32>(1.0, -1.0));
+            return outerProduct(s, b);
+        }
+
+        @fragment
+        fn main(@location(0) x: f32) -> @location(0) vec4<f32> {
+            var color = vec4<f32>(mix(0.0, x, 0.25), fma(x, 2.0, 1.0), distance(x, 1.0), 1.0);
+            if x > 0.5 {
+                color.x = radians(x);
+            }
+            return color;
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    let unsupported = [
+        MathFunction::Radians,
+        MathFunction::Degrees,
+        MathFunction::Step,
+        MathFunction::Mix,
+        MathFunction::Fma,
+        MathFunction::Atan2,
+        MathFunction::Sign,
+        MathFunction::Outer,
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    lower_math_functions(&mut module, &unsupported);
+
+    let functions = module
+        .functions
+        .iter()
+        .map(|(_, fun)| fun)
+        .chain(module.entry_points.iter().map(|ep| &ep.function));
+    for fun in functions {
+        for (_, expr) in fun.expressions.iter() {
+            if let Expression::Math { fun, .. } = *expr {
+                assert!(!unsupported.contains(&fun), "{:?} wasn't lowered", fun);
+            }
+        }
+    }
+    let factor = std::f64::consts::PI / 180.0;
+    assert!(module.constants.iter().any(|(_, constant)| matches!(
+        constant.inner,
+        crate::ConstantInner::Scalar {
+            value: crate::ScalarValue::Float(value),
+            ..
+        } if value == factor
+    )));
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
+
+#[cfg(all(test, feature = "validate", feature = "glsl-in"))]
+#[test]
+fn test_lower_inverse() {
+    let mut parser = crate::front::glsl::Parser::default();
+    let mut module = parser
+        .parse(
+            &crate::front::glsl::Options::from(crate::ShaderStage::Vertex),
+            "
+            #version 450
+            layout(std430, binding = 0) buffer Matrices {
+                mat2 a;
+                mat3 b;
+                mat4 c;
+            };
+
+            void main() {
+                gl_Position = inverse(c) * vec4(inverse(b)[0], inverse(a)[1].x);
+            }
+            ",
+        )
+        .unwrap();
+    let unsupported = std::iter::once(MathFunction::Inverse).collect();
+    lower_math_functions(&mut module, &unsupported);
+
+    for (_, expr) in module.entry_points[0].function.expressions.iter() {
+        assert!(!matches!(
+            *expr,
+            Expression::Math {
+                fun: MathFunction::Inverse,
+                ..
+            }
+        ));
+    }
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
@@ -0,0 +1,210 @@
+use super::strip::{remap_type_inner, remap_types};
+use crate::FastHashSet;
+
+/// Rename everything named in the module to the shortest unique identifiers.
+///
+/// Types, constants, global variables and functions get the names `a`, `b`,
+/// ..., `z`, `aa`, `ab` and so on, skipping the entry point names, which are
+/// kept since they're needed to refer to the entry points from outside of the
+/// module. Arguments, local variables and named expressions continue the
+/// sequence separately in each function, so they never shadow anything global,
+/// and struct members are numbered from `a` in each struct. Things without a
+/// name are left unnamed.
+///
+/// Backends still run the names through their [`Namer`](super::Namer), which
+/// keeps them apart from the keywords of the output language.
+pub fn minify_names(module: &mut crate::Module) {
+    let mut names = ShortNames {
+        next: 0,
+        reserved: module
+            .entry_points
+            .iter()
+            .map(|ep| ep.name.clone())
+            .collect(),
+    };
+
+    let old_types = std::mem::take(&mut module.types);
+    let mut type_map = Vec::with_capacity(old_types.len());
+    for (handle, ty) in old_types.iter() {
+        let mut inner = remap_type_inner(&ty.inner, &type_map);
+        if let crate::TypeInner::Struct {
+            ref mut members, ..
+        } = inner
+        {
+            let mut member_names = ShortNames::default();
+            for member in members.iter_mut() {
+                member_names.rename(&mut member.name);
+            }
+        }
+        let mut name = ty.name.clone();
+        names.rename(&mut name);
+        let new_handle = module
+            .types
+            .insert(crate::Type { name, inner }, old_types.get_span(handle));
+        type_map.push(new_handle);
+    }
+    remap_types(module, &type_map);
+
+    for (_, constant) in module.constants.iter_mut() {
+        names.rename(&mut constant.name);
+    }
+
+    for (_, var) in module.global_variables.iter_mut() {
+        names.rename(&mut var.name);
+    }
+
+    for (_, fun) in module.functions.iter_mut() {
+        names.rename(&mut fun.name);
+    }
+
+    for (_, fun) in module.functions.iter_mut() {
+        minify_function(fun, names.clone());
+    }
+
+    for ep in module.entry_points.iter_mut() {
+        minify_function(&mut ep.function, names.clone());
+    }
+}
+
+fn minify_function(fun: &mut crate::Function, mut names: ShortNames) {
+    for argument in fun.arguments.iter_mut() {
+        names.rename(&mut argument.name);
+    }
+    for (_, local) in fun.local_variables.iter_mut() {
+        names.rename(&mut local.name);
+    }
+    for name in fun.named_expressions.values_mut() {
+        *name = names.next();
+    }
+}
+
+/// A generator of the identifiers `a`, `b`, ..., `z`, `aa`, `ab`, ...
+#[derive(Clone, Default)]
+struct ShortNames {
+    next: usize,
+    reserved: FastHashSet<String>,
+}
+
+impl ShortNames {
+    fn next(&mut self) -> String {
+        loop {
+            let mut index = self.next;
+            self.next += 1;
+            let mut name = Vec::new();
+            loop {
+                name.push(b'a' + (index % 26) as u8);
+                index /= 26;
+                if index == 0 {
+                    break;
+                }
+                index -= 1;
+            }
+            name.reverse();
+            let name = String::from_utf8(name).unwrap();
+            if !self.reserved.contains(&name) {
+                return name;
+            }
+        }
+    }
+
+    fn rename(&mut self, name: &mut Option<String>) {
+        if name.is_some() {
+            *name = Some(self.next());
+        }
+    }
+}
+
+#[test]
+fn test_short_names() {
+    let mut names = ShortNames {
+        next: 0,
+        reserved: std::iter::once("c".to_string()).collect(),
+    };
+    let list: Vec<_> = (0..30).map(|_| names.next()).collect();
+    assert_eq!(list[..3], ["a", "b", "d"]);
+    assert_eq!(list[24..], ["z", "aa", "ab", "ac", "ad", "ae"]);
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_minify_names() {
+    let mut module = crate::front::wgsl::parse_str(
+        "
+        struct Light {
+            position: vec3<f32>,
+            color: vec3<f32>,
+        }
+
+        let INTENSITY: f32 = 2.0;
+        var<private> light: Light;
+
+        fn shade(normal: vec3<f32>, second: Light) -> vec3<f32> {
+            let amount = max(dot(normal, second.position), 0.0);
+            return second.color * amount * INTENSITY;
+        }
+
+        @fragment
+        fn main(@location(0) normal: vec3<f32>) -> @location(0) vec4<f32> {
+            var color = shade(normal, light);
+            return vec4<f32>(color, 1.0);
+        }
+        ",
+    )
+    .unwrap();
+    minify_names(&mut module);
+
+    let light = module.global_variables.iter().next().unwrap().1;
+    assert_eq!(light.name.as_deref(), Some("c"));
+    let light_type = &module.types[light.ty];
+    assert_eq!(light_type.name.as_deref(), Some("a"));
+    let members: Vec<_> = match light_type.inner {
+        crate::TypeInner::Struct { ref members, .. } => {
+            members.iter().map(|m| m.name.as_deref()).collect()
+        }
+        _ => Vec::new(),
+    };
+    assert_eq!(members, [Some("a"), Some("b")]);
+    let constant = module.constants.iter().find(|&(_, c)| c.name.is_some());
+    assert_eq!(constant.unwrap().1.name.as_deref(), Some("b"));
+
+    let (_, shade) = module.functions.iter().next().unwrap();
+    assert_eq!(shade.name.as_deref(), Some("d"));
+    let arguments: Vec<_> = shade.arguments.iter().map(|a| a.name.as_deref()).collect();
+    assert_eq!(arguments, [Some("e"), Some("f")]);
+    let named: Vec<_> = shade
+        .named_expressions
+        .values()
+        .map(|n| n.as_str())
+        .collect();
+    assert_eq!(named, ["g"]);
+
+    let ep = &module.entry_points[0];
+    assert_eq!(ep.name, "main");
+    assert_eq!(ep.function.arguments[0].name.as_deref(), Some("e"));
+    let (_, local) = ep.function.local_variables.iter().next().unwrap();
+    assert_eq!(local.name.as_deref(), Some("f"));
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    // A global never takes the name of an entry point.
+    let mut module = crate::front::wgsl::parse_str(
+        "
+        var<private> value: f32;
+
+        @compute @workgroup_size(1)
+        fn a() {
+            value = 1.0;
+        }
+        ",
+    )
+    .unwrap();
+    minify_names(&mut module);
+    let (_, value) = module.global_variables.iter().next().unwrap();
+    assert_eq!(value.name.as_deref(), Some("b"));
+    assert_eq!(module.entry_points[0].name, "a");
+}
@@ -2,19 +2,43 @@
 [`Module`](super::Module) processing functionality.
 */
 
+mod array_sizes;
+mod collapse_loops;
+mod cse;
+mod fold_conversions;
+mod hoist;
 pub mod index;
+mod interface;
 mod layouter;
+mod lower_compare;
+mod lower_math;
+mod minify;
 mod namer;
+mod self_stores;
+mod strip;
 mod terminator;
 mod typifier;
+mod vectorize;
 
 use std::cmp::PartialEq;
 
+pub use array_sizes::resolve_array_sizes;
+pub use collapse_loops::collapse_single_iteration_loops;
+pub use cse::eliminate_common_subexpressions;
+pub use fold_conversions::fold_conversions;
+pub use hoist::hoist_loop_invariants;
 pub use index::{BoundsCheckPolicies, BoundsCheckPolicy, IndexableLength, IndexableLengthError};
+pub use interface::{sort_interface, InterfaceIndex, InterfaceOrder};
 pub use layouter::{Alignment, LayoutError, LayoutErrorInner, Layouter, TypeLayout};
+pub use lower_compare::lower_depth_comparisons;
+pub use lower_math::lower_math_functions;
+pub use minify::minify_names;
 pub use namer::{EntryPointIndex, NameKey, Namer};
+pub use self_stores::remove_self_stores;
+pub use strip::strip_names;
 pub use terminator::ensure_block_returns;
 pub use typifier::{ResolveContext, ResolveError, TypeResolution};
+pub use vectorize::vectorize_scalar_operations;
 
 impl From<super::StorageFormat> for super::ScalarKind {
     fn from(format: super::StorageFormat) -> Self {
@@ -368,6 +392,124 @@ impl crate::Function {
     }
 }
 
+/// Resolve the expression types of `fun`, if any of its expressions matches
+/// `needs_types`.
+///
+/// Returns `None` if none of them does, or if some type can't be resolved.
+pub(super) fn resolve_types(
+    module: &crate::Module,
+    fun: &crate::Function,
+    needs_types: impl Fn(&crate::Expression) -> bool,
+) -> Option<crate::front::Typifier> {
+    if !fun.expressions.iter().any(|(_, expr)| needs_types(expr)) {
+        return None;
+    }
+
+    let ctx = ResolveContext {
+        constants: &module.constants,
+        types: &module.types,
+        global_vars: &module.global_variables,
+        local_vars: &fun.local_variables,
+        functions: &module.functions,
+        arguments: &fun.arguments,
+    };
+    let mut typifier = crate::front::Typifier::new();
+    for (handle, _) in fun.expressions.iter() {
+        typifier.grow(handle, &fun.expressions, &ctx).ok()?;
+    }
+    Some(typifier)
+}
+
+/// Rebuild the expression arena of `fun` on top of `expressions`, replacing
+/// the old expressions `lower` returns a new one for.
+///
+/// `lower` is called with the new arena, the new expression for each of the
+/// old ones so far, and the handle, value and span of the old expression. It
+/// may append the operands of the new expression to the arena. The other
+/// expressions are copied over, and the statements of `fun` are updated to
+/// refer to and emit the new expressions.
+pub(super) fn rebuild_expressions(
+    fun: &mut crate::Function,
+    mut expressions: crate::Arena<crate::Expression>,
+    mut lower: impl FnMut(
+        &mut crate::Arena<crate::Expression>,
+        &[crate::Handle<crate::Expression>],
+        crate::Handle<crate::Expression>,
+        &crate::Expression,
+        crate::Span,
+    ) -> Option<crate::Expression>,
+) {
+    let old_expressions = std::mem::take(&mut fun.expressions);
+    let mut remap = Vec::with_capacity(old_expressions.len());
+    // The first new expression evaluating each old one.
+    let mut first = Vec::with_capacity(old_expressions.len());
+    for (handle, expr) in old_expressions.iter() {
+        let span = old_expressions.get_span(handle);
+        let start = expressions.len();
+        let new_expr = lower(&mut expressions, &remap, handle, expr, span).unwrap_or_else(|| {
+            let mut expr = expr.clone();
+            cse::remap_expression(&mut expr, &remap);
+            expr
+        });
+        let new_handle = expressions.append(new_expr, span);
+        first.push(expressions.range_from(start).next().unwrap());
+        remap.push(new_handle);
+    }
+
+    fun.expressions = expressions;
+    cse::remap_block(&mut fun.body, &remap);
+    remap_emitted(&mut fun.body, &first, &remap);
+    fun.named_expressions = std::mem::take(&mut fun.named_expressions)
+        .into_iter()
+        .map(|(handle, name)| (remap[handle.index()], name))
+        .collect();
+}
+
+/// Replace the ranges of old expressions emitted in `block` with the ranges
+/// of the new expressions evaluating them.
+fn remap_emitted(
+    block: &mut crate::Block,
+    first: &[crate::Handle<crate::Expression>],
+    remap: &[crate::Handle<crate::Expression>],
+) {
+    for statement in block.iter_mut() {
+        match *statement {
+            crate::Statement::Emit(ref mut range) => {
+                let mut handles = range.clone();
+                if let Some(start) = handles.next() {
+                    let end = handles.last().unwrap_or(start);
+                    *range = crate::arena::Range::new_from_bounds(
+                        first[start.index()],
+                        remap[end.index()],
+                    );
+                }
+            }
+            crate::Statement::Block(ref mut b) => remap_emitted(b, first, remap),
+            crate::Statement::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                remap_emitted(accept, first, remap);
+                remap_emitted(reject, first, remap);
+            }
+            crate::Statement::Switch { ref mut cases, .. } => {
+                for case in cases.iter_mut() {
+                    remap_emitted(&mut case.body, first, remap);
+                }
+            }
+            crate::Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                remap_emitted(body, first, remap);
+                remap_emitted(continuing, first, remap);
+            }
+            _ => {}
+        }
+    }
+}
+
 impl crate::SampleLevel {
     pub const fn implicit_derivatives(&self) -> bool {
         match *self {
@@ -259,3 +259,55 @@ fn test() {
     assert_eq!(namer.call("x"), "x_1");
     assert_eq!(namer.call("x1"), "x1_");
 }
+
+#[test]
+fn test_shadowing() {
+    let mut module = crate::Module::default();
+    let ty = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Float,
+                width: 4,
+            },
+        },
+        Default::default(),
+    );
+    let global = module.global_variables.append(
+        crate::GlobalVariable {
+            name: Some("value".to_string()),
+            space: crate::AddressSpace::Private,
+            binding: None,
+            ty,
+            init: None,
+        },
+        Default::default(),
+    );
+    let mut fun = crate::Function {
+        name: Some("f".to_string()),
+        ..Default::default()
+    };
+    fun.arguments.push(crate::FunctionArgument {
+        name: Some("value".to_string()),
+        ty,
+        binding: None,
+    });
+    let local = fun.local_variables.append(
+        crate::LocalVariable {
+            name: Some("value".to_string()),
+            ty,
+            init: None,
+        },
+        Default::default(),
+    );
+    let fun = module.functions.append(fun, Default::default());
+
+    let mut names = FastHashMap::default();
+    Namer::default().reset(&module, &[], &[], &mut names);
+    let global_name = &names[&NameKey::GlobalVariable(global)];
+    let argument_name = &names[&NameKey::FunctionArgument(fun, 0)];
+    let local_name = &names[&NameKey::FunctionLocal(fun, local)];
+    assert_ne!(global_name, argument_name);
+    assert_ne!(global_name, local_name);
+    assert_ne!(argument_name, local_name);
+}
@@ -0,0 +1,149 @@
+use crate::arena::Handle;
+use crate::{Arena, Block, Expression, Statement};
+
+/// Remove the stores of the function that write back the value just loaded
+/// from the same pointer, like `x = x;`.
+///
+/// A `Store` is removed when its value is a `Load` emitted by the `Emit`
+/// statements right before it, so that nothing can write to the pointer in
+/// between, and both pointers are provably the same: either the same
+/// expression, or the same chain of accesses with the same indices into the
+/// same variable.
+///
+/// The function needs to be validated again afterwards.
+pub fn remove_self_stores(fun: &mut crate::Function) {
+    remove_in_block(&mut fun.body, &fun.expressions);
+}
+
+fn remove_in_block(block: &mut Block, expressions: &Arena<Expression>) {
+    let mut index = 0;
+    while index < block.len() {
+        let remove = match block[index] {
+            Statement::Block(ref mut b) => {
+                remove_in_block(b, expressions);
+                false
+            }
+            Statement::If {
+                ref mut accept,
+                ref mut reject,
+                ..
+            } => {
+                remove_in_block(accept, expressions);
+                remove_in_block(reject, expressions);
+                false
+            }
+            Statement::Switch { ref mut cases, .. } => {
+                for case in cases.iter_mut() {
+                    remove_in_block(&mut case.body, expressions);
+                }
+                false
+            }
+            Statement::Loop {
+                ref mut body,
+                ref mut continuing,
+            } => {
+                remove_in_block(body, expressions);
+                remove_in_block(continuing, expressions);
+                false
+            }
+            Statement::Store { pointer, value } => match expressions[value] {
+                Expression::Load { pointer: source } => {
+                    same_pointer(pointer, source, expressions) && just_emitted(value, block, index)
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        if remove {
+            block.cull(index..index + 1);
+        } else {
+            index += 1;
+        }
+    }
+}
+
+/// Return whether `value` is evaluated by the `Emit` statements right before
+/// the statement at `index` of `block`.
+fn just_emitted(value: Handle<Expression>, block: &Block, index: usize) -> bool {
+    for statement in block.iter().take(index).rev() {
+        match *statement {
+            Statement::Emit(ref range) => {
+                if range.clone().any(|handle| handle == value) {
+                    return true;
+                }
+            }
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Return whether the pointers `a` and `b` always point to the same location.
+fn same_pointer(
+    a: Handle<Expression>,
+    b: Handle<Expression>,
+    expressions: &Arena<Expression>,
+) -> bool {
+    if a == b {
+        return true;
+    }
+    match (&expressions[a], &expressions[b]) {
+        (
+            &Expression::Access { base, index },
+            &Expression::Access {
+                base: other_base,
+                index: other_index,
+            },
+        ) => index == other_index && same_pointer(base, other_base, expressions),
+        (
+            &Expression::AccessIndex { base, index },
+            &Expression::AccessIndex {
+                base: other_base,
+                index: other_index,
+            },
+        ) => index == other_index && same_pointer(base, other_base, expressions),
+        (&Expression::GlobalVariable(var), &Expression::GlobalVariable(other)) => var == other,
+        (&Expression::LocalVariable(var), &Expression::LocalVariable(other)) => var == other,
+        _ => false,
+    }
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_remove_self_stores() {
+    let mut module = crate::front::wgsl::parse_str(
+        "
+        var<private> data: array<vec4<f32>, 4>;
+
+        fn main(i: i32) -> f32 {
+            var x: f32 = 1.0;
+            var y: f32 = 2.0;
+            x = x;
+            data[i].y = data[i].y;
+            x = y;
+            data[i].x = data[i].y;
+            return x + data[0].x;
+        }
+        ",
+    )
+    .unwrap();
+
+    let stores = |block: &Block| {
+        block
+            .iter()
+            .filter(|statement| matches!(**statement, Statement::Store { .. }))
+            .count()
+    };
+    let (_, main) = module.functions.iter_mut().next().unwrap();
+    assert_eq!(stores(&main.body), 4);
+    remove_self_stores(main);
+    // The stores of other values are kept.
+    assert_eq!(stores(&main.body), 2);
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
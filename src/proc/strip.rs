@@ -0,0 +1,244 @@
+use crate::arena::Handle;
+
+/// Remove all optional names from the module.
+///
+/// This clears the names of types, struct members, constants, global
+/// variables, functions, arguments, local variables and named expressions,
+/// leaving only the entry point names, which are needed to refer to the entry
+/// points from outside of the module. Backends generate names for everything
+/// else, so the output is functionally the same.
+///
+/// Types that only differed by name are merged.
+pub fn strip_names(module: &mut crate::Module) {
+    let old_types = std::mem::take(&mut module.types);
+    let mut type_map = Vec::with_capacity(old_types.len());
+    for (handle, ty) in old_types.iter() {
+        let mut inner = remap_type_inner(&ty.inner, &type_map);
+        if let crate::TypeInner::Struct {
+            ref mut members, ..
+        } = inner
+        {
+            for member in members.iter_mut() {
+                member.name = None;
+            }
+        }
+        let new_handle = module.types.insert(
+            crate::Type { name: None, inner },
+            old_types.get_span(handle),
+        );
+        type_map.push(new_handle);
+    }
+    remap_types(module, &type_map);
+
+    for (_, constant) in module.constants.iter_mut() {
+        constant.name = None;
+    }
+
+    for (_, var) in module.global_variables.iter_mut() {
+        var.name = None;
+    }
+
+    for (_, fun) in module.functions.iter_mut() {
+        strip_function(fun);
+    }
+
+    for ep in module.entry_points.iter_mut() {
+        strip_function(&mut ep.function);
+    }
+}
+
+fn strip_function(fun: &mut crate::Function) {
+    fun.name = None;
+    for argument in fun.arguments.iter_mut() {
+        argument.name = None;
+    }
+    for (_, local) in fun.local_variables.iter_mut() {
+        local.name = None;
+    }
+    fun.named_expressions.clear();
+}
+
+/// Replace the type handles used by the module with the ones in `type_map`,
+/// indexed by the old handles.
+pub(super) fn remap_types(module: &mut crate::Module, type_map: &[Handle<crate::Type>]) {
+    for (_, constant) in module.constants.iter_mut() {
+        if let crate::ConstantInner::Composite { ref mut ty, .. } = constant.inner {
+            *ty = type_map[ty.index()];
+        }
+    }
+
+    for (_, var) in module.global_variables.iter_mut() {
+        var.ty = type_map[var.ty.index()];
+    }
+
+    for (_, fun) in module.functions.iter_mut() {
+        remap_function_types(fun, type_map);
+    }
+
+    for ep in module.entry_points.iter_mut() {
+        remap_function_types(&mut ep.function, type_map);
+    }
+}
+
+fn remap_function_types(fun: &mut crate::Function, type_map: &[Handle<crate::Type>]) {
+    for argument in fun.arguments.iter_mut() {
+        argument.ty = type_map[argument.ty.index()];
+    }
+    if let Some(ref mut result) = fun.result {
+        result.ty = type_map[result.ty.index()];
+    }
+    for (_, local) in fun.local_variables.iter_mut() {
+        local.ty = type_map[local.ty.index()];
+    }
+    for (_, expr) in fun.expressions.iter_mut() {
+        if let crate::Expression::Compose { ref mut ty, .. } = *expr {
+            *ty = type_map[ty.index()];
+        }
+    }
+}
+
+/// Copy `inner`, replacing the type handles it refers to with the ones in
+/// `type_map`.
+pub(super) fn remap_type_inner(
+    inner: &crate::TypeInner,
+    type_map: &[Handle<crate::Type>],
+) -> crate::TypeInner {
+    use crate::TypeInner as Ti;
+    match *inner {
+        Ti::Pointer { base, space } => Ti::Pointer {
+            base: type_map[base.index()],
+            space,
+        },
+        Ti::Array { base, size, stride } => Ti::Array {
+            base: type_map[base.index()],
+            size,
+            stride,
+        },
+        Ti::Struct { ref members, span } => Ti::Struct {
+            members: members
+                .iter()
+                .map(|member| crate::StructMember {
+                    name: member.name.clone(),
+                    ty: type_map[member.ty.index()],
+                    binding: member.binding.clone(),
+                    offset: member.offset,
+                })
+                .collect(),
+            span,
+        },
+        Ti::BindingArray { base, size } => Ti::BindingArray {
+            base: type_map[base.index()],
+            size,
+        },
+        Ti::Scalar { kind, width } => Ti::Scalar { kind, width },
+        Ti::Vector { size, kind, width } => Ti::Vector { size, kind, width },
+        Ti::Matrix {
+            columns,
+            rows,
+            width,
+        } => Ti::Matrix {
+            columns,
+            rows,
+            width,
+        },
+        Ti::Atomic { kind, width } => Ti::Atomic { kind, width },
+        Ti::ValuePointer {
+            size,
+            kind,
+            width,
+            space,
+        } => Ti::ValuePointer {
+            size,
+            kind,
+            width,
+            space,
+        },
+        Ti::Image {
+            dim,
+            arrayed,
+            class,
+        } => Ti::Image {
+            dim,
+            arrayed,
+            class,
+        },
+        Ti::Sampler { comparison } => Ti::Sampler { comparison },
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "validate",
+    feature = "wgsl-in",
+    feature = "wgsl-out",
+    feature = "msl-out"
+))]
+#[test]
+fn test_strip_names() {
+    let source = "
+        struct VertexOutput {
+            @builtin(position) position: vec4<f32>,
+            @location(0) color: vec4<f32>,
+        }
+
+        struct FragmentInput {
+            @builtin(position) position: vec4<f32>,
+            @location(0) color: vec4<f32>,
+        }
+
+        let scale: f32 = 0.5;
+
+        @group(0) @binding(0)
+        var<uniform> tint: vec4<f32>;
+
+        fn shade(color: vec4<f32>) -> vec4<f32> {
+            var result = color * tint;
+            let scaled = result * scale;
+            return scaled;
+        }
+
+        @vertex
+        fn vs_main(@location(0) pos: vec4<f32>) -> VertexOutput {
+            return VertexOutput(pos, vec4<f32>(1.0));
+        }
+
+        @fragment
+        fn fs_main(input: FragmentInput) -> @location(0) vec4<f32> {
+            return shade(input.color);
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    let type_count = module.types.len();
+    strip_names(&mut module);
+
+    // The two interface structs only differed by name.
+    assert_eq!(module.types.len(), type_count - 1);
+    assert!(module
+        .functions
+        .iter()
+        .all(|(_, fun)| fun.name.is_none() && fun.named_expressions.is_empty()));
+    let entry_point_names: Vec<_> = module
+        .entry_points
+        .iter()
+        .map(|ep| ep.name.as_str())
+        .collect();
+    assert_eq!(entry_point_names, ["vs_main", "fs_main"]);
+
+    let info = crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    let wgsl =
+        crate::back::wgsl::write_string(&module, &info, crate::back::wgsl::WriterFlags::empty())
+            .unwrap();
+    assert!(wgsl.contains("fn vs_main("));
+    assert!(wgsl.contains("fn fs_main("));
+
+    let mut writer = crate::back::msl::Writer::new(String::new());
+    writer
+        .write(&module, &info, &Default::default(), &Default::default())
+        .unwrap();
+}
@@ -14,6 +14,7 @@ pub fn ensure_block_returns(block: &mut crate::Block) {
             condition: _,
             ref mut accept,
             ref mut reject,
+            ..
         }) => {
             ensure_block_returns(accept);
             ensure_block_returns(reject);
@@ -0,0 +1,589 @@
+use crate::arena::{Arena, Handle, Range, UniqueArena};
+use crate::front::Typifier;
+use crate::{BinaryOperator, Block, Expression, FastHashMap, Statement, TypeInner};
+
+/// Fuse componentwise scalar operations on vectors into vector operations.
+///
+/// Two patterns are recognized, where `op` is the same arithmetic or bitwise
+/// operator for every component, and the components appear in order:
+///
+/// - `vecN(a.x op b.x, a.y op b.y, ...)` becomes `a op b`, if `a` and `b`
+///   are values rather than components loaded through pointers.
+/// - `r.x = a.x op b.x; r.y = a.y op b.y; ...` becomes `r = a op b`, as long
+///   as nothing between the stores reads from `r`.
+///
+/// Naively translated shaders often load `a` and `b` again for every
+/// component. Such loads are considered to produce the same value if
+/// nothing can write to the loaded variable in between.
+///
+/// The replaced expressions are left in the arena. The module needs to be
+/// validated again afterwards.
+pub fn vectorize_scalar_operations(module: &mut crate::Module) {
+    let handles: Vec<_> = module.functions.iter().map(|(handle, _)| handle).collect();
+    for handle in handles {
+        if let Some(typifier) = super::resolve_types(module, &module.functions[handle], |_| true) {
+            vectorize_function(&mut module.functions[handle], &module.types, &typifier);
+        }
+    }
+    for index in 0..module.entry_points.len() {
+        if let Some(typifier) =
+            super::resolve_types(module, &module.entry_points[index].function, |_| true)
+        {
+            vectorize_function(
+                &mut module.entry_points[index].function,
+                &module.types,
+                &typifier,
+            );
+        }
+    }
+}
+
+fn vectorize_function(
+    fun: &mut crate::Function,
+    types: &UniqueArena<crate::Type>,
+    typifier: &Typifier,
+) {
+    let mut vectorizer = Vectorizer {
+        types,
+        typifier,
+        resolved: fun.expressions.len(),
+        expressions: &mut fun.expressions,
+    };
+    vectorizer.block(&mut fun.body);
+}
+
+/// The size, kind and width of a vector type.
+type VectorType = (crate::VectorSize, crate::ScalarKind, crate::Bytes);
+
+/// The variable a pointer expression points into.
+#[derive(Clone, Copy, PartialEq)]
+enum Root {
+    Global(Handle<crate::GlobalVariable>),
+    Local(Handle<crate::LocalVariable>),
+}
+
+/// Return true if a store through a pointer into `stored` may change what a
+/// load through a pointer into `loaded` reads. Unknown roots may alias
+/// anything.
+fn may_alias(stored: Option<Root>, loaded: Option<Root>) -> bool {
+    match (stored, loaded) {
+        (Some(stored), Some(loaded)) => stored == loaded,
+        _ => true,
+    }
+}
+
+/// One side of a componentwise operation.
+#[derive(Clone, Copy)]
+enum Operand {
+    /// A component of this vector value.
+    Value(Handle<Expression>),
+    /// A component loaded through this vector pointer.
+    Pointer(Handle<Expression>),
+}
+
+/// A binary operation on the same component of two vectors.
+#[derive(Clone, Copy)]
+struct ComponentOp {
+    op: BinaryOperator,
+    left: Operand,
+    right: Operand,
+    /// The expressions extracting the component for each side.
+    left_component: Handle<Expression>,
+    right_component: Handle<Expression>,
+}
+
+struct Vectorizer<'a> {
+    types: &'a UniqueArena<crate::Type>,
+    typifier: &'a Typifier,
+    /// The number of expressions `typifier` has types for.
+    resolved: usize,
+    expressions: &'a mut Arena<Expression>,
+}
+
+impl Vectorizer<'_> {
+    fn block(&mut self, block: &mut Block) {
+        for statement in block.iter_mut() {
+            match *statement {
+                Statement::Block(ref mut b) => self.block(b),
+                Statement::If {
+                    ref mut accept,
+                    ref mut reject,
+                    ..
+                } => {
+                    self.block(accept);
+                    self.block(reject);
+                }
+                Statement::Switch { ref mut cases, .. } => {
+                    for case in cases.iter_mut() {
+                        self.block(&mut case.body);
+                    }
+                }
+                Statement::Loop {
+                    ref mut body,
+                    ref mut continuing,
+                } => {
+                    self.block(body);
+                    self.block(continuing);
+                }
+                _ => {}
+            }
+        }
+
+        let emitted_at = emission_indices(block);
+        let mut loads = Vec::new();
+        for (index, statement) in block.iter().enumerate() {
+            if let Statement::Emit(ref range) = *statement {
+                for handle in range.clone() {
+                    if let Some(fused) = self.match_compose(handle, block, index, &emitted_at) {
+                        let span = self.expressions.get_span(handle);
+                        let start = self.expressions.len();
+                        let (left, right) = self.fused_operands(fused, span);
+                        self.expressions[handle] = Expression::Binary {
+                            op: fused.op,
+                            left,
+                            right,
+                        };
+                        if self.expressions.len() > start {
+                            loads.push((index, self.new_range(start)));
+                        }
+                    }
+                }
+            }
+        }
+        // Evaluate the new loads right before the statement that needs them.
+        for (index, range) in loads.into_iter().rev() {
+            block.splice(index..index, Block::from_vec(vec![Statement::Emit(range)]));
+        }
+
+        let mut emitted_at = emission_indices(block);
+        let mut index = 0;
+        while index < block.len() {
+            if let Some((pointer, fused, stores)) = self.match_stores(block, index, &emitted_at) {
+                let span = match block[index] {
+                    Statement::Store { value, .. } => self.expressions.get_span(value),
+                    _ => unreachable!(),
+                };
+                let start = self.expressions.len();
+                let (left, right) = self.fused_operands(fused, span);
+                let value = self.expressions.append(
+                    Expression::Binary {
+                        op: fused.op,
+                        left,
+                        right,
+                    },
+                    span,
+                );
+                for &store in stores.iter().rev() {
+                    block.cull(store..=store);
+                }
+                block[index] = Statement::Store { pointer, value };
+                let emit = Statement::Emit(self.new_range(start));
+                block.splice(index..index, Block::from_vec(vec![emit]));
+                emitted_at = emission_indices(block);
+                index += 1;
+            }
+            index += 1;
+        }
+    }
+
+    /// Return the range of expressions appended since there were `start`.
+    fn new_range(&self, start: usize) -> Range<Expression> {
+        let mut handles = self
+            .expressions
+            .iter()
+            .skip(start)
+            .map(|(handle, _)| handle);
+        let first = handles.next().unwrap();
+        Range::new_from_bounds(first, handles.last().unwrap_or(first))
+    }
+
+    /// Return the vector operands of `fused`, loading them if needed.
+    fn fused_operands(
+        &mut self,
+        fused: ComponentOp,
+        span: crate::Span,
+    ) -> (Handle<Expression>, Handle<Expression>) {
+        let mut operand = |operand| match operand {
+            Operand::Value(value) => value,
+            Operand::Pointer(pointer) => {
+                self.expressions.append(Expression::Load { pointer }, span)
+            }
+        };
+        (operand(fused.left), operand(fused.right))
+    }
+
+    /// Return the vector type of `operand`, if it has one.
+    fn operand_type(&self, operand: Operand) -> Option<VectorType> {
+        match operand {
+            Operand::Value(value) => self.vector_type(value),
+            Operand::Pointer(pointer) => self.pointee_vector_type(pointer),
+        }
+    }
+
+    /// Return the vector type of `handle`, if it has one.
+    fn vector_type(&self, handle: Handle<Expression>) -> Option<VectorType> {
+        if handle.index() >= self.resolved {
+            return None;
+        }
+        match *self.typifier.get(handle, self.types) {
+            TypeInner::Vector { size, kind, width } => Some((size, kind, width)),
+            _ => None,
+        }
+    }
+
+    /// Return the vector type `handle` points to, if it does.
+    fn pointee_vector_type(&self, handle: Handle<Expression>) -> Option<VectorType> {
+        if handle.index() >= self.resolved {
+            return None;
+        }
+        match *self.typifier.get(handle, self.types) {
+            TypeInner::Pointer { base, .. } => match self.types[base].inner {
+                TypeInner::Vector { size, kind, width } => Some((size, kind, width)),
+                _ => None,
+            },
+            TypeInner::ValuePointer {
+                size: Some(size),
+                kind,
+                width,
+                ..
+            } => Some((size, kind, width)),
+            _ => None,
+        }
+    }
+
+    /// Match `left[index] op right[index]`, for an operator that applies
+    /// componentwise to vectors.
+    fn component_op(&self, handle: Handle<Expression>, index: u32) -> Option<ComponentOp> {
+        let (op, left, right) = match self.expressions[handle] {
+            Expression::Binary { op, left, right } => (op, left, right),
+            _ => return None,
+        };
+        match op {
+            BinaryOperator::Add
+            | BinaryOperator::Subtract
+            | BinaryOperator::Multiply
+            | BinaryOperator::Divide
+            | BinaryOperator::Modulo
+            | BinaryOperator::And
+            | BinaryOperator::ExclusiveOr
+            | BinaryOperator::InclusiveOr => {}
+            _ => return None,
+        }
+        let operand = |handle| match self.expressions[handle] {
+            Expression::AccessIndex { base, index: i } if i == index => Some(Operand::Value(base)),
+            Expression::Load { pointer } => match self.expressions[pointer] {
+                Expression::AccessIndex { base, index: i } if i == index => {
+                    Some(Operand::Pointer(base))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        Some(ComponentOp {
+            op,
+            left: operand(left)?,
+            right: operand(right)?,
+            left_component: left,
+            right_component: right,
+        })
+    }
+
+    fn root(&self, mut pointer: Handle<Expression>) -> Option<Root> {
+        loop {
+            pointer = match self.expressions[pointer] {
+                Expression::Access { base, .. } | Expression::AccessIndex { base, .. } => base,
+                Expression::GlobalVariable(handle) => return Some(Root::Global(handle)),
+                Expression::LocalVariable(handle) => return Some(Root::Local(handle)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn same_pointer(&self, first: Handle<Expression>, other: Handle<Expression>) -> bool {
+        first == other
+            || match (&self.expressions[first], &self.expressions[other]) {
+                (&Expression::GlobalVariable(a), &Expression::GlobalVariable(b)) => a == b,
+                (&Expression::LocalVariable(a), &Expression::LocalVariable(b)) => a == b,
+                _ => false,
+            }
+    }
+
+    /// Return true if `other` gives the same vector as `first`, when moving
+    /// the evaluation of both to statement `index`.
+    ///
+    /// Loads match if they load the same variable, `clobbered` says it isn't
+    /// written to between them, and only expressions are evaluated between
+    /// the original loads and `index`.
+    fn same_operand(
+        &self,
+        first: Operand,
+        other: Operand,
+        ctx: &MatchContext,
+        clobbered: impl Fn(Option<Root>) -> bool,
+    ) -> bool {
+        let (first_pointer, other_pointer) = match (first, other) {
+            (Operand::Value(a), Operand::Value(b)) if a == b => return true,
+            (Operand::Value(a), Operand::Value(b)) => {
+                match (&self.expressions[a], &self.expressions[b]) {
+                    (&Expression::Load { pointer: p }, &Expression::Load { pointer: q })
+                        if ctx.is_movable(a) && ctx.is_movable(b) =>
+                    {
+                        (p, q)
+                    }
+                    _ => return false,
+                }
+            }
+            (Operand::Pointer(p), Operand::Pointer(q)) => (p, q),
+            _ => return false,
+        };
+        self.same_pointer(first_pointer, other_pointer) && !clobbered(self.root(first_pointer))
+    }
+
+    fn match_compose(
+        &self,
+        handle: Handle<Expression>,
+        block: &Block,
+        index: usize,
+        emitted_at: &FastHashMap<Handle<Expression>, usize>,
+    ) -> Option<ComponentOp> {
+        let components = match self.expressions[handle] {
+            Expression::Compose { ref components, .. } => components,
+            _ => return None,
+        };
+        let ty = self.vector_type(handle)?;
+        if components.len() != ty.0 as usize {
+            return None;
+        }
+
+        let ctx = MatchContext {
+            block,
+            index,
+            emitted_at,
+        };
+        let first = self.component_op(components[0], 0)?;
+        if self.operand_type(first.left)? != ty || self.operand_type(first.right)? != ty {
+            return None;
+        }
+        // The composition is replaced in place, so it can't refer to vectors
+        // loaded after it.
+        if !matches!(
+            (first.left, first.right),
+            (Operand::Value(_), Operand::Value(_))
+        ) {
+            return None;
+        }
+        for (i, &component) in components.iter().enumerate() {
+            let other = self.component_op(component, i as u32)?;
+            if other.op != first.op
+                || !self.same_operand(first.left, other.left, &ctx, |_| false)
+                || !self.same_operand(first.right, other.right, &ctx, |_| false)
+            {
+                return None;
+            }
+        }
+        Some(first)
+    }
+
+    /// Match a store to every component of a vector, starting at `index`.
+    ///
+    /// Returns the vector pointer, the fused operation, and the indices of the
+    /// stores after the first one.
+    fn match_stores(
+        &self,
+        block: &Block,
+        index: usize,
+        emitted_at: &FastHashMap<Handle<Expression>, usize>,
+    ) -> Option<(Handle<Expression>, ComponentOp, Vec<usize>)> {
+        let component_store = |statement: &Statement, i: u32| match *statement {
+            Statement::Store { pointer, value } => match self.expressions[pointer] {
+                Expression::AccessIndex { base, index } if index == i => {
+                    Some((base, self.component_op(value, i)?))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let (pointer, first) = component_store(&block[index], 0)?;
+        let ty = self.pointee_vector_type(pointer)?;
+        let size = ty.0 as u32;
+        if self.operand_type(first.left)? != ty || self.operand_type(first.right)? != ty {
+            return None;
+        }
+        let ctx = MatchContext {
+            block,
+            index,
+            emitted_at,
+        };
+        if !ctx.loads_are_movable(first) {
+            return None;
+        }
+        let root = self.root(pointer);
+        let clobbered = |loaded| may_alias(root, loaded);
+
+        let mut stores = Vec::new();
+        let mut next = 1;
+        for (position, statement) in block.iter().enumerate().skip(index + 1) {
+            if next == size {
+                break;
+            }
+            match *statement {
+                Statement::Emit(ref range) => {
+                    // Loads between the stores would see them being merged.
+                    let reads_clobbered =
+                        range.clone().any(|handle| match self.expressions[handle] {
+                            Expression::Load { pointer } => may_alias(root, self.root(pointer)),
+                            _ => false,
+                        });
+                    if reads_clobbered {
+                        return None;
+                    }
+                }
+                Statement::Store { .. } => {
+                    let (other_pointer, other) = component_store(statement, next)?;
+                    if other.op != first.op
+                        || !self.same_pointer(pointer, other_pointer)
+                        || !ctx.loads_are_movable(other)
+                        || !self.same_operand(first.left, other.left, &ctx, clobbered)
+                        || !self.same_operand(first.right, other.right, &ctx, clobbered)
+                    {
+                        return None;
+                    }
+                    stores.push(position);
+                    next += 1;
+                }
+                _ => return None,
+            }
+        }
+        if next != size {
+            return None;
+        }
+        Some((pointer, first, stores))
+    }
+}
+
+/// Return the index of the statement evaluating each expression in `block`.
+fn emission_indices(block: &Block) -> FastHashMap<Handle<Expression>, usize> {
+    let mut emitted_at = FastHashMap::default();
+    for (index, statement) in block.iter().enumerate() {
+        if let Statement::Emit(ref range) = *statement {
+            for handle in range.clone() {
+                emitted_at.insert(handle, index);
+            }
+        }
+    }
+    emitted_at
+}
+
+/// Where a fused operation is going to be evaluated.
+struct MatchContext<'a> {
+    block: &'a Block,
+    /// The statement the fused operation is evaluated at.
+    index: usize,
+    emitted_at: &'a FastHashMap<Handle<Expression>, usize>,
+}
+
+impl MatchContext<'_> {
+    /// Return true if `handle` is evaluated in this block, and evaluating it
+    /// at `self.index` instead gives the same result.
+    ///
+    /// This is the case if it is evaluated after `self.index`, or only other
+    /// expressions are evaluated in between.
+    fn is_movable(&self, handle: Handle<Expression>) -> bool {
+        match self.emitted_at.get(&handle) {
+            Some(&position) => {
+                position >= self.index
+                    || self.block[position..self.index]
+                        .iter()
+                        .all(|statement| matches!(*statement, Statement::Emit(_)))
+            }
+            None => false,
+        }
+    }
+
+    /// Return true if the components loaded by `component_op` can be loaded
+    /// at `self.index` instead.
+    fn loads_are_movable(&self, component_op: ComponentOp) -> bool {
+        let movable = |operand, component| match operand {
+            Operand::Pointer(_) => self.is_movable(component),
+            Operand::Value(_) => true,
+        };
+        movable(component_op.left, component_op.left_component)
+            && movable(component_op.right, component_op.right_component)
+    }
+}
+
+#[cfg(all(test, feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn test_vectorize_scalar_operations() {
+    fn count_stores(block: &Block) -> usize {
+        block
+            .iter()
+            .filter(|statement| matches!(**statement, Statement::Store { .. }))
+            .count()
+    }
+
+    let source = "
+        var<private> a: vec3<f32>;
+        var<private> b: vec3<f32>;
+
+        fn reloaded() -> vec3<f32> {
+            var r: vec3<f32>;
+            r.x = a.x + b.x;
+            r.y = a.y + b.y;
+            r.z = a.z + b.z;
+            return r + vec3<f32>(a.x * b.x, a.y * b.y, a.z * b.z);
+        }
+
+        fn composed(c: vec3<f32>, d: vec3<f32>) -> vec3<f32> {
+            return vec3<f32>(c.x * d.x, c.y * d.y, c.z * d.z);
+        }
+
+        fn mismatched(c: vec3<f32>, d: vec3<f32>) -> vec3<f32> {
+            var r: vec3<f32>;
+            r.x = c.x + d.x;
+            r.y = c.y - d.y;
+            r.z = c.z + d.z;
+            return vec3<f32>(c.y * d.y, c.x * d.x, c.z * d.z);
+        }
+
+        fn interleaved(c: vec3<f32>, d: vec3<f32>) -> f32 {
+            var r: vec3<f32>;
+            r.x = c.x + d.x;
+            let partial = r;
+            r.y = c.y + d.y;
+            r.z = c.z + d.z;
+            return partial.y;
+        }
+    ";
+    let mut module = crate::front::wgsl::parse_str(source).unwrap();
+    vectorize_scalar_operations(&mut module);
+
+    let mut functions = module.functions.iter().map(|(_, fun)| fun);
+    let reloaded = functions.next().unwrap();
+    assert_eq!(count_stores(&reloaded.body), 1);
+    assert!(reloaded
+        .expressions
+        .iter()
+        .any(|(_, expr)| matches!(*expr, Expression::Compose { .. })));
+    let composed = functions.next().unwrap();
+    assert!(composed
+        .expressions
+        .iter()
+        .all(|(_, expr)| !matches!(*expr, Expression::Compose { .. })));
+    let mismatched = functions.next().unwrap();
+    assert_eq!(count_stores(&mismatched.body), 3);
+    assert!(mismatched
+        .expressions
+        .iter()
+        .any(|(_, expr)| matches!(*expr, Expression::Compose { .. })));
+    let interleaved = functions.next().unwrap();
+    assert_eq!(count_stores(&interleaved.body), 3);
+
+    crate::valid::Validator::new(
+        crate::valid::ValidationFlags::all(),
+        crate::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+}
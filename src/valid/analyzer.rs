@@ -9,7 +9,7 @@ Figures out the following properties:
 use super::{CallError, ExpressionError, FunctionError, ModuleInfo, ShaderStages, ValidationFlags};
 use crate::span::{AddSpan as _, WithSpan};
 use crate::{
-    arena::{Arena, Handle},
+    arena::{Arena, Handle, UniqueArena},
     proc::{ResolveContext, ResolveError, TypeResolution},
 };
 use std::ops;
@@ -207,6 +207,9 @@ pub struct FunctionInfo {
     pub uniformity: Uniformity,
     /// Function may kill the invocation.
     pub may_kill: bool,
+    /// Function or its callees access atomics in workgroup memory, which
+    /// only compute shaders can do.
+    pub uses_workgroup_atomics: bool,
 
     /// All pairs of (texture, sampler) globals that may be used together in
     /// sampling operations by this function and its callees. This includes
@@ -404,6 +407,7 @@ impl FunctionInfo {
         for (mine, other) in self.global_uses.iter_mut().zip(callee.global_uses.iter()) {
             *mine |= *other;
         }
+        self.uses_workgroup_atomics |= callee.uses_workgroup_atomics;
 
         Ok(FunctionUniformity {
             result: callee.uniformity.clone(),
@@ -418,6 +422,21 @@ impl FunctionInfo {
     /// Computes the expression info and stores it in `self.expressions`.
     /// Also, bumps the reference counts on dependent expressions.
     #[allow(clippy::or_fun_call)]
+    /// Return whether `pointer` points to an atomic in workgroup memory.
+    fn is_workgroup_atomic(
+        &self,
+        pointer: Handle<crate::Expression>,
+        types: &UniqueArena<crate::Type>,
+    ) -> bool {
+        match *self.expressions[pointer.index()].ty.inner_with(types) {
+            crate::TypeInner::Pointer {
+                base,
+                space: crate::AddressSpace::WorkGroup,
+            } => matches!(types[base].inner, crate::TypeInner::Atomic { .. }),
+            _ => false,
+        }
+    }
+
     fn process_expression(
         &mut self,
         handle: Handle<crate::Expression>,
@@ -517,7 +536,10 @@ impl FunctionInfo {
             }
             // depends on the builtin or interpolation
             E::FunctionArgument(index) => {
-                let arg = &resolve_context.arguments[index as usize];
+                let arg = resolve_context
+                    .arguments
+                    .get(index as usize)
+                    .ok_or(ExpressionError::InvalidFunctionArgument { index })?;
                 let uniform = match arg.binding {
                     Some(crate::Binding::BuiltIn(built_in)) => match built_in {
                         // per-polygon built-ins are uniform
@@ -544,7 +566,10 @@ impl FunctionInfo {
             E::GlobalVariable(gh) => {
                 use crate::AddressSpace as As;
                 assignable_global = Some(gh);
-                let var = &resolve_context.global_vars[gh];
+                let var = resolve_context
+                    .global_vars
+                    .try_get(gh)
+                    .map_err(|_| ExpressionError::InvalidGlobal(gh))?;
                 let uniform = match var.space {
                     // local data is non-uniform
                     As::Function | As::Private => false,
@@ -565,10 +590,14 @@ impl FunctionInfo {
                 non_uniform_result: Some(handle),
                 requirements: UniformityRequirements::empty(),
             },
-            E::Load { pointer } => Uniformity {
-                non_uniform_result: self.add_ref(pointer),
-                requirements: UniformityRequirements::empty(),
-            },
+            E::Load { pointer } => {
+                self.uses_workgroup_atomics |=
+                    self.is_workgroup_atomic(pointer, resolve_context.types);
+                Uniformity {
+                    non_uniform_result: self.add_ref(pointer),
+                    requirements: UniformityRequirements::empty(),
+                }
+            }
             E::ImageSample {
                 image,
                 sampler,
@@ -707,12 +736,29 @@ impl FunctionInfo {
             },
         };
 
-        let ty = resolve_context.resolve(expression, |h| {
-            self.expressions
-                .get(h.index())
-                .map(|ei| &ei.ty)
-                .ok_or(ResolveError::ExpressionForwardDependency(h))
-        })?;
+        let ty = resolve_context
+            .resolve(expression, |h| {
+                self.expressions
+                    .get(h.index())
+                    .map(|ei| &ei.ty)
+                    .ok_or(ResolveError::ExpressionForwardDependency(h))
+            })
+            .map_err(|error| match error {
+                // Constant indices are checked when resolving the type, report
+                // them like the out of bounds indices of `Access`.
+                ResolveError::OutOfBoundsIndex { expr, index } => {
+                    ExpressionError::IndexOutOfBounds(expr, crate::ScalarValue::Uint(index as _))
+                }
+                // Report the operands themselves, like the checks of the
+                // validator do.
+                ResolveError::IncompatibleOperands(_) => match *expression {
+                    E::Binary { op, left, right } => {
+                        ExpressionError::IncompatibleOperands { op, left, right }
+                    }
+                    _ => error.into(),
+                },
+                other => other.into(),
+            })?;
         self.expressions[handle.index()] = ExpressionInfo {
             uniformity,
             ref_count: 0,
@@ -738,6 +784,7 @@ impl FunctionInfo {
         other_functions: &[FunctionInfo],
         mut disruptor: Option<UniformityDisruptor>,
         expression_arena: &Arena<crate::Expression>,
+        types: &UniqueArena<crate::Type>,
     ) -> Result<FunctionUniformity, WithSpan<FunctionError>> {
         use crate::Statement as S;
 
@@ -789,12 +836,13 @@ impl FunctionInfo {
                     exit: ExitFlags::empty(),
                 },
                 S::Block(ref b) => {
-                    self.process_block(b, other_functions, disruptor, expression_arena)?
+                    self.process_block(b, other_functions, disruptor, expression_arena, types)?
                 }
                 S::If {
                     condition,
                     ref accept,
                     ref reject,
+                    ..
                 } => {
                     let condition_nur = self.add_ref(condition);
                     let branch_disruptor =
@@ -804,12 +852,14 @@ impl FunctionInfo {
                         other_functions,
                         branch_disruptor,
                         expression_arena,
+                        types,
                     )?;
                     let reject_uniformity = self.process_block(
                         reject,
                         other_functions,
                         branch_disruptor,
                         expression_arena,
+                        types,
                     )?;
                     accept_uniformity | reject_uniformity
                 }
@@ -828,6 +878,7 @@ impl FunctionInfo {
                             other_functions,
                             case_disruptor,
                             expression_arena,
+                            types,
                         )?;
                         case_disruptor = if case.fall_through {
                             case_disruptor.or(case_uniformity.exit_disruptor())
@@ -842,14 +893,20 @@ impl FunctionInfo {
                     ref body,
                     ref continuing,
                 } => {
-                    let body_uniformity =
-                        self.process_block(body, other_functions, disruptor, expression_arena)?;
+                    let body_uniformity = self.process_block(
+                        body,
+                        other_functions,
+                        disruptor,
+                        expression_arena,
+                        types,
+                    )?;
                     let continuing_disruptor = disruptor.or(body_uniformity.exit_disruptor());
                     let continuing_uniformity = self.process_block(
                         continuing,
                         other_functions,
                         continuing_disruptor,
                         expression_arena,
+                        types,
                     )?;
                     body_uniformity | continuing_uniformity
                 }
@@ -868,6 +925,7 @@ impl FunctionInfo {
                 // and their results do not affect the function return value,
                 // so we can ignore their non-uniformity.
                 S::Store { pointer, value } => {
+                    self.uses_workgroup_atomics |= self.is_workgroup_atomic(pointer, types);
                     let _ = self.add_ref_impl(pointer, GlobalUse::WRITE);
                     let _ = self.add_ref(value);
                     FunctionUniformity::new()
@@ -910,6 +968,7 @@ impl FunctionInfo {
                     value,
                     result: _,
                 } => {
+                    self.uses_workgroup_atomics |= self.is_workgroup_atomic(pointer, types);
                     let _ = self.add_ref_impl(pointer, GlobalUse::WRITE);
                     let _ = self.add_ref(value);
                     if let crate::AtomicFunction::Exchange { compare: Some(cmp) } = *fun {
@@ -941,6 +1000,7 @@ impl ModuleInfo {
             available_stages: ShaderStages::all(),
             uniformity: Uniformity::new(),
             may_kill: false,
+            uses_workgroup_atomics: false,
             sampling_set: crate::FastHashSet::default(),
             global_uses: vec![GlobalUse::empty(); module.global_variables.len()].into_boxed_slice(),
             expressions: vec![ExpressionInfo::new(); fun.expressions.len()].into_boxed_slice(),
@@ -969,7 +1029,13 @@ impl ModuleInfo {
             }
         }
 
-        let uniformity = info.process_block(&fun.body, &self.functions, None, &fun.expressions)?;
+        let uniformity = info.process_block(
+            &fun.body,
+            &self.functions,
+            None,
+            &fun.expressions,
+            &module.types,
+        )?;
         info.uniformity = uniformity.result;
         info.may_kill = uniformity.exit.contains(ExitFlags::MAY_KILL);
 
@@ -1067,6 +1133,7 @@ fn uniform_control_flow() {
         available_stages: ShaderStages::all(),
         uniformity: Uniformity::new(),
         may_kill: false,
+        uses_workgroup_atomics: false,
         sampling_set: crate::FastHashSet::default(),
         global_uses: vec![GlobalUse::empty(); global_var_arena.len()].into_boxed_slice(),
         expressions: vec![ExpressionInfo::new(); expressions.len()].into_boxed_slice(),
@@ -1110,13 +1177,15 @@ fn uniform_control_flow() {
             },
         ]
         .into(),
+        hint: None,
     };
     assert_eq!(
         info.process_block(
             &vec![stmt_emit1, stmt_if_uniform].into(),
             &[],
             None,
-            &expressions
+            &expressions,
+            &type_arena
         ),
         Ok(FunctionUniformity {
             result: Uniformity {
@@ -1141,13 +1210,15 @@ fn uniform_control_flow() {
         ]
         .into(),
         reject: crate::Block::new(),
+        hint: None,
     };
     assert_eq!(
         info.process_block(
             &vec![stmt_emit2, stmt_if_non_uniform].into(),
             &[],
             None,
-            &expressions
+            &expressions,
+            &type_arena
         ),
         Err(FunctionError::NonUniformControlFlow(
             UniformityRequirements::DERIVATIVE,
@@ -1168,7 +1239,8 @@ fn uniform_control_flow() {
             &vec![stmt_emit3, stmt_return_non_uniform].into(),
             &[],
             Some(UniformityDisruptor::Return),
-            &expressions
+            &expressions,
+            &type_arena
         ),
         Ok(FunctionUniformity {
             result: Uniformity {
@@ -1195,7 +1267,8 @@ fn uniform_control_flow() {
             &vec![stmt_emit4, stmt_assign, stmt_kill, stmt_return_pointer].into(),
             &[],
             Some(UniformityDisruptor::Discard),
-            &expressions
+            &expressions,
+            &type_arena
         ),
         Ok(FunctionUniformity {
             result: Uniformity {
@@ -1207,3 +1280,90 @@ fn uniform_control_flow() {
     );
     assert_eq!(info[non_uniform_global], GlobalUse::READ | GlobalUse::WRITE);
 }
+
+#[test]
+#[cfg(feature = "validate")]
+fn access_address_space_mismatch() {
+    use crate::{Expression as E, TypeInner as Ti};
+
+    let mut module = crate::Module::default();
+    let float = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: Ti::Scalar {
+                kind: crate::ScalarKind::Float,
+                width: 4,
+            },
+        },
+        Default::default(),
+    );
+    let array = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: Ti::Array {
+                base: float,
+                size: crate::ArraySize::Dynamic,
+                stride: 4,
+            },
+        },
+        Default::default(),
+    );
+    let global = module.global_variables.append(
+        crate::GlobalVariable {
+            name: None,
+            init: None,
+            ty: array,
+            space: crate::AddressSpace::Storage {
+                access: crate::StorageAccess::LOAD,
+            },
+            binding: None,
+        },
+        Default::default(),
+    );
+
+    let mut fun = crate::Function::default();
+    let global_expr = fun
+        .expressions
+        .append(E::GlobalVariable(global), Default::default());
+    let access_expr = fun.expressions.append(
+        E::AccessIndex {
+            base: global_expr,
+            index: 0,
+        },
+        Default::default(),
+    );
+
+    let mod_info = ModuleInfo {
+        functions: Vec::new(),
+        entry_points: Vec::new(),
+    };
+    let mut info = mod_info
+        .process_function(
+            &fun,
+            &module,
+            ValidationFlags::all(),
+            super::Capabilities::empty(),
+        )
+        .unwrap();
+    // The typifier keeps the address space, so pretend it didn't.
+    info.expressions[access_expr.index()].ty = TypeResolution::Value(Ti::Pointer {
+        base: float,
+        space: crate::AddressSpace::Function,
+    });
+
+    let validator = super::Validator::new(ValidationFlags::all(), super::Capabilities::empty());
+    let error = validator
+        .validate_expression(
+            access_expr,
+            &fun.expressions[access_expr],
+            &fun,
+            &module,
+            &info,
+            &[],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ExpressionError::AddressSpaceNotPreserved(handle) if handle == access_expr
+    ));
+}
@@ -89,7 +89,7 @@ pub fn validate_compose(
             base,
             size: crate::ArraySize::Constant(handle),
             stride: _,
-        } => {
+        } if constant_arena[handle].specialization.is_none() => {
             let count = constant_arena[handle].to_array_length().unwrap();
             if count as usize != component_resolutions.len() {
                 return Err(ComposeError::ComponentCount {
@@ -21,14 +21,16 @@ pub enum ExpressionError {
     BadDependency(#[from] BadHandle),
     #[error("Base type {0:?} is not compatible with this expression")]
     InvalidBaseType(Handle<crate::Expression>),
-    #[error("Accessing with index {0:?} can't be done")]
-    InvalidIndexType(Handle<crate::Expression>),
+    #[error("Accessing with index {0:?} can't be done, as it is not an integer scalar")]
+    NonIntegerIndex(Handle<crate::Expression>),
     #[error("Accessing index {1:?} is out of {0:?} bounds")]
     IndexOutOfBounds(Handle<crate::Expression>, crate::ScalarValue),
     #[error("The expression {0:?} may only be indexed by a constant")]
     IndexMustBeConstant(Handle<crate::Expression>),
-    #[error("Function argument {0:?} doesn't exist")]
-    FunctionArgumentDoesntExist(u32),
+    #[error("Function argument {index} doesn't exist")]
+    InvalidFunctionArgument { index: u32 },
+    #[error("Global variable {0:?} doesn't exist")]
+    InvalidGlobal(Handle<crate::GlobalVariable>),
     #[error("Loading of {0:?} can't be done")]
     InvalidPointerType(Handle<crate::Expression>),
     #[error("Array length of {0:?} can't be done")]
@@ -43,14 +45,17 @@ pub enum ExpressionError {
     Compose(#[from] super::ComposeError),
     #[error(transparent)]
     IndexableLength(#[from] IndexableLengthError),
-    #[error("Operation {0:?} can't work with {1:?}")]
-    InvalidUnaryOperandType(crate::UnaryOperator, Handle<crate::Expression>),
-    #[error("Operation {0:?} can't work with {1:?} and {2:?}")]
-    InvalidBinaryOperandTypes(
-        crate::BinaryOperator,
-        Handle<crate::Expression>,
-        Handle<crate::Expression>,
-    ),
+    #[error("Operation {op:?} can't work with operands of kind {ty:?}")]
+    InvalidUnaryOperand {
+        op: crate::UnaryOperator,
+        ty: Option<crate::ScalarKind>,
+    },
+    #[error("Operation {op:?} can't work with {left:?} and {right:?}")]
+    IncompatibleOperands {
+        op: crate::BinaryOperator,
+        left: Handle<crate::Expression>,
+        right: Handle<crate::Expression>,
+    },
     #[error("Selecting is not possible")]
     InvalidSelectTypes,
     #[error("Relational argument {0:?} is not a boolean vector")]
@@ -73,6 +78,10 @@ pub enum ExpressionError {
     ExpectedSamplerType(Handle<crate::Type>),
     #[error("Unable to operate on image class {0:?}")]
     InvalidImageClass(crate::ImageClass),
+    #[error("Query {0:?} doesn't apply to image {1:?}")]
+    InvalidImageQuery(crate::ImageQuery, Handle<crate::Type>),
+    #[error("Accessing {0:?} doesn't give a pointer in the address space of its base")]
+    AddressSpaceNotPreserved(Handle<crate::Expression>),
     #[error("Derivatives can only be taken from scalar and vector floats")]
     InvalidDerivative,
     #[error("Image array index parameter is misplaced")]
@@ -107,8 +116,8 @@ pub enum ExpressionError {
     InvalidSampleLevelExactType(Handle<crate::Expression>),
     #[error("Sample level (bias) type {0:?} is not a scalar float")]
     InvalidSampleLevelBiasType(Handle<crate::Expression>),
-    #[error("Sample level (gradient) of {1:?} doesn't match the image dimension {0:?}")]
-    InvalidSampleLevelGradientType(crate::ImageDimension, Handle<crate::Expression>),
+    #[error("Sample gradient {1:?} doesn't match the image dimension {0:?}")]
+    GradientDimensionMismatch(crate::ImageDimension, Handle<crate::Expression>),
     #[error("Unable to cast")]
     InvalidCastArgument,
     #[error("Invalid argument count for {0:?}")]
@@ -161,6 +170,17 @@ impl super::Validator {
             info,
         };
 
+        // Accessing through a pointer gives a pointer in the same space.
+        let check_address_space = |base| -> Result<(), ExpressionError> {
+            if let Some(space) = resolver.resolve(base)?.pointer_space() {
+                let result_space = info[root].ty.inner_with(&module.types).pointer_space();
+                if result_space != Some(space) {
+                    return Err(ExpressionError::AddressSpaceNotPreserved(root));
+                }
+            }
+            Ok(())
+        };
+
         let stages = match *expression {
             E::Access { base, index } => {
                 let base_type = resolver.resolve(base)?;
@@ -168,9 +188,17 @@ impl super::Validator {
                 let dynamic_indexing_restricted = match *base_type {
                     Ti::Vector { .. } => false,
                     Ti::Matrix { .. } | Ti::Array { .. } => true,
-                    Ti::Pointer { .. }
-                    | Ti::ValuePointer { size: Some(_), .. }
-                    | Ti::BindingArray { .. } => false,
+                    Ti::Pointer { base: pointee, .. } => match module.types[pointee].inner {
+                        Ti::Vector { .. }
+                        | Ti::Matrix { .. }
+                        | Ti::Array { .. }
+                        | Ti::BindingArray { .. } => false,
+                        ref other => {
+                            log::error!("Indexing through a pointer to {:?}", other);
+                            return Err(ExpressionError::InvalidBaseType(base));
+                        }
+                    },
+                    Ti::ValuePointer { size: Some(_), .. } | Ti::BindingArray { .. } => false,
                     ref other => {
                         log::error!("Indexing of {:?}", other);
                         return Err(ExpressionError::InvalidBaseType(base));
@@ -184,7 +212,7 @@ impl super::Validator {
                     } => {}
                     ref other => {
                         log::error!("Indexing by {:?}", other);
-                        return Err(ExpressionError::InvalidIndexType(index));
+                        return Err(ExpressionError::NonIntegerIndex(index));
                     }
                 }
                 if dynamic_indexing_restricted
@@ -222,6 +250,7 @@ impl super::Validator {
                     }
                 }
 
+                check_address_space(base)?;
                 ShaderStages::all()
             }
             E::AccessIndex { base, index } => {
@@ -240,7 +269,9 @@ impl super::Validator {
                         Ti::Array {
                             size: crate::ArraySize::Constant(handle),
                             ..
-                        } => module.constants[handle].to_array_length().unwrap(),
+                        } if module.constants[handle].specialization.is_none() => {
+                            module.constants[handle].to_array_length().unwrap()
+                        }
                         Ti::Array { .. } | Ti::BindingArray { .. } => u32::MAX, // can't statically know, but need run-time checks
                         Ti::Pointer { base, .. } if top_level => {
                             resolve_index_limit(module, top, &module.types[base].inner, false)?
@@ -258,9 +289,10 @@ impl super::Validator {
                 if index >= limit {
                     return Err(ExpressionError::IndexOutOfBounds(
                         base,
-                        crate::ScalarValue::Uint(limit as _),
+                        crate::ScalarValue::Uint(index as _),
                     ));
                 }
+                check_address_space(base)?;
                 ShaderStages::all()
             }
             E::Constant(handle) => {
@@ -309,12 +341,14 @@ impl super::Validator {
             }
             E::FunctionArgument(index) => {
                 if index >= function.arguments.len() as u32 {
-                    return Err(ExpressionError::FunctionArgumentDoesntExist(index));
+                    return Err(ExpressionError::InvalidFunctionArgument { index });
                 }
                 ShaderStages::all()
             }
             E::GlobalVariable(handle) => {
-                let _ = module.global_variables.try_get(handle)?;
+                if module.global_variables.try_get(handle).is_err() {
+                    return Err(ExpressionError::InvalidGlobal(handle));
+                }
                 ShaderStages::all()
             }
             E::LocalVariable(handle) => {
@@ -542,30 +576,22 @@ impl super::Validator {
                         ShaderStages::all()
                     }
                     crate::SampleLevel::Gradient { x, y } => {
-                        match *resolver.resolve(x)? {
-                            Ti::Scalar {
-                                kind: Sk::Float, ..
-                            } if num_components == 1 => {}
-                            Ti::Vector {
-                                size,
-                                kind: Sk::Float,
-                                ..
-                            } if size as u32 == num_components => {}
-                            _ => {
-                                return Err(ExpressionError::InvalidSampleLevelGradientType(dim, x))
-                            }
-                        }
-                        match *resolver.resolve(y)? {
-                            Ti::Scalar {
-                                kind: Sk::Float, ..
-                            } if num_components == 1 => {}
-                            Ti::Vector {
-                                size,
-                                kind: Sk::Float,
-                                ..
-                            } if size as u32 == num_components => {}
-                            _ => {
-                                return Err(ExpressionError::InvalidSampleLevelGradientType(dim, y))
+                        for gradient in [x, y] {
+                            match *resolver.resolve(gradient)? {
+                                Ti::Scalar {
+                                    kind: Sk::Float,
+                                    width: 4,
+                                } if num_components == 1 => {}
+                                Ti::Vector {
+                                    size,
+                                    kind: Sk::Float,
+                                    width: 4,
+                                } if size as u32 == num_components => {}
+                                _ => {
+                                    return Err(ExpressionError::GradientDimensionMismatch(
+                                        dim, gradient,
+                                    ))
+                                }
                             }
                         }
                         ShaderStages::all()
@@ -697,7 +723,7 @@ impl super::Validator {
                             crate::ImageQuery::NumSamples => class.is_multisampled(),
                         };
                         if !good {
-                            return Err(ExpressionError::InvalidImageClass(class));
+                            return Err(ExpressionError::InvalidImageQuery(query, ty));
                         }
                     }
                     _ => return Err(ExpressionError::ExpectedImageType(ty)),
@@ -707,14 +733,14 @@ impl super::Validator {
             E::Unary { op, expr } => {
                 use crate::UnaryOperator as Uo;
                 let inner = resolver.resolve(expr)?;
+                // `Not` is the logical negation of booleans and the bitwise
+                // complement of integers.
                 match (op, inner.scalar_kind()) {
-                    (_, Some(Sk::Sint | Sk::Bool))
-                    //TODO: restrict Negate for bools?
-                    | (Uo::Negate, Some(Sk::Float))
-                    | (Uo::Not, Some(Sk::Uint)) => {}
-                    other => {
-                        log::error!("Op {:?} kind {:?}", op, other);
-                        return Err(ExpressionError::InvalidUnaryOperandType(op, expr));
+                    (Uo::Negate, Some(Sk::Sint | Sk::Float))
+                    | (Uo::Not, Some(Sk::Sint | Sk::Uint | Sk::Bool)) => {}
+                    (_, ty) => {
+                        log::error!("Op {:?} kind {:?}", op, ty);
+                        return Err(ExpressionError::InvalidUnaryOperand { op, ty });
                     }
                 }
                 ShaderStages::all()
@@ -809,7 +835,13 @@ impl super::Validator {
                         };
                         kind_match && types_match && left_width == right_width
                     }
-                    Bo::Equal | Bo::NotEqual => left_inner.is_sized() && left_inner == right_inner,
+                    Bo::Equal | Bo::NotEqual => match *left_inner {
+                        Ti::Scalar { .. } | Ti::Vector { .. } => left_inner == right_inner,
+                        ref other => {
+                            log::error!("Op {:?} left type {:?}", op, other);
+                            false
+                        }
+                    },
                     Bo::Less | Bo::LessEqual | Bo::Greater | Bo::GreaterEqual => {
                         match *left_inner {
                             Ti::Scalar { kind, .. } | Ti::Vector { kind, .. } => match kind {
@@ -889,7 +921,7 @@ impl super::Validator {
                         function.expressions[right],
                         right_inner
                     );
-                    return Err(ExpressionError::InvalidBinaryOperandTypes(op, left, right));
+                    return Err(ExpressionError::IncompatibleOperands { op, left, right });
                 }
                 ShaderStages::all()
             }
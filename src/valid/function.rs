@@ -38,6 +38,8 @@ pub enum CallError {
         required: Handle<crate::Type>,
         seen_expression: Handle<crate::Expression>,
     },
+    #[error("Argument {index} points to a different address space than the parameter")]
+    PointerAddressSpaceMismatch { index: usize },
     #[error("The emitted expression doesn't match the call")]
     ExpressionMismatch(Option<Handle<crate::Expression>>),
 }
@@ -47,6 +49,10 @@ pub enum CallError {
 pub enum AtomicError {
     #[error("Pointer {0:?} to atomic is invalid.")]
     InvalidPointer(Handle<crate::Expression>),
+    #[error(
+        "Pointer {0:?} to atomic is in the {1:?} address space, which doesn't allow atomic access"
+    )]
+    InvalidPointerSpace(Handle<crate::Expression>, crate::AddressSpace),
     #[error("Operand {0:?} has invalid type.")]
     InvalidOperand(Handle<crate::Expression>),
     #[error("Result expression {0:?} has already been introduced earlier")]
@@ -61,7 +67,7 @@ pub enum LocalVariableError {
     #[error("Local variable has a type {0:?} that can't be stored in a local variable.")]
     InvalidType(Handle<crate::Type>),
     #[error("Initializer doesn't match the variable type")]
-    InitializerType,
+    InitTypeMismatch,
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
@@ -92,8 +98,14 @@ pub enum FunctionError {
         name: String,
         space: crate::AddressSpace,
     },
+    #[error("Argument or local variable '{name}' shadows a global variable or constant")]
+    ShadowingGlobal { name: String },
+    #[error("Barrier flags {0:?} are not valid")]
+    InvalidBarrier(crate::Barrier),
     #[error("There are instructions after `return`/`break`/`continue`")]
     InstructionsAfterReturn,
+    #[error("The continuing block of the loop can't be reached")]
+    UnreachableContinuing,
     #[error("The `break` is used outside of a `loop` or `switch` context")]
     BreakOutsideOfLoopOrSwitch,
     #[error("The `continue` is used outside of a `loop` context")]
@@ -102,12 +114,16 @@ pub enum FunctionError {
     InvalidReturnSpot,
     #[error("The `return` value {0:?} does not match the function return value")]
     InvalidReturnType(Option<Handle<crate::Expression>>),
-    #[error("The `if` condition {0:?} is not a boolean scalar")]
-    InvalidIfType(Handle<crate::Expression>),
+    #[error("The `return` value {0:?} is present while the function returns nothing, or the other way around")]
+    ReturnValueMismatch(Option<Handle<crate::Expression>>),
+    #[error("The function returns a value, but control can reach the end of its body")]
+    MissingReturn,
+    #[error("The condition {0:?} is not a boolean scalar")]
+    NonBoolCondition(Handle<crate::Expression>),
     #[error("The `switch` value {0:?} is not an integer scalar")]
     InvalidSwitchType(Handle<crate::Expression>),
     #[error("Multiple `switch` cases for {0:?} are present")]
-    ConflictingSwitchCase(i32),
+    DuplicateSwitchCase(i32),
     #[error("The `switch` is missing a `default` case")]
     MissingDefaultCase,
     #[error("Multiple `default` cases are present")]
@@ -116,6 +132,8 @@ pub enum FunctionError {
     LastCaseFallTrough,
     #[error("The pointer {0:?} doesn't relate to a valid destination for a store")]
     InvalidStorePointer(Handle<crate::Expression>),
+    #[error("The pointer {0:?} writes to a `uniform` buffer, which is read-only")]
+    WriteToUniform(Handle<crate::Expression>),
     #[error("The value {0:?} can not be stored")]
     InvalidStoreValue(Handle<crate::Expression>),
     #[error("Store of {value:?} into {pointer:?} doesn't have matching types")]
@@ -125,6 +143,8 @@ pub enum FunctionError {
     },
     #[error("Image store parameters are invalid")]
     InvalidImageStore(#[source] ExpressionError),
+    #[error("The image {0:?} is not a writable storage image")]
+    StoreToNonStorageImage(Handle<crate::Expression>),
     #[error("Call to {function:?} is invalid")]
     InvalidCall {
         function: Handle<crate::Function>,
@@ -133,6 +153,10 @@ pub enum FunctionError {
     },
     #[error("Atomic operation is invalid")]
     InvalidAtomic(#[from] AtomicError),
+    #[error(
+        "Atomics in workgroup memory can't be used in the {stage:?} stage, only in compute shaders"
+    )]
+    AtomicOutsideCompute { stage: crate::ShaderStage },
     #[error(
         "Required uniformity of control flow for {0:?} in {1:?} is not fulfilled because of {2:?}"
     )]
@@ -270,6 +294,12 @@ impl super::Validator {
                     CallError::Argument { index, error }.with_span_handle(expr, context.expressions)
                 })?;
             let arg_inner = &context.types[arg.ty].inner;
+            if let (Some(required), Some(seen)) = (arg_inner.pointer_space(), ty.pointer_space()) {
+                if required != seen {
+                    return Err(CallError::PointerAddressSpaceMismatch { index }
+                        .with_span_handle(expr, context.expressions));
+                }
+            }
             if !ty.equivalent(arg_inner, context.types) {
                 return Err(CallError::ArgumentType {
                     index,
@@ -313,9 +343,9 @@ impl super::Validator {
         context: &BlockContext,
     ) -> Result<(), WithSpan<FunctionError>> {
         let pointer_inner = context.resolve_type(pointer, &self.valid_expression_set)?;
-        let (ptr_kind, ptr_width) = match *pointer_inner {
-            crate::TypeInner::Pointer { base, .. } => match context.types[base].inner {
-                crate::TypeInner::Atomic { kind, width } => (kind, width),
+        let (ptr_kind, ptr_width, ptr_space) = match *pointer_inner {
+            crate::TypeInner::Pointer { base, space } => match context.types[base].inner {
+                crate::TypeInner::Atomic { kind, width } => (kind, width, space),
                 ref other => {
                     log::error!("Atomic pointer to type {:?}", other);
                     return Err(AtomicError::InvalidPointer(pointer)
@@ -331,6 +361,17 @@ impl super::Validator {
             }
         };
 
+        match ptr_space {
+            crate::AddressSpace::WorkGroup => {}
+            crate::AddressSpace::Storage { access }
+                if access.contains(crate::StorageAccess::STORE) => {}
+            other => {
+                return Err(AtomicError::InvalidPointerSpace(pointer, other)
+                    .with_span_handle(pointer, context.expressions)
+                    .into_other())
+            }
+        }
+
         let value_inner = context.resolve_type(value, &self.valid_expression_set)?;
         match *value_inner {
             crate::TypeInner::Scalar { width, kind } if kind == ptr_kind && width == ptr_width => {}
@@ -358,13 +399,14 @@ impl super::Validator {
                 .with_span_handle(result, context.expressions)
                 .into_other());
         }
+        // A comparing exchange also reports whether the exchange happened.
+        let has_comparison = matches!(*fun, crate::AtomicFunction::Exchange { compare: Some(_) });
         match context.expressions[result] {
-            //TODO: support atomic result with comparison
             crate::Expression::AtomicResult {
                 kind,
                 width,
-                comparison: false,
-            } if kind == ptr_kind && width == ptr_width => {}
+                comparison,
+            } if kind == ptr_kind && width == ptr_width && comparison == has_comparison => {}
             _ => {
                 return Err(AtomicError::ResultTypeMismatch(result)
                     .with_span_handle(result, context.expressions)
@@ -408,6 +450,7 @@ impl super::Validator {
                     condition,
                     ref accept,
                     ref reject,
+                    ..
                 } => {
                     match *context.resolve_type(condition, &self.valid_expression_set)? {
                         Ti::Scalar {
@@ -415,7 +458,7 @@ impl super::Validator {
                             width: _,
                         } => {}
                         _ => {
-                            return Err(FunctionError::InvalidIfType(condition)
+                            return Err(FunctionError::NonBoolCondition(condition)
                                 .with_span_handle(condition, context.expressions))
                         }
                     }
@@ -446,7 +489,7 @@ impl super::Validator {
                         match case.value {
                             crate::SwitchValue::Integer(value) => {
                                 if !self.select_cases.insert(value) {
-                                    return Err(FunctionError::ConflictingSwitchCase(value)
+                                    return Err(FunctionError::DuplicateSwitchCase(value)
                                         .with_span_static(
                                             case.body
                                                 .span_iter()
@@ -500,6 +543,13 @@ impl super::Validator {
                 } => {
                     // special handling for block scoping is needed here,
                     // because the continuing{} block inherits the scope
+                    if self.report_dead_code
+                        && !continuing.is_empty()
+                        && !exits(body).intersects(BlockExits::FALL_THROUGH | BlockExits::CONTINUE)
+                    {
+                        return Err(FunctionError::UnreachableContinuing
+                            .with_span_static(span, "loop whose body never continues"));
+                    }
                     let base_expression_count = self.valid_expression_list.len();
                     let pass_through_abilities = context.abilities & ControlFlowAbility::RETURN;
                     stages &= self
@@ -552,7 +602,10 @@ impl super::Validator {
                         (Some(value_inner), Some(expected_inner)) => {
                             value_inner.equivalent(expected_inner, context.types)
                         }
-                        (_, _) => false,
+                        (_, _) => {
+                            return Err(FunctionError::ReturnValueMismatch(value)
+                                .with_span_static(span, "invalid return"));
+                        }
                     };
 
                     if !okay {
@@ -574,7 +627,12 @@ impl super::Validator {
                 S::Kill => {
                     finished = true;
                 }
-                S::Barrier(_) => {
+                S::Barrier(barrier) => {
+                    // An empty set is fine: it is a pure execution barrier.
+                    if crate::Barrier::from_bits(barrier.bits()).is_none() {
+                        return Err(FunctionError::InvalidBarrier(barrier)
+                            .with_span_static(span, "invalid barrier"));
+                    }
                     stages &= super::ShaderStages::COMPUTE;
                 }
                 S::Store { pointer, value } => {
@@ -586,6 +644,13 @@ impl super::Validator {
                         match context.expressions[current] {
                             crate::Expression::Access { base, .. }
                             | crate::Expression::AccessIndex { base, .. } => current = base,
+                            crate::Expression::GlobalVariable(var)
+                                if context.global_vars[var].space
+                                    == crate::AddressSpace::Uniform =>
+                            {
+                                return Err(FunctionError::WriteToUniform(pointer)
+                                    .with_span_handle(pointer, context.expressions))
+                            }
                             crate::Expression::LocalVariable(_)
                             | crate::Expression::GlobalVariable(_)
                             | crate::Expression::FunctionArgument(_) => break,
@@ -727,7 +792,9 @@ impl super::Validator {
                                 }
                             }
                             match class {
-                                crate::ImageClass::Storage { format, .. } => {
+                                crate::ImageClass::Storage { format, access }
+                                    if access.contains(crate::StorageAccess::STORE) =>
+                                {
                                     crate::TypeInner::Vector {
                                         kind: format.into(),
                                         size: crate::VectorSize::Quad,
@@ -735,10 +802,8 @@ impl super::Validator {
                                     }
                                 }
                                 _ => {
-                                    return Err(FunctionError::InvalidImageStore(
-                                        ExpressionError::InvalidImageClass(class),
-                                    )
-                                    .with_span_handle(image, context.expressions));
+                                    return Err(FunctionError::StoreToNonStorageImage(image)
+                                        .with_span_handle(image, context.expressions));
                                 }
                             }
                         }
@@ -809,10 +874,8 @@ impl super::Validator {
             .types
             .get(var.ty.index())
             .ok_or(LocalVariableError::InvalidType(var.ty))?;
-        if !type_info
-            .flags
-            .contains(super::TypeFlags::DATA | super::TypeFlags::SIZED)
-        {
+        let required = super::TypeFlags::DATA | super::TypeFlags::SIZED | super::TypeFlags::COPY;
+        if !type_info.flags.contains(required) {
             return Err(LocalVariableError::InvalidType(var.ty));
         }
 
@@ -824,12 +887,12 @@ impl super::Validator {
                         kind: value.scalar_kind(),
                     };
                     if types[var.ty].inner != ty_inner {
-                        return Err(LocalVariableError::InitializerType);
+                        return Err(LocalVariableError::InitTypeMismatch);
                     }
                 }
                 crate::ConstantInner::Composite { ty, components: _ } => {
                     if ty != var.ty {
-                        return Err(LocalVariableError::InitializerType);
+                        return Err(LocalVariableError::InitTypeMismatch);
                     }
                 }
             }
@@ -894,6 +957,29 @@ impl super::Validator {
             }
         }
 
+        #[cfg(feature = "validate")]
+        if self.report_shadowing {
+            let names = fun
+                .arguments
+                .iter()
+                .filter_map(|argument| argument.name.as_deref())
+                .chain(
+                    fun.local_variables
+                        .iter()
+                        .filter_map(|(_, var)| var.name.as_deref()),
+                );
+            for name in names {
+                if self.global_names.contains(name) {
+                    self.function_warnings.push(
+                        FunctionError::ShadowingGlobal {
+                            name: name.to_string(),
+                        }
+                        .with_span(),
+                    );
+                }
+            }
+        }
+
         self.valid_expression_set.clear();
         self.valid_expression_list.clear();
         for (handle, expr) in fun.expressions.iter() {
@@ -928,7 +1014,80 @@ impl super::Validator {
                 )?
                 .stages;
             info.available_stages &= stages;
+
+            if fun.result.is_some() && exits(&fun.body).contains(BlockExits::FALL_THROUGH) {
+                return Err(FunctionError::MissingReturn.with_span());
+            }
         }
         Ok(info)
     }
 }
+
+#[cfg(feature = "validate")]
+bitflags::bitflags! {
+    /// The ways control can leave a block, other than returning.
+    struct BlockExits: u8 {
+        /// The control can reach the end of the block.
+        const FALL_THROUGH = 0x1;
+        /// The control can break out of the block.
+        const BREAK = 0x2;
+        /// The control can continue out of the block.
+        const CONTINUE = 0x4;
+    }
+}
+
+/// Return the ways control can leave `block`, assuming that it has already
+/// been validated.
+#[cfg(feature = "validate")]
+fn exits(block: &crate::Block) -> BlockExits {
+    use crate::Statement as S;
+
+    let mut result = BlockExits::empty();
+    for statement in block.iter() {
+        let statement_exits = match *statement {
+            S::Return { .. } | S::Kill => BlockExits::empty(),
+            S::Break => BlockExits::BREAK,
+            S::Continue => BlockExits::CONTINUE,
+            S::Block(ref block) => exits(block),
+            S::If {
+                ref accept,
+                ref reject,
+                ..
+            } => exits(accept) | exits(reject),
+            S::Switch { ref cases, .. } => {
+                // There is always a default case, and breaking out of a case
+                // leaves the switch.
+                let mut switch_exits = BlockExits::empty();
+                for case in cases.iter() {
+                    let mut case_exits = exits(&case.body);
+                    if case.fall_through {
+                        case_exits.remove(BlockExits::FALL_THROUGH);
+                    }
+                    if case_exits.contains(BlockExits::BREAK) {
+                        case_exits.remove(BlockExits::BREAK);
+                        case_exits.insert(BlockExits::FALL_THROUGH);
+                    }
+                    switch_exits |= case_exits;
+                }
+                switch_exits
+            }
+            S::Loop {
+                ref body,
+                ref continuing,
+            } => {
+                // Only breaking leaves the loop.
+                if (exits(body) | exits(continuing)).contains(BlockExits::BREAK) {
+                    BlockExits::FALL_THROUGH
+                } else {
+                    BlockExits::empty()
+                }
+            }
+            _ => BlockExits::FALL_THROUGH,
+        };
+        result |= statement_exits - BlockExits::FALL_THROUGH;
+        if !statement_exits.contains(BlockExits::FALL_THROUGH) {
+            return result;
+        }
+    }
+    result | BlockExits::FALL_THROUGH
+}
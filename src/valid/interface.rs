@@ -18,6 +18,14 @@ pub enum GlobalVariableError {
     InvalidUsage(crate::AddressSpace),
     #[error("Type isn't compatible with address space {0:?}")]
     InvalidType(crate::AddressSpace),
+    #[error("Type {0:?} isn't host-shareable, so it can't be in address space {1:?}")]
+    TypeNotHostShareable(Handle<crate::Type>, crate::AddressSpace),
+    #[error("Type {0:?} is not an image, a sampler or an array of them, so it can't be a handle")]
+    NonHandleInHandleSpace(Handle<crate::Type>),
+    #[error("Variables in address space {0:?} can't have an initializer")]
+    InitializerNotAllowed(crate::AddressSpace),
+    #[error("Unsized types like {0:?} must be in the `Storage` address space, not `{1:?}`")]
+    UnsizedType(Handle<crate::Type>, crate::AddressSpace),
     #[error("Type flags {seen:?} do not meet the required {required:?}")]
     MissingTypeFlags {
         required: super::TypeFlags,
@@ -47,14 +55,19 @@ pub enum VaryingError {
     MissingInterpolation,
     #[error("Built-in {0:?} is not available at this stage")]
     InvalidBuiltInStage(crate::BuiltIn),
-    #[error("Built-in type for {0:?} is invalid")]
-    InvalidBuiltInType(crate::BuiltIn),
+    #[error("Built-in {builtin:?} must have the type `{expected}`")]
+    WrongBuiltinType {
+        builtin: crate::BuiltIn,
+        expected: &'static str,
+    },
     #[error("Entry point arguments and return values must all have bindings")]
     MissingBinding,
     #[error("Struct member {0} is missing a binding")]
     MemberMissingBinding(u32),
     #[error("Multiple bindings at location {location} are present")]
     BindingCollision { location: u32 },
+    #[error("Location {location} is out of range, the limit is {max} locations")]
+    LocationTooLarge { location: u32, max: u32 },
     #[error("Built-in {0:?} is present more than once")]
     DuplicateBuiltIn(crate::BuiltIn),
     #[error("Capability {0:?} is not supported")]
@@ -65,10 +78,18 @@ pub enum VaryingError {
 pub enum EntryPointError {
     #[error("Multiple conflicting entry points")]
     Conflict,
+    #[error("Entry point name is empty")]
+    EmptyName,
+    #[error("Entry point name is not a valid identifier")]
+    InvalidName,
     #[error("Early depth test is not applicable")]
     UnexpectedEarlyDepthTest,
+    #[error("Forced early depth test can't be combined with writing the depth")]
+    EarlyDepthTestWithDepthWrite,
     #[error("Workgroup size is not applicable")]
     UnexpectedWorkgroupSize,
+    #[error("Workgroup size has a zero dimension")]
+    ZeroWorkgroupSize,
     #[error("Workgroup size is out of range")]
     OutOfRangeWorkgroupSize,
     #[error("Uses operations forbidden at this stage")]
@@ -81,6 +102,8 @@ pub enum EntryPointError {
     Argument(u32, #[source] VaryingError),
     #[error(transparent)]
     Result(#[from] VaryingError),
+    #[error("Vertex shaders must return a `@builtin(position)` output value")]
+    MissingVertexPosition,
     #[error("Location {location} interpolation of an integer has to be flat")]
     InvalidIntegerInterpolation { location: u32 },
     #[error(transparent)]
@@ -107,6 +130,7 @@ struct VaryingContext<'a> {
     location_mask: &'a mut BitSet,
     built_ins: &'a mut crate::FastHashSet<crate::BuiltIn>,
     capabilities: Capabilities,
+    max_inter_stage_locations: Option<u32>,
 }
 
 impl VaryingContext<'_> {
@@ -136,7 +160,7 @@ impl VaryingContext<'_> {
                 self.built_ins.insert(canonical);
 
                 let width = 4;
-                let (visible, type_good) = match built_in {
+                let (visible, type_good, expected) = match built_in {
                     Bi::BaseInstance | Bi::BaseVertex | Bi::InstanceIndex | Bi::VertexIndex => (
                         self.stage == St::Vertex && !self.output,
                         *ty_inner
@@ -144,6 +168,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Uint,
                                 width,
                             },
+                        "u32",
                     ),
                     Bi::ClipDistance | Bi::CullDistance => (
                         self.stage == St::Vertex && self.output,
@@ -157,6 +182,7 @@ impl VaryingContext<'_> {
                             }
                             _ => false,
                         },
+                        "array<f32>",
                     ),
                     Bi::PointSize => (
                         self.stage == St::Vertex && self.output,
@@ -165,6 +191,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Float,
                                 width,
                             },
+                        "f32",
                     ),
                     Bi::Position { .. } => (
                         match self.stage {
@@ -178,6 +205,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Float,
                                 width,
                             },
+                        "vec4<f32>",
                     ),
                     Bi::ViewIndex => (
                         match self.stage {
@@ -189,6 +217,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Sint,
                                 width,
                             },
+                        "i32",
                     ),
                     Bi::FragDepth => (
                         self.stage == St::Fragment && self.output,
@@ -197,6 +226,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Float,
                                 width,
                             },
+                        "f32",
                     ),
                     Bi::FrontFacing => (
                         self.stage == St::Fragment && !self.output,
@@ -205,6 +235,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Bool,
                                 width: crate::BOOL_WIDTH,
                             },
+                        "bool",
                     ),
                     Bi::PrimitiveIndex => {
                         if !self.capabilities.contains(Capabilities::PRIMITIVE_INDEX) {
@@ -219,6 +250,7 @@ impl VaryingContext<'_> {
                                     kind: Sk::Uint,
                                     width,
                                 },
+                            "u32",
                         )
                     }
                     Bi::SampleIndex => (
@@ -228,6 +260,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Uint,
                                 width,
                             },
+                        "u32",
                     ),
                     Bi::SampleMask => (
                         self.stage == St::Fragment,
@@ -236,6 +269,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Uint,
                                 width,
                             },
+                        "u32",
                     ),
                     Bi::LocalInvocationIndex => (
                         self.stage == St::Compute && !self.output,
@@ -244,6 +278,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Uint,
                                 width,
                             },
+                        "u32",
                     ),
                     Bi::GlobalInvocationId
                     | Bi::LocalInvocationId
@@ -257,6 +292,7 @@ impl VaryingContext<'_> {
                                 kind: Sk::Uint,
                                 width,
                             },
+                        "vec3<u32>",
                     ),
                 };
 
@@ -265,7 +301,10 @@ impl VaryingContext<'_> {
                 }
                 if !type_good {
                     log::warn!("Wrong builtin type: {:?}", ty_inner);
-                    return Err(VaryingError::InvalidBuiltInType(built_in));
+                    return Err(VaryingError::WrongBuiltinType {
+                        builtin: built_in,
+                        expected,
+                    });
                 }
             }
             crate::Binding::Location {
@@ -309,6 +348,15 @@ impl VaryingContext<'_> {
                     }
                     None => return Err(VaryingError::InvalidType(ty)),
                 }
+
+                // Only scalars and vectors make it here, and each of them
+                // takes a single location. Matrices and arrays aren't
+                // IO-shareable, so there is no wider span to account for.
+                if let Some(max) = self.max_inter_stage_locations {
+                    if location >= max {
+                        return Err(VaryingError::LocationTooLarge { location, max });
+                    }
+                }
             }
         }
 
@@ -366,6 +414,36 @@ impl super::Validator {
             index: var.ty.index(),
         })?;
 
+        // Runtime-sized arrays may only be used by storage buffers, as the last
+        // member of a struct or as the whole store type. Non-data types like
+        // images are not sized either, but they are checked below.
+        if type_info.flags.contains(TypeFlags::DATA) && !type_info.flags.contains(TypeFlags::SIZED)
+        {
+            match var.space {
+                crate::AddressSpace::Storage { .. } | crate::AddressSpace::Handle => {}
+                _ => return Err(GlobalVariableError::UnsizedType(var.ty, var.space)),
+            }
+        }
+
+        // The contents of these address spaces are shared with the host, so
+        // their layout needs to be well defined.
+        match var.space {
+            crate::AddressSpace::Storage { .. }
+            | crate::AddressSpace::Uniform
+            | crate::AddressSpace::PushConstant
+                if !type_info.flags.contains(TypeFlags::HOST_SHAREABLE) =>
+            {
+                return Err(GlobalVariableError::TypeNotHostShareable(var.ty, var.space));
+            }
+            _ => {}
+        }
+
+        // Only private variables are initialized by the shader, the others
+        // are shared with the host or the other invocations.
+        if var.init.is_some() && var.space != crate::AddressSpace::Private {
+            return Err(GlobalVariableError::InitializerNotAllowed(var.space));
+        }
+
         let (required_type_flags, is_resource) = match var.space {
             crate::AddressSpace::Function => {
                 return Err(GlobalVariableError::InvalidUsage(var.space))
@@ -406,14 +484,20 @@ impl super::Validator {
                     | crate::TypeInner::Sampler { .. }
                     | crate::TypeInner::BindingArray { .. } => {}
                     _ => {
-                        return Err(GlobalVariableError::InvalidType(var.space));
+                        return Err(GlobalVariableError::NonHandleInHandleSpace(var.ty));
                     }
                 };
                 (TypeFlags::empty(), true)
             }
-            crate::AddressSpace::Private | crate::AddressSpace::WorkGroup => {
+            crate::AddressSpace::Private => {
+                // Resources like textures, samplers and atomics can't be
+                // copied around, so they can't be private.
+                if !type_info.flags.contains(TypeFlags::DATA | TypeFlags::COPY) {
+                    return Err(GlobalVariableError::InvalidType(var.space));
+                }
                 (TypeFlags::DATA | TypeFlags::SIZED, false)
             }
+            crate::AddressSpace::WorkGroup => (TypeFlags::DATA | TypeFlags::SIZED, false),
             crate::AddressSpace::PushConstant => {
                 if !self.capabilities.contains(Capabilities::PUSH_CONSTANT) {
                     return Err(GlobalVariableError::UnsupportedCapability(
@@ -450,6 +534,22 @@ impl super::Validator {
         module: &crate::Module,
         mod_info: &ModuleInfo,
     ) -> Result<FunctionInfo, WithSpan<EntryPointError>> {
+        // Backends have to emit entry point names as they are, since the
+        // pipeline refers to them by name. Which characters are allowed
+        // beyond that depends on the target language.
+        #[cfg(feature = "validate")]
+        {
+            let mut chars = ep.name.chars();
+            match chars.next() {
+                None => return Err(EntryPointError::EmptyName.with_span()),
+                Some(c) if c == '_' || c.is_alphabetic() => {}
+                Some(_) => return Err(EntryPointError::InvalidName.with_span()),
+            }
+            if !chars.all(|c| c == '_' || c.is_alphanumeric()) {
+                return Err(EntryPointError::InvalidName.with_span());
+            }
+        }
+
         #[cfg(feature = "validate")]
         if ep.early_depth_test.is_some() && ep.stage != crate::ShaderStage::Fragment {
             return Err(EntryPointError::UnexpectedEarlyDepthTest.with_span());
@@ -457,11 +557,10 @@ impl super::Validator {
 
         #[cfg(feature = "validate")]
         if ep.stage == crate::ShaderStage::Compute {
-            if ep
-                .workgroup_size
-                .iter()
-                .any(|&s| s == 0 || s > MAX_WORKGROUP_SIZE)
-            {
+            if ep.workgroup_size.contains(&0) {
+                return Err(EntryPointError::ZeroWorkgroupSize.with_span());
+            }
+            if ep.workgroup_size.iter().any(|&s| s > MAX_WORKGROUP_SIZE) {
                 return Err(EntryPointError::OutOfRangeWorkgroupSize.with_span());
             }
         } else if ep.workgroup_size != [0; 3] {
@@ -485,6 +584,16 @@ impl super::Validator {
             if !info.available_stages.contains(stage_bit) {
                 return Err(EntryPointError::ForbiddenStageOperations.with_span());
             }
+
+            // Only compute shaders can use atomics in workgroup memory.
+            if info.uses_workgroup_atomics && ep.stage != crate::ShaderStage::Compute {
+                return Err(
+                    EntryPointError::Function(FunctionError::AtomicOutsideCompute {
+                        stage: ep.stage,
+                    })
+                    .with_span(),
+                );
+            }
         }
 
         self.location_mask.clear();
@@ -499,14 +608,15 @@ impl super::Validator {
                 location_mask: &mut self.location_mask,
                 built_ins: &mut argument_built_ins,
                 capabilities: self.capabilities,
+                max_inter_stage_locations: self.max_inter_stage_locations,
             };
             ctx.validate(fa.ty, fa.binding.as_ref())
                 .map_err_inner(|e| EntryPointError::Argument(index as u32, e).with_span())?;
         }
 
         self.location_mask.clear();
+        let mut result_built_ins = crate::FastHashSet::default();
         if let Some(ref fr) = ep.function.result {
-            let mut result_built_ins = crate::FastHashSet::default();
             let mut ctx = VaryingContext {
                 stage: ep.stage,
                 output: true,
@@ -515,9 +625,27 @@ impl super::Validator {
                 location_mask: &mut self.location_mask,
                 built_ins: &mut result_built_ins,
                 capabilities: self.capabilities,
+                max_inter_stage_locations: self.max_inter_stage_locations,
             };
             ctx.validate(fr.ty, fr.binding.as_ref())
                 .map_err_inner(|e| EntryPointError::Result(e).with_span())?;
+
+            // Without conservative depth, the tests happen before the
+            // shader gets to write the depth.
+            #[cfg(feature = "validate")]
+            if ep.early_depth_test == Some(crate::EarlyDepthTest { conservative: None })
+                && result_built_ins.contains(&crate::BuiltIn::FragDepth)
+            {
+                return Err(EntryPointError::EarlyDepthTestWithDepthWrite.with_span());
+            }
+        }
+
+        // The varying checks make sure the position is a `vec4<f32>`.
+        if self.require_vertex_position
+            && ep.stage == crate::ShaderStage::Vertex
+            && !result_built_ins.contains(&crate::BuiltIn::Position { invariant: false })
+        {
+            return Err(EntryPointError::MissingVertexPosition.with_span());
         }
 
         for bg in self.bind_group_masks.iter_mut() {
@@ -14,7 +14,7 @@ use crate::arena::{Arena, UniqueArena};
 
 use crate::{
     arena::{BadHandle, Handle},
-    proc::{LayoutError, Layouter},
+    proc::{LayoutError, LayoutErrorInner, Layouter},
     FastHashSet,
 };
 use bit_set::BitSet;
@@ -27,7 +27,7 @@ use crate::span::{AddSpan as _, WithSpan};
 pub use analyzer::{ExpressionInfo, FunctionInfo, GlobalUse, Uniformity, UniformityRequirements};
 pub use compose::ComposeError;
 pub use expression::ExpressionError;
-pub use function::{CallError, FunctionError, LocalVariableError};
+pub use function::{AtomicError, CallError, FunctionError, LocalVariableError};
 pub use interface::{EntryPointError, GlobalVariableError, VaryingError};
 pub use r#type::{Disalignment, TypeError, TypeFlags};
 
@@ -94,6 +94,11 @@ bitflags::bitflags! {
         const UNIFORM_BUFFER_AND_STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING = 0x10;
         /// Support for non-uniform indexing of samplers.
         const SAMPLER_NON_UNIFORM_INDEXING = 0x20;
+        /// Float values with width = 2.
+        const FLOAT16 = 0x40;
+        /// Support for storage textures with formats that aren't guaranteed to
+        /// be storage-compatible, like `R8Unorm` or `Rg11b10Float`.
+        const STORAGE_TEXTURE_EXTENDED_FORMATS = 0x80;
     }
 }
 
@@ -135,6 +140,16 @@ pub struct Validator {
     select_cases: FastHashSet<i32>,
     valid_expression_list: Vec<Handle<crate::Expression>>,
     valid_expression_set: BitSet,
+    report_shadowing: bool,
+    /// The names of the global variables and constants, when shadowing is
+    /// reported.
+    global_names: FastHashSet<String>,
+    /// The warnings of the function being validated.
+    function_warnings: Vec<WithSpan<FunctionError>>,
+    warnings: Vec<WithSpan<ValidationError>>,
+    report_dead_code: bool,
+    require_vertex_position: bool,
+    max_inter_stage_locations: Option<u32>,
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
@@ -195,27 +210,6 @@ pub enum ValidationError {
 }
 
 impl crate::TypeInner {
-    #[cfg(feature = "validate")]
-    const fn is_sized(&self) -> bool {
-        match *self {
-            Self::Scalar { .. }
-            | Self::Vector { .. }
-            | Self::Matrix { .. }
-            | Self::Array {
-                size: crate::ArraySize::Constant(_),
-                ..
-            }
-            | Self::Atomic { .. }
-            | Self::Pointer { .. }
-            | Self::ValuePointer { .. }
-            | Self::Struct { .. } => true,
-            Self::Array { .. }
-            | Self::Image { .. }
-            | Self::Sampler { .. }
-            | Self::BindingArray { .. } => false,
-        }
-    }
-
     /// Return the `ImageDimension` for which `self` is an appropriate coordinate.
     #[cfg(feature = "validate")]
     const fn image_storage_coordinates(&self) -> Option<crate::ImageDimension> {
@@ -252,9 +246,62 @@ impl Validator {
             select_cases: FastHashSet::default(),
             valid_expression_list: Vec::new(),
             valid_expression_set: BitSet::new(),
+            report_shadowing: false,
+            global_names: FastHashSet::default(),
+            function_warnings: Vec::new(),
+            warnings: Vec::new(),
+            report_dead_code: false,
+            require_vertex_position: false,
+            max_inter_stage_locations: None,
         }
     }
 
+    /// Report function arguments and local variables that have the same name
+    /// as a global variable or constant.
+    ///
+    /// Shadowing is valid, and backends rename the shadowed variables, so this
+    /// is off by default. It's meant for tools that want to warn about it, so
+    /// it's reported in [`warnings`](Self::warnings) rather than as an error.
+    pub fn report_shadowing(&mut self, report: bool) -> &mut Self {
+        self.report_shadowing = report;
+        self
+    }
+
+    /// Return the problems found by the last call to
+    /// [`validate`](Self::validate) that don't make the module invalid.
+    pub fn warnings(&self) -> &[WithSpan<ValidationError>] {
+        &self.warnings
+    }
+
+    /// Report code that can never run, like the `continuing` block of a loop
+    /// whose body always breaks or returns.
+    ///
+    /// Such code is valid, so this is off by default, like
+    /// [`report_shadowing`](Self::report_shadowing).
+    pub fn report_dead_code(&mut self, report: bool) -> &mut Self {
+        self.report_dead_code = report;
+        self
+    }
+
+    /// Require vertex entry points to return a `@builtin(position)` value.
+    ///
+    /// WebGPU requires it, but other targets let vertex shaders that only
+    /// write other outputs, or have no outputs at all, so this is off by
+    /// default.
+    pub fn require_vertex_position(&mut self, require: bool) -> &mut Self {
+        self.require_vertex_position = require;
+        self
+    }
+
+    /// Limit the locations of all entry point inputs and outputs to `0..max`.
+    ///
+    /// Entry points using a location outside of the limit fail validation
+    /// with [`VaryingError::LocationTooLarge`]. There is no limit by default.
+    pub fn max_inter_stage_locations(&mut self, max: Option<u32>) -> &mut Self {
+        self.max_inter_stage_locations = max;
+        self
+    }
+
     /// Reset the validator internals
     pub fn reset(&mut self) {
         self.types.clear();
@@ -264,6 +311,9 @@ impl Validator {
         self.select_cases.clear();
         self.valid_expression_list.clear();
         self.valid_expression_set.clear();
+        self.global_names.clear();
+        self.function_warnings.clear();
+        self.warnings.clear();
     }
 
     #[cfg(feature = "validate")]
@@ -318,7 +368,15 @@ impl Validator {
             .update(&module.types, &module.constants)
             .map_err(|e| {
                 let handle = e.ty;
-                ValidationError::from(e).with_span_handle(handle, &module.types)
+                let error = match e.inner {
+                    LayoutErrorInner::RecursiveType => ValidationError::Type {
+                        handle,
+                        name: module.types[handle].name.clone().unwrap_or_default(),
+                        error: TypeError::RecursiveType { handle },
+                    },
+                    _ => ValidationError::from(e),
+                };
+                error.with_span_handle(handle, &module.types)
             })?;
 
         #[cfg(feature = "validate")]
@@ -363,13 +421,32 @@ impl Validator {
                 })?;
         }
 
+        #[cfg(feature = "validate")]
+        if self.report_shadowing {
+            let variables = module.global_variables.iter().map(|(_, var)| &var.name);
+            let constants = module.constants.iter().map(|(_, constant)| &constant.name);
+            self.global_names
+                .extend(variables.chain(constants).flatten().cloned());
+        }
+
         let mut mod_info = ModuleInfo {
             functions: Vec::with_capacity(module.functions.len()),
             entry_points: Vec::with_capacity(module.entry_points.len()),
         };
 
         for (handle, fun) in module.functions.iter() {
-            match self.validate_function(fun, module, &mod_info) {
+            let result = self.validate_function(fun, module, &mod_info);
+            for warning in self.function_warnings.drain(..) {
+                self.warnings.push(warning.and_then(|error| {
+                    ValidationError::Function {
+                        handle,
+                        name: fun.name.clone().unwrap_or_default(),
+                        error,
+                    }
+                    .with_span_handle(handle, &module.functions)
+                }));
+            }
+            match result {
                 Ok(info) => mod_info.functions.push(info),
                 Err(error) => {
                     return Err(error.and_then(|error| {
@@ -395,7 +472,18 @@ impl Validator {
                 .with_span()); // TODO: keep some EP span information?
             }
 
-            match self.validate_entry_point(ep, module, &mod_info) {
+            let result = self.validate_entry_point(ep, module, &mod_info);
+            for warning in self.function_warnings.drain(..) {
+                self.warnings.push(warning.and_then(|error| {
+                    ValidationError::EntryPoint {
+                        stage: ep.stage,
+                        name: ep.name.clone(),
+                        error: EntryPointError::Function(error),
+                    }
+                    .with_span()
+                }));
+            }
+            match result {
                 Ok(info) => mod_info.entry_points.push(info),
                 Err(error) => {
                     return Err(error.and_then(|inner| {
@@ -413,3 +501,388 @@ impl Validator {
         Ok(mod_info)
     }
 }
+
+#[test]
+fn recursive_types() {
+    use std::num::NonZeroU32;
+
+    fn struct_type(name: &str, member_index: u32) -> crate::Type {
+        crate::Type {
+            name: Some(name.to_string()),
+            inner: crate::TypeInner::Struct {
+                members: vec![crate::StructMember {
+                    name: Some("member".to_string()),
+                    ty: Handle::new(NonZeroU32::new(member_index + 1).unwrap()),
+                    binding: None,
+                    offset: 0,
+                }],
+                span: 4,
+            },
+        }
+    }
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::empty());
+
+    // `struct Foo { member: Foo }`
+    let mut module = crate::Module::default();
+    module
+        .types
+        .insert(struct_type("Foo", 0), Default::default());
+    let error = validator.validate(&module).unwrap_err();
+    assert!(matches!(
+        *error.as_inner(),
+        ValidationError::Type {
+            error: TypeError::RecursiveType { .. },
+            ..
+        }
+    ));
+
+    // `struct Foo { member: Bar }` and `struct Bar { member: Foo }`
+    let mut module = crate::Module::default();
+    module
+        .types
+        .insert(struct_type("Foo", 1), Default::default());
+    module
+        .types
+        .insert(struct_type("Bar", 0), Default::default());
+    let error = validator.validate(&module).unwrap_err();
+    assert!(matches!(
+        *error.as_inner(),
+        ValidationError::Type {
+            error: TypeError::RecursiveType { .. },
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn missing_return() {
+    let mut module = crate::Module::default();
+    let ty = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Sint,
+                width: 4,
+            },
+        },
+        Default::default(),
+    );
+    // `loop { break; }` falls through to the end of the body.
+    let mut body = crate::Block::new();
+    let mut loop_body = crate::Block::new();
+    loop_body.push(crate::Statement::Break, Default::default());
+    body.push(
+        crate::Statement::Loop {
+            body: loop_body,
+            continuing: crate::Block::new(),
+        },
+        Default::default(),
+    );
+    module.functions.append(
+        crate::Function {
+            name: Some("f".to_string()),
+            result: Some(crate::FunctionResult { ty, binding: None }),
+            body,
+            ..Default::default()
+        },
+        Default::default(),
+    );
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::empty());
+    let error = validator.validate(&module).unwrap_err();
+    assert!(matches!(
+        *error.as_inner(),
+        ValidationError::Function {
+            error: FunctionError::MissingReturn,
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn access_index_out_of_bounds() {
+    let mut module = crate::Module::default();
+    let ty = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Vector {
+                size: crate::VectorSize::Tri,
+                kind: crate::ScalarKind::Float,
+                width: 4,
+            },
+        },
+        Default::default(),
+    );
+    // `fn f(v: vec3<f32>) { v[4]; }`
+    let mut function = crate::Function {
+        name: Some("f".to_string()),
+        arguments: vec![crate::FunctionArgument {
+            name: Some("v".to_string()),
+            ty,
+            binding: None,
+        }],
+        ..Default::default()
+    };
+    let base = function
+        .expressions
+        .append(crate::Expression::FunctionArgument(0), Default::default());
+    let start = function.expressions.len();
+    function.expressions.append(
+        crate::Expression::AccessIndex { base, index: 4 },
+        Default::default(),
+    );
+    let range = function.expressions.range_from(start);
+    function
+        .body
+        .push(crate::Statement::Emit(range), Default::default());
+    module.functions.append(function, Default::default());
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::empty());
+    let error = validator.validate(&module).unwrap_err();
+    assert!(matches!(
+        *error.as_inner(),
+        ValidationError::Function {
+            error: FunctionError::Expression {
+                error: ExpressionError::IndexOutOfBounds(_, crate::ScalarValue::Uint(4)),
+                ..
+            },
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn non_handle_in_handle_space() {
+    let mut module = crate::Module::default();
+    let ty = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Uint,
+                width: 4,
+            },
+        },
+        Default::default(),
+    );
+    module.global_variables.append(
+        crate::GlobalVariable {
+            name: Some("value".to_string()),
+            space: crate::AddressSpace::Handle,
+            binding: Some(crate::ResourceBinding {
+                group: 0,
+                binding: 0,
+            }),
+            ty,
+            init: None,
+        },
+        Default::default(),
+    );
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::empty());
+    let error = validator.validate(&module).unwrap_err();
+    assert!(matches!(
+        *error.as_inner(),
+        ValidationError::GlobalVariable {
+            error: GlobalVariableError::NonHandleInHandleSpace(handle),
+            ..
+        } if handle == ty
+    ));
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn local_variable_init_type_mismatch() {
+    let mut module = crate::Module::default();
+    let ty = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Float,
+                width: 4,
+            },
+        },
+        Default::default(),
+    );
+    let mut constant = |value| {
+        module.constants.append(
+            crate::Constant {
+                name: None,
+                specialization: None,
+                inner: crate::ConstantInner::Scalar { width: 4, value },
+            },
+            Default::default(),
+        )
+    };
+    let good = constant(crate::ScalarValue::Float(1.0));
+    let bad = constant(crate::ScalarValue::Sint(1));
+
+    let validate = |module: &mut crate::Module, init| {
+        // `fn f() { var x: f32 = init; }`
+        let mut function = crate::Function {
+            name: Some("f".to_string()),
+            ..Default::default()
+        };
+        function.local_variables.append(
+            crate::LocalVariable {
+                name: Some("x".to_string()),
+                ty,
+                init: Some(init),
+            },
+            Default::default(),
+        );
+        module.functions.clear();
+        module.functions.append(function, Default::default());
+        Validator::new(ValidationFlags::all(), Capabilities::empty()).validate(module)
+    };
+
+    assert!(validate(&mut module, good).is_ok());
+    let error = validate(&mut module, bad).unwrap_err();
+    assert!(matches!(
+        *error.as_inner(),
+        ValidationError::Function {
+            error: FunctionError::LocalVariable {
+                error: LocalVariableError::InitTypeMismatch,
+                ..
+            },
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn dangling_global_variable() {
+    let ty = crate::Type {
+        name: None,
+        inner: crate::TypeInner::Scalar {
+            kind: crate::ScalarKind::Float,
+            width: 4,
+        },
+    };
+    let mut module = crate::Module::default();
+    let mut globals = Arena::new();
+    let dangling = globals.append(
+        crate::GlobalVariable {
+            name: Some("missing".to_string()),
+            space: crate::AddressSpace::Private,
+            binding: None,
+            ty: module.types.insert(ty, Default::default()),
+            init: None,
+        },
+        Default::default(),
+    );
+
+    // A function referring to a global of another module.
+    let mut function = crate::Function::default();
+    let expr = function.expressions.append(
+        crate::Expression::GlobalVariable(dangling),
+        Default::default(),
+    );
+    module.functions.append(function, Default::default());
+
+    let error = Validator::new(ValidationFlags::all(), Capabilities::empty())
+        .validate(&module)
+        .unwrap_err();
+    assert!(matches!(
+        *error.as_inner(),
+        ValidationError::Function {
+            error: FunctionError::Expression {
+                handle,
+                error: ExpressionError::InvalidGlobal(global),
+            },
+            ..
+        } if handle == expr && global == dangling
+    ));
+}
+
+#[cfg(feature = "validate")]
+#[test]
+fn function_argument_out_of_range() {
+    let mut module = crate::Module::default();
+    let ty = module.types.insert(
+        crate::Type {
+            name: None,
+            inner: crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Float,
+                width: 4,
+            },
+        },
+        Default::default(),
+    );
+    // `fn f(x: f32) { }`, using a second argument.
+    let mut function = crate::Function {
+        name: Some("f".to_string()),
+        arguments: vec![crate::FunctionArgument {
+            name: Some("x".to_string()),
+            ty,
+            binding: None,
+        }],
+        ..Default::default()
+    };
+    function
+        .expressions
+        .append(crate::Expression::FunctionArgument(0), Default::default());
+    let expr = function
+        .expressions
+        .append(crate::Expression::FunctionArgument(1), Default::default());
+    module.functions.append(function, Default::default());
+
+    let error = Validator::new(ValidationFlags::all(), Capabilities::empty())
+        .validate(&module)
+        .unwrap_err();
+    assert!(matches!(
+        *error.as_inner(),
+        ValidationError::Function {
+            error: FunctionError::Expression {
+                handle,
+                error: ExpressionError::InvalidFunctionArgument { index: 1 },
+            },
+            ..
+        } if handle == expr
+    ));
+}
+
+#[cfg(all(feature = "validate", feature = "wgsl-in"))]
+#[test]
+fn access_preserves_address_space() {
+    let module = crate::front::wgsl::parse_str(
+        "
+        struct Data { values: array<f32> }
+        @group(0) @binding(0) var<storage, read_write> data: Data;
+
+        fn f(i: u32) {
+            data.values[i] = 1.0;
+        }
+        ",
+    )
+    .unwrap();
+    let info = Validator::new(ValidationFlags::all(), Capabilities::empty())
+        .validate(&module)
+        .unwrap();
+
+    let (handle, function) = module.functions.iter().next().unwrap();
+    let function_info = &info[handle];
+    let access = function
+        .expressions
+        .iter()
+        .find(|&(_, expr)| matches!(*expr, crate::Expression::Access { .. }))
+        .unwrap()
+        .0;
+    match *function_info[access].ty.inner_with(&module.types) {
+        crate::TypeInner::Pointer {
+            base,
+            space: crate::AddressSpace::Storage { .. },
+        } => assert_eq!(
+            module.types[base].inner,
+            crate::TypeInner::Scalar {
+                kind: crate::ScalarKind::Float,
+                width: 4,
+            }
+        ),
+        ref other => panic!("Unexpected type {:?}", other),
+    }
+}
@@ -102,8 +102,6 @@ pub enum TypeError {
     InvalidArrayBaseType(Handle<crate::Type>),
     #[error("The constant {0:?} can not be used for an array size")]
     InvalidArraySizeConstant(Handle<crate::Constant>),
-    #[error("The constant {0:?} is specialized, and cannot be used as an array size")]
-    UnsupportedSpecializedArrayLength(Handle<crate::Constant>),
     #[error("Array type {0:?} must have a length of one or more")]
     NonPositiveArrayLength(Handle<crate::Constant>),
     #[error("Array stride {stride} does not match the expected {expected}")]
@@ -123,6 +121,10 @@ pub enum TypeError {
     },
     #[error("Structure types must have at least one member")]
     EmptyStruct,
+    #[error("Type {handle:?} contains itself by value")]
+    RecursiveType { handle: Handle<crate::Type> },
+    #[error("The image class {1:?} is invalid for a {0:?} image")]
+    InvalidImageFormat(crate::ImageDimension, crate::ImageClass),
 }
 
 // Only makes sense if `flags.contains(HOST_SHARED)`
@@ -203,7 +205,9 @@ impl super::Validator {
         match kind {
             crate::ScalarKind::Bool => width == crate::BOOL_WIDTH,
             crate::ScalarKind::Float => {
-                width == 4 || (width == 8 && self.capabilities.contains(Capabilities::FLOAT64))
+                width == 4
+                    || (width == 8 && self.capabilities.contains(Capabilities::FLOAT64))
+                    || (width == 2 && self.capabilities.contains(Capabilities::FLOAT16))
             }
             crate::ScalarKind::Sint | crate::ScalarKind::Uint => width == 4,
         }
@@ -255,7 +259,6 @@ impl super::Validator {
                     TypeFlags::DATA
                         | TypeFlags::SIZED
                         | TypeFlags::COPY
-                        | TypeFlags::HOST_SHAREABLE
                         | TypeFlags::ARGUMENT
                         | shareable,
                     count * (width as u32),
@@ -420,23 +423,11 @@ impl super::Validator {
                     Err(e) => Err(e),
                 };
 
-                let sized_flag = match size {
+                let base_mask = TypeFlags::COPY | TypeFlags::HOST_SHAREABLE;
+                let (sized_flag, base_mask) = match size {
                     crate::ArraySize::Constant(const_handle) => {
                         let constant = constants.try_get(const_handle)?;
                         let length_is_positive = match *constant {
-                            crate::Constant {
-                                specialization: Some(_),
-                                ..
-                            } => {
-                                // Many of our back ends don't seem to support
-                                // specializable array lengths. If you want to try to make
-                                // this work, be sure to address all uses of
-                                // `Constant::to_array_length`, which ignores
-                                // specialization.
-                                return Err(TypeError::UnsupportedSpecializedArrayLength(
-                                    const_handle,
-                                ));
-                            }
                             crate::Constant {
                                 inner:
                                     crate::ConstantInner::Scalar {
@@ -467,17 +458,24 @@ impl super::Validator {
                             return Err(TypeError::NonPositiveArrayLength(const_handle));
                         }
 
-                        TypeFlags::SIZED | TypeFlags::ARGUMENT
+                        if constant.specialization.is_some() {
+                            // The pipeline may override the length, so the
+                            // array can't be laid out in host-shareable memory,
+                            // or passed by value. Only the default length is
+                            // checked here.
+                            (TypeFlags::SIZED, TypeFlags::COPY)
+                        } else {
+                            (TypeFlags::SIZED | TypeFlags::ARGUMENT, base_mask)
+                        }
                     }
                     crate::ArraySize::Dynamic => {
                         // Non-SIZED types may only appear as the last element of a structure.
                         // This is enforced by checks for SIZED-ness for all compound types,
                         // and a special case for structs.
-                        TypeFlags::empty()
+                        (TypeFlags::empty(), base_mask)
                     }
                 };
 
-                let base_mask = TypeFlags::COPY | TypeFlags::HOST_SHAREABLE;
                 TypeInfo {
                     flags: TypeFlags::DATA | (base_info.flags & base_mask) | sized_flag,
                     uniform_layout,
@@ -611,8 +609,53 @@ impl super::Validator {
 
                 ti
             }
-            Ti::Image { .. } | Ti::Sampler { .. } => TypeInfo::new(TypeFlags::ARGUMENT, 0),
+            Ti::Image { dim, class, .. } => {
+                let multi_is_valid = |multi: bool| !multi || dim == crate::ImageDimension::D2;
+                let valid = match class {
+                    crate::ImageClass::Sampled { kind, multi } => {
+                        kind != crate::ScalarKind::Bool && multi_is_valid(multi)
+                    }
+                    crate::ImageClass::Depth { multi } => {
+                        dim != crate::ImageDimension::D3 && multi_is_valid(multi)
+                    }
+                    crate::ImageClass::Storage { format, .. } => {
+                        is_storage_format(format)
+                            || self
+                                .capabilities
+                                .contains(Capabilities::STORAGE_TEXTURE_EXTENDED_FORMATS)
+                    }
+                };
+                if !valid {
+                    return Err(TypeError::InvalidImageFormat(dim, class));
+                }
+                TypeInfo::new(TypeFlags::ARGUMENT, 0)
+            }
+            Ti::Sampler { .. } => TypeInfo::new(TypeFlags::ARGUMENT, 0),
             Ti::BindingArray { .. } => TypeInfo::new(TypeFlags::empty(), 0),
         })
     }
 }
+
+/// Return true if storage textures can always use `format`.
+const fn is_storage_format(format: crate::StorageFormat) -> bool {
+    use crate::StorageFormat as Sf;
+    matches!(
+        format,
+        Sf::Rgba8Unorm
+            | Sf::Rgba8Snorm
+            | Sf::Rgba8Uint
+            | Sf::Rgba8Sint
+            | Sf::Rgba16Uint
+            | Sf::Rgba16Sint
+            | Sf::Rgba16Float
+            | Sf::R32Uint
+            | Sf::R32Sint
+            | Sf::R32Float
+            | Sf::Rg32Uint
+            | Sf::Rg32Sint
+            | Sf::Rg32Float
+            | Sf::Rgba32Uint
+            | Sf::Rgba32Sint
+            | Sf::Rgba32Float
+    )
+}
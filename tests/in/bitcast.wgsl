@@ -0,0 +1,23 @@
+@compute @workgroup_size(1)
+fn main() {
+    var f: f32 = 1.5;
+    var f4: vec4<f32> = vec4<f32>(1.5);
+
+    // bitcasts reinterpret the bits
+    let u = bitcast<u32>(f);
+    let u4 = bitcast<vec4<u32>>(f4);
+    let i = bitcast<i32>(u);
+    let i4 = bitcast<vec4<i32>>(u4);
+    let back4 = bitcast<vec4<f32>>(i4);
+
+    // conversions change the value
+    let c = i32(f);
+    let cu = u32(f);
+    let c4 = vec4<i32>(f4);
+    let back = f32(u);
+
+    // identity constructors keep the value
+    var b2: vec2<bool> = vec2<bool>(true, false);
+    let same_b2 = vec2(b2);
+    let same_f4 = vec4(f4);
+}
@@ -0,0 +1,19 @@
+// Module-scope constants, immutable `let` bindings and mutable `var` locals
+// must keep their declaration kinds through a round trip.
+
+const count: i32 = 4;
+const scale: vec2<f32> = vec2<f32>(0.5, 2.0);
+
+fn main() -> f32 {
+    let doubled = count * 2;
+    var total = 0.0;
+    var i = 0;
+    loop {
+        if (i >= doubled) {
+            break;
+        }
+        total = total + scale.x;
+        i = i + 1;
+    }
+    return total * scale.y;
+}
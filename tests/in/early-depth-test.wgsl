@@ -0,0 +1,11 @@
+@fragment
+@early_depth_test
+fn early_test(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(position.xy, 0.0, 1.0);
+}
+
+@fragment
+@early_depth_test(greater_equal)
+fn conservative_depth(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+    return position.z + 0.5;
+}
@@ -128,6 +128,11 @@ void ternary(bool a) {
     uint nested = a ? (a ? (a ? 2u : 3) : 4u) : 5;
 }
 
+void testUnaryOpUint(uint a, uvec2 b) {
+    uint v = -a;
+    uvec2 w = -b;
+}
+
 out vec4 o_color;
 void main() {
     privatePointer(global);
@@ -0,0 +1,13 @@
+#version 450 core
+
+layout(location = 0) in vec2 a_pos;
+
+out gl_PerVertex {
+    vec4 gl_Position;
+    float gl_PointSize;
+};
+
+void main() {
+    gl_Position = vec4(a_pos, 0.0, 1.0);
+    gl_PointSize = 4.0;
+}
@@ -0,0 +1,7 @@
+@compute @workgroup_size(1)
+fn main() {
+    var d2: vec2<f64> = vec2<f64>(f64(1.5));
+
+    // identity constructors keep the value
+    let same_d2 = vec2(d2);
+}
@@ -9,6 +9,7 @@ struct FragmentInput {
   @location(4) @interpolate(perspective) perspective : vec4<f32>,
   @location(5) @interpolate(perspective, centroid) perspective_centroid : f32,
   @location(6) @interpolate(perspective, sample) perspective_sample : f32,
+  @location(7) _int : i32,
 }
 
 @vertex
@@ -23,6 +24,7 @@ fn vert_main() -> FragmentInput {
    out.perspective = vec4<f32>(729.0, 1000.0, 1331.0, 1728.0);
    out.perspective_centroid = 2197.0;
    out.perspective_sample = 2744.0;
+   out._int = -9;
 
    return out;
 }
@@ -0,0 +1,23 @@
+// Nested, struct and runtime-sized arrays must keep their shapes through a
+// round trip.
+
+struct Item {
+    a: f32,
+    b: vec2<f32>,
+}
+
+struct Data {
+    grid: array<array<vec2<f32>, 3>, 5>,
+    items: array<Item, 2>,
+    rest: array<array<f32, 4>>,
+}
+
+@group(0) @binding(0)
+var<storage, read_write> data: Data;
+
+@compute @workgroup_size(1)
+fn main() {
+    data.grid[4][2] = data.rest[1][3] * data.items[1].b;
+    var local: array<array<i32, 2>, 3>;
+    local[2][1] = 1;
+}
@@ -291,6 +291,15 @@ fn assignment() {
     var vec0: vec3<i32> = vec3<i32>();
     vec0[1]++;
     vec0[1]--;
+
+    var vec1 = vec3<f32>(1.0);
+    vec1 += vec3<f32>(2.0);
+    vec1 *= 2.0;
+    vec1.y -= 1.0;
+
+    var mat0 = mat2x2<f32>(vec2<f32>(1.0), vec2<f32>(1.0));
+    mat0 += mat0;
+    mat0 *= 0.5;
 }
 
 @compute @workgroup_size(1)
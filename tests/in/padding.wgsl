@@ -17,6 +17,17 @@ struct Test3 {
     b: f32, // offset: 64
 }
 
+struct Test4 {
+    @size(20) a: vec3<f32>,
+    @align(16) b: f32, // offset: 32
+    @size(16) c: f32, // offset: 36
+}
+
+struct Test5 {
+    a: f32,
+    @align(16) b: vec2<f32>, // offset: 16
+}
+
 @group(0) @binding(0)
 var<uniform> input1: Test;
 
@@ -26,8 +37,14 @@ var<uniform> input2: Test2;
 @group(0) @binding(2)
 var<uniform> input3: Test3;
 
+@group(0) @binding(3)
+var<uniform> input4: Test4;
+
+@group(0) @binding(4)
+var<uniform> input5: Test5;
+
 
 @vertex
 fn vertex() -> @builtin(position) vec4<f32> {
-    return vec4<f32>(1.0) * input1.b * input2.b * input3.b;
+    return vec4<f32>(1.0) * input1.b * input2.b * input3.b * input4.c * input5.b.x;
 }
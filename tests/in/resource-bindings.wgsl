@@ -0,0 +1,16 @@
+// Explicit `binding=` qualifiers need GLSL 4.20.
+struct Globals {
+    tint: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> globals: Globals;
+@group(0) @binding(1)
+var t_diffuse: texture_2d<f32>;
+@group(0) @binding(2)
+var s_diffuse: sampler;
+
+@fragment
+fn main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    return textureSample(t_diffuse, s_diffuse, uv) * globals.tint;
+}
@@ -0,0 +1,15 @@
+// Per-sample fragment builtins, which are arrays of signed integers in GLSL.
+
+struct FragmentOutput {
+    @builtin(sample_mask) sample_mask: u32,
+    @location(0) color: f32,
+}
+
+@fragment
+fn main(
+    @builtin(sample_index) sample_index: u32,
+    @builtin(sample_mask) sample_mask: u32,
+) -> FragmentOutput {
+    let mask = sample_mask & (1u << sample_index);
+    return FragmentOutput(mask, 1.0);
+}
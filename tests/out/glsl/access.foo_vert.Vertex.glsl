@@ -82,7 +82,7 @@ void main() {
     uvec2 arr[2] = _group_0_binding_0_vs.arr;
     float b = _group_0_binding_0_vs._matrix[3][0];
     int a_1 = _group_0_binding_0_vs.data[(uint(_group_0_binding_0_vs.data.length()) - 2u)].value;
-    ivec2 c_1 = _group_0_binding_2_vs;
+    ivec2 c2_ = _group_0_binding_2_vs;
     float _e30 = read_from_private(foo);
     c = int[5](a_1, int(b), 3, 4, 5);
     c[(vi + 1u)] = 42;
@@ -0,0 +1,34 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+
+void main() {
+    float f = 1.5;
+    vec4 f4_ = vec4(0.0);
+    bvec2 b2_ = bvec2(false);
+    f4_ = vec4(1.5);
+    float _e5 = f;
+    uint u = floatBitsToUint(_e5);
+    vec4 _e7 = f4_;
+    uvec4 u4_ = floatBitsToUint(_e7);
+    int i = int(u);
+    ivec4 i4_ = ivec4(u4_);
+    vec4 back4_ = intBitsToFloat(i4_);
+    float _e12 = f;
+    int c = int(_e12);
+    float _e14 = f;
+    uint cu = uint(_e14);
+    vec4 _e16 = f4_;
+    ivec4 c4_ = ivec4(_e16);
+    float back = float(u);
+    b2_ = bvec2(true, false);
+    bvec2 _e23 = b2_;
+    bvec2 same_b2_ = _e23;
+    vec4 _e25 = f4_;
+    vec4 same_f4_ = _e25;
+}
+
@@ -0,0 +1,34 @@
+#version 450 core
+struct type_8 {
+    vec4 member;
+    float member_1[2];
+};
+vec4 a_pos_1 = vec4(0.0);
+
+vec4 global = vec4(0.0, 0.0, 0.0, 1.0);
+
+float clip_distance[2] = float[2](0.0, 0.0);
+
+layout(location = 0) in vec4 _p2vs_location0;
+out float gl_ClipDistance[2];
+
+void function() {
+    vec4 _e10 = a_pos_1;
+    global = _e10;
+    clip_distance[0] = _e10.x;
+    clip_distance[1] = _e10.y;
+    return;
+}
+
+void main() {
+    vec4 a_pos = _p2vs_location0;
+    a_pos_1 = a_pos;
+    function();
+    vec4 _e4 = global;
+    float _e5[2] = clip_distance;
+    type_8 _tmp_return = type_8(_e4, _e5);
+    gl_Position = _tmp_return.member;
+    gl_ClipDistance = _tmp_return.member_1;
+    return;
+}
+
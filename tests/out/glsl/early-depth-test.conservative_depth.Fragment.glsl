@@ -0,0 +1,16 @@
+#version 310 es
+#extension GL_EXT_conservative_depth : require
+
+precision highp float;
+precision highp int;
+
+layout(early_fragment_tests) in;
+layout (depth_greater) out float gl_FragDepth;
+
+
+void main() {
+    vec4 position_1 = gl_FragCoord;
+    gl_FragDepth = (position_1.z + 0.5);
+    return;
+}
+
@@ -0,0 +1,15 @@
+#version 310 es
+
+precision highp float;
+precision highp int;
+
+layout(early_fragment_tests) in;
+
+layout(location = 0) out vec4 _fs2p_location0;
+
+void main() {
+    vec4 position = gl_FragCoord;
+    _fs2p_location0 = vec4(position.xy, 0.0, 1.0);
+    return;
+}
+
@@ -0,0 +1,29 @@
+#version 450 core
+#extension GL_EXT_shader_explicit_arithmetic_types_float16 : require
+vec3 color_1 = vec3(0.0);
+
+vec4 frag_color = vec4(0.0);
+
+f16vec3 half_color = f16vec3(0.0);
+
+layout(location = 0) smooth in vec3 _vs2fs_location0;
+layout(location = 0) out vec4 _fs2p_location0;
+
+void function() {
+    vec3 _e8 = color_1;
+    half_color = f16vec3(_e8);
+    f16vec3 _e10 = half_color;
+    vec3 _e11 = vec3(_e10);
+    frag_color = vec4(_e11.x, _e11.y, _e11.z, 1.0);
+    return;
+}
+
+void main() {
+    vec3 color = _vs2fs_location0;
+    color_1 = color;
+    function();
+    vec4 _e3 = frag_color;
+    _fs2p_location0 = _e3;
+    return;
+}
+
@@ -8,6 +8,7 @@ struct FragmentInput {
     vec4 perspective;
     float perspective_centroid;
     float perspective_sample;
+    int _int;
 };
 flat in uint _vs2fs_location0;
 noperspective in float _vs2fs_location1;
@@ -16,9 +17,10 @@ noperspective sample in vec3 _vs2fs_location3;
 smooth in vec4 _vs2fs_location4;
 smooth centroid in float _vs2fs_location5;
 smooth sample in float _vs2fs_location6;
+flat in int _vs2fs_location7;
 
 void main() {
-    FragmentInput val = FragmentInput(gl_FragCoord, _vs2fs_location0, _vs2fs_location1, _vs2fs_location2, _vs2fs_location3, _vs2fs_location4, _vs2fs_location5, _vs2fs_location6);
+    FragmentInput val = FragmentInput(gl_FragCoord, _vs2fs_location0, _vs2fs_location1, _vs2fs_location2, _vs2fs_location3, _vs2fs_location4, _vs2fs_location5, _vs2fs_location6, _vs2fs_location7);
     return;
 }
 
@@ -8,6 +8,7 @@ struct FragmentInput {
     vec4 perspective;
     float perspective_centroid;
     float perspective_sample;
+    int _int;
 };
 flat out uint _vs2fs_location0;
 noperspective out float _vs2fs_location1;
@@ -16,9 +17,10 @@ noperspective sample out vec3 _vs2fs_location3;
 smooth out vec4 _vs2fs_location4;
 smooth centroid out float _vs2fs_location5;
 smooth sample out float _vs2fs_location6;
+flat out int _vs2fs_location7;
 
 void main() {
-    FragmentInput out_ = FragmentInput(vec4(0.0), 0u, 0.0, vec2(0.0), vec3(0.0), vec4(0.0), 0.0, 0.0);
+    FragmentInput out_ = FragmentInput(vec4(0.0), 0u, 0.0, vec2(0.0), vec3(0.0), vec4(0.0), 0.0, 0.0, 0);
     out_.position = vec4(2.0, 4.0, 5.0, 6.0);
     out_._flat = 8u;
     out_._linear = 27.0;
@@ -27,15 +29,17 @@ void main() {
     out_.perspective = vec4(729.0, 1000.0, 1331.0, 1728.0);
     out_.perspective_centroid = 2197.0;
     out_.perspective_sample = 2744.0;
-    FragmentInput _e30 = out_;
-    gl_Position = _e30.position;
-    _vs2fs_location0 = _e30._flat;
-    _vs2fs_location1 = _e30._linear;
-    _vs2fs_location2 = _e30.linear_centroid;
-    _vs2fs_location3 = _e30.linear_sample;
-    _vs2fs_location4 = _e30.perspective;
-    _vs2fs_location5 = _e30.perspective_centroid;
-    _vs2fs_location6 = _e30.perspective_sample;
+    out_._int = -9;
+    FragmentInput _e32 = out_;
+    gl_Position = _e32.position;
+    _vs2fs_location0 = _e32._flat;
+    _vs2fs_location1 = _e32._linear;
+    _vs2fs_location2 = _e32.linear_centroid;
+    _vs2fs_location3 = _e32.linear_sample;
+    _vs2fs_location4 = _e32.perspective;
+    _vs2fs_location5 = _e32.perspective_centroid;
+    _vs2fs_location6 = _e32.perspective_sample;
+    _vs2fs_location7 = _e32._int;
     return;
 }
 
@@ -205,6 +205,8 @@ void comparison() {
 void assignment() {
     int a = 1;
     ivec3 vec0_ = ivec3(0, 0, 0);
+    vec3 vec1_ = vec3(0.0);
+    mat2x2 mat0_ = mat2x2(0.0);
     int _e6 = a;
     a = (_e6 + 1);
     int _e9 = a;
@@ -235,6 +237,19 @@ void assignment() {
     vec0_.y = (_e46 + 1);
     int _e51 = vec0_.y;
     vec0_.y = (_e51 - 1);
+    vec1_ = vec3(1.0);
+    vec3 _e57 = vec1_;
+    vec1_ = (_e57 + vec3(2.0));
+    vec3 _e61 = vec1_;
+    vec1_ = (_e61 * 2.0);
+    float _e65 = vec1_.y;
+    vec1_.y = (_e65 - 1.0);
+    mat0_ = mat2x2(vec2(1.0), vec2(1.0));
+    mat2x2 _e74 = mat0_;
+    mat2x2 _e75 = mat0_;
+    mat0_ = (_e74 + _e75);
+    mat2x2 _e77 = mat0_;
+    mat0_ = (_e77 * 0.5);
     return;
 }
 
@@ -18,18 +18,33 @@ struct Test3_ {
     mat4x3 a;
     float b;
 };
+struct Test4_ {
+    vec3 a;
+    float b;
+    float c;
+};
+struct Test5_ {
+    float a;
+    vec2 b;
+};
 uniform Test_block_0Vertex { Test _group_0_binding_0_vs; };
 
 uniform Test2__block_1Vertex { Test2_ _group_0_binding_1_vs; };
 
 uniform Test3__block_2Vertex { Test3_ _group_0_binding_2_vs; };
 
+uniform Test4__block_3Vertex { Test4_ _group_0_binding_3_vs; };
+
+uniform Test5__block_4Vertex { Test5_ _group_0_binding_4_vs; };
+
 
 void main() {
-    float _e6 = _group_0_binding_0_vs.b;
-    float _e9 = _group_0_binding_1_vs.b;
-    float _e12 = _group_0_binding_2_vs.b;
-    gl_Position = (((vec4(1.0) * _e6) * _e9) * _e12);
+    float _e8 = _group_0_binding_0_vs.b;
+    float _e11 = _group_0_binding_1_vs.b;
+    float _e14 = _group_0_binding_2_vs.b;
+    float _e17 = _group_0_binding_3_vs.c;
+    float _e21 = _group_0_binding_4_vs.b.x;
+    gl_Position = (((((vec4(1.0) * _e8) * _e11) * _e14) * _e17) * _e21);
     gl_Position.yz = vec2(-gl_Position.y, gl_Position.z * 2.0 - gl_Position.w);
     return;
 }
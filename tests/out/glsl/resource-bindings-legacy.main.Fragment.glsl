@@ -0,0 +1,19 @@
+#version 330 core
+struct Globals {
+    vec4 tint;
+};
+layout(std140) uniform Globals_block_0Fragment { Globals _group_0_binding_0_fs; };
+
+uniform highp sampler2D _group_0_binding_1_fs;
+
+smooth in vec2 _vs2fs_location0;
+layout(location = 0) out vec4 _fs2p_location0;
+
+void main() {
+    vec2 uv = _vs2fs_location0;
+    vec4 _e4 = texture(_group_0_binding_1_fs, vec2(uv));
+    vec4 _e6 = _group_0_binding_0_fs.tint;
+    _fs2p_location0 = (_e4 * _e6);
+    return;
+}
+
@@ -0,0 +1,22 @@
+#version 310 es
+#extension GL_OES_sample_variables : require
+
+precision highp float;
+precision highp int;
+
+struct FragmentOutput {
+    uint sample_mask;
+    float color;
+};
+layout(location = 0) out float _fs2p_location0;
+
+void main() {
+    uint sample_index = uint(gl_SampleID);
+    uint sample_mask = uint(gl_SampleMaskIn[0]);
+    uint mask = (sample_mask & (1u << sample_index));
+    FragmentOutput _tmp_return = FragmentOutput(mask, 1.0);
+    gl_SampleMask[0] = int(_tmp_return.sample_mask);
+    _fs2p_location0 = _tmp_return.color;
+    return;
+}
+
@@ -21,7 +21,7 @@ ret_Constructarray5_array10_float__ Constructarray5_array10_float__(float arg0[1
 }
 
 RWByteAddressBuffer bar : register(u0);
-cbuffer baz : register(b1) { Baz baz; }
+ConstantBuffer<Baz> baz : register(b1);
 RWByteAddressBuffer qux : register(u2);
 groupshared uint val;
 
@@ -138,7 +138,7 @@ float4 foo_vert(uint vi : SV_VertexID) : SV_Position
     uint2 arr[2] = {asuint(bar.Load2(104+0)), asuint(bar.Load2(104+8))};
     float b = asfloat(bar.Load(0+48+0));
     int a_1 = asint(bar.Load(0+(((NagaBufferLengthRW(bar) - 120) / 8) - 2u)*8+120));
-    int2 c_1 = asint(qux.Load2(0));
+    int2 c2_ = asint(qux.Load2(0));
     const float _e30 = read_from_private(foo);
     {
         int _result[5]=Constructarray5_int_(a_1, int(b), 3, 4, 5);
@@ -15,7 +15,7 @@ Texture2D<float> texture_array_depth[5] : register(t0, space4);
 RWTexture2D<float4> texture_array_storage[5] : register(u0, space5);
 SamplerState samp[5] : register(s0, space6);
 SamplerComparisonState samp_comp[5] : register(s0, space7);
-cbuffer uni : register(b0, space8) { UniformIndex uni; }
+ConstantBuffer<UniformIndex> uni : register(b0, space8);
 
 struct FragmentInput_main {
     nointerpolation uint index : LOC0;
@@ -32,7 +32,7 @@ int NagaNumLayers2DArray(Texture2DArray<float4> tex)
 {
     uint4 ret;
     tex.GetDimensions(0, ret.x, ret.y, ret.z, ret.w);
-    return ret.w;
+    return ret.z;
 }
 
 int NagaNumLevels2D(Texture2D<float4> tex)
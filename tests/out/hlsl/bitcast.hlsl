@@ -0,0 +1,29 @@
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    float f = 1.5;
+    float4 f4_ = (float4)0;
+    bool2 b2_ = (bool2)0;
+
+    f4_ = (1.5).xxxx;
+    float _expr5 = f;
+    uint u = asuint(_expr5);
+    float4 _expr7 = f4_;
+    uint4 u4_ = asuint(_expr7);
+    int i = asint(u);
+    int4 i4_ = asint(u4_);
+    float4 back4_ = asfloat(i4_);
+    float _expr12 = f;
+    int c = int(_expr12);
+    float _expr14 = f;
+    uint cu = uint(_expr14);
+    float4 _expr16 = f4_;
+    int4 c4_ = int4(_expr16);
+    float back = float(u);
+    b2_ = bool2(true, false);
+    bool2 _expr23 = b2_;
+    bool2 same_b2_ = _expr23;
+    float4 _expr25 = f4_;
+    float4 same_f4_ = _expr25;
+}
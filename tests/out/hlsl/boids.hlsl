@@ -15,7 +15,7 @@ struct SimParams {
     float rule3Scale;
 };
 
-cbuffer params : register(b0) { SimParams params; }
+ConstantBuffer<SimParams> params : register(b0);
 ByteAddressBuffer particlesSrc : register(t1);
 RWByteAddressBuffer particlesDst : register(u2);
 
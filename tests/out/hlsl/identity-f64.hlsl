@@ -0,0 +1,10 @@
+
+[numthreads(1, 1, 1)]
+void main()
+{
+    double2 d2_ = (double2)0;
+
+    d2_ = (double(1.5)).xx;
+    double2 _expr4 = d2_;
+    double2 same_d2_ = _expr4;
+}
@@ -178,7 +178,7 @@ int NagaNumLayers2DArray(Texture2DArray<float4> tex)
 {
     uint4 ret;
     tex.GetDimensions(0, ret.x, ret.y, ret.z, ret.w);
-    return ret.w;
+    return ret.z;
 }
 
 int NagaNumLevelsCube(TextureCube<float4> tex)
@@ -199,7 +199,7 @@ int NagaNumLayersCubeArray(TextureCubeArray<float4> tex)
 {
     uint4 ret;
     tex.GetDimensions(0, ret.x, ret.y, ret.z, ret.w);
-    return ret.w;
+    return ret.z;
 }
 
 int NagaNumLevels3D(Texture3D<float4> tex)
@@ -266,5 +266,6 @@ float4 depth_no_comparison() : SV_Target0
     float2 tc_3 = (0.5).xx;
     float s2d_2 = image_2d_depth.Sample(sampler_reg, tc_3);
     float4 s2d_gather = image_2d_depth.Gather(sampler_reg, tc_3);
-    return ((s2d_2).xxxx + s2d_gather);
+    float s2d_level_1 = image_2d_depth.SampleLevel(sampler_reg, tc_3, float(1));
+    return (((s2d_2).xxxx + s2d_gather) + (s2d_level_1).xxxx);
 }
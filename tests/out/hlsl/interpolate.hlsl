@@ -8,6 +8,7 @@ struct FragmentInput {
     float4 perspective : LOC4;
     centroid float perspective_centroid : LOC5;
     sample float perspective_sample : LOC6;
+    nointerpolation int _int : LOC7;
 };
 
 struct VertexOutput_vert_main {
@@ -18,6 +19,7 @@ struct VertexOutput_vert_main {
     float4 perspective : LOC4;
     centroid float perspective_centroid : LOC5;
     sample float perspective_sample : LOC6;
+    nointerpolation int _int : LOC7;
     float4 position : SV_Position;
 };
 
@@ -29,6 +31,7 @@ struct FragmentInput_frag_main {
     float4 perspective_1 : LOC4;
     centroid float perspective_centroid_1 : LOC5;
     sample float perspective_sample_1 : LOC6;
+    nointerpolation int _int_1 : LOC7;
     float4 position_1 : SV_Position;
 };
 
@@ -44,14 +47,15 @@ VertexOutput_vert_main vert_main()
     out_.perspective = float4(729.0, 1000.0, 1331.0, 1728.0);
     out_.perspective_centroid = 2197.0;
     out_.perspective_sample = 2744.0;
-    FragmentInput _expr30 = out_;
-    const FragmentInput fragmentinput = _expr30;
-    const VertexOutput_vert_main fragmentinput_1 = { fragmentinput._flat, fragmentinput._linear, fragmentinput.linear_centroid, fragmentinput.linear_sample, fragmentinput.perspective, fragmentinput.perspective_centroid, fragmentinput.perspective_sample, fragmentinput.position };
+    out_._int = -9;
+    FragmentInput _expr32 = out_;
+    const FragmentInput fragmentinput = _expr32;
+    const VertexOutput_vert_main fragmentinput_1 = { fragmentinput._flat, fragmentinput._linear, fragmentinput.linear_centroid, fragmentinput.linear_sample, fragmentinput.perspective, fragmentinput.perspective_centroid, fragmentinput.perspective_sample, fragmentinput._int, fragmentinput.position };
     return fragmentinput_1;
 }
 
 void frag_main(FragmentInput_frag_main fragmentinput_frag_main)
 {
-    FragmentInput val = { fragmentinput_frag_main.position_1, fragmentinput_frag_main._flat_1, fragmentinput_frag_main._linear_1, fragmentinput_frag_main.linear_centroid_1, fragmentinput_frag_main.linear_sample_1, fragmentinput_frag_main.perspective_1, fragmentinput_frag_main.perspective_centroid_1, fragmentinput_frag_main.perspective_sample_1 };
+    FragmentInput val = { fragmentinput_frag_main.position_1, fragmentinput_frag_main._flat_1, fragmentinput_frag_main._linear_1, fragmentinput_frag_main.linear_centroid_1, fragmentinput_frag_main.linear_sample_1, fragmentinput_frag_main.perspective_1, fragmentinput_frag_main.perspective_centroid_1, fragmentinput_frag_main.perspective_sample_1, fragmentinput_frag_main._int_1 };
     return;
 }
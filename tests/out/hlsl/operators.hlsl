@@ -31,8 +31,8 @@ float4 builtins()
     float4 s3_ = (bool4(false, false, false, false) ? float4(0.0, 0.0, 0.0, 0.0) : float4(1.0, 1.0, 1.0, 1.0));
     float4 m1_ = lerp(float4(0.0, 0.0, 0.0, 0.0), float4(1.0, 1.0, 1.0, 1.0), float4(0.5, 0.5, 0.5, 0.5));
     float4 m2_ = lerp(float4(0.0, 0.0, 0.0, 0.0), float4(1.0, 1.0, 1.0, 1.0), 0.10000000149011612);
-    float b1_ = float(int4(1, 1, 1, 1).x);
-    float4 b2_ = float4(int4(1, 1, 1, 1));
+    float b1_ = asfloat(int4(1, 1, 1, 1).x);
+    float4 b2_ = asfloat(int4(1, 1, 1, 1));
     int4 v_i32_zero = int4(float4(0.0, 0.0, 0.0, 0.0));
     return (((((float4(((s1_).xxxx + v_i32_zero)) + s2_) + m1_) + m2_) + (b1_).xxxx) + b2_);
 }
@@ -72,8 +72,8 @@ float constructors()
     float unnamed_6 = float(0.0);
     uint2 unnamed_7 = uint2(uint2(0u, 0u));
     float2x3 unnamed_8 = float2x3(float2x3(float3(0.0, 0.0, 0.0), float3(0.0, 0.0, 0.0)));
-    uint2 unnamed_9 = uint2(uint2(0u, 0u));
-    float2x3 unnamed_10 = float2x3(float2x3(float3(0.0, 0.0, 0.0), float3(0.0, 0.0, 0.0)));
+    uint2 unnamed_9 = uint2(0u, 0u);
+    float2x3 unnamed_10 = float2x3(float3(0.0, 0.0, 0.0), float3(0.0, 0.0, 0.0));
     float _expr75 = foo.a.x;
     return _expr75;
 }
@@ -235,6 +235,8 @@ void assignment()
 {
     int a = 1;
     int3 vec0_ = int3(0, 0, 0);
+    float3 vec1_ = (float3)0;
+    float2x2 mat0_ = (float2x2)0;
 
     int _expr6 = a;
     a = (_expr6 + 1);
@@ -266,6 +268,19 @@ void assignment()
     vec0_.y = (_expr46 + 1);
     int _expr51 = vec0_.y;
     vec0_.y = (_expr51 - 1);
+    vec1_ = (1.0).xxx;
+    float3 _expr57 = vec1_;
+    vec1_ = (_expr57 + (2.0).xxx);
+    float3 _expr61 = vec1_;
+    vec1_ = (_expr61 * 2.0);
+    float _expr65 = vec1_.y;
+    vec1_.y = (_expr65 - 1.0);
+    mat0_ = float2x2((1.0).xx, (1.0).xx);
+    float2x2 _expr74 = mat0_;
+    float2x2 _expr75 = mat0_;
+    mat0_ = (_expr74 + _expr75);
+    float2x2 _expr77 = mat0_;
+    mat0_ = mul(0.5, _expr77);
     return;
 }
 
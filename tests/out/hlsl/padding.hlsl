@@ -30,14 +30,45 @@ struct Test3_ {
     int _end_pad_2;
 };
 
-cbuffer input1_ : register(b0) { Test input1_; }
-cbuffer input2_ : register(b1) { Test2_ input2_; }
-cbuffer input3_ : register(b2) { Test3_ input3_; }
+struct Test4_ {
+    float3 a;
+    int _pad1_0;
+    int _pad1_1;
+    int _pad1_2;
+    int _pad1_3;
+    int _pad1_4;
+    float b;
+    float c;
+    int _end_pad_0;
+    int _end_pad_1;
+    int _end_pad_2;
+    int _end_pad_3;
+    int _end_pad_4;
+    int _end_pad_5;
+};
+
+struct Test5_ {
+    float a;
+    int _pad1_0;
+    int _pad1_1;
+    int _pad1_2;
+    float2 b;
+    int _end_pad_0;
+    int _end_pad_1;
+};
+
+ConstantBuffer<Test> input1_ : register(b0);
+ConstantBuffer<Test2_> input2_ : register(b1);
+ConstantBuffer<Test3_> input3_ : register(b2);
+ConstantBuffer<Test4_> input4_ : register(b3);
+ConstantBuffer<Test5_> input5_ : register(b4);
 
 float4 vertex() : SV_Position
 {
-    float _expr6 = input1_.b;
-    float _expr9 = input2_.b;
-    float _expr12 = input3_.b;
-    return ((((1.0).xxxx * _expr6) * _expr9) * _expr12);
+    float _expr8 = input1_.b;
+    float _expr11 = input2_.b;
+    float _expr14 = input3_.b;
+    float _expr17 = input4_.c;
+    float _expr21 = input5_.b.x;
+    return ((((((1.0).xxxx * _expr8) * _expr11) * _expr14) * _expr17) * _expr21);
 }
@@ -0,0 +1,59 @@
+
+struct type_5 {
+    float member;
+};
+
+static float4 o_color = (float4)0;
+RWByteAddressBuffer storage : register(u0);
+
+void double_in_place(inout float x)
+{
+    float _expr12 = x;
+    x = (_expr12 * 2.0);
+    return;
+}
+
+void bump(inout type_5 counter)
+{
+    float _expr13 = counter.member;
+    counter.member = (_expr13 + 1.0);
+    return;
+}
+
+type_5 Constructtype_5(float arg0) {
+    type_5 ret = (type_5)0;
+    ret.member = arg0;
+    return ret;
+}
+
+void main_1()
+{
+    float value = (float)0;
+    float values[2] = {(float)0,(float)0};
+    type_5 local = (type_5)0;
+
+    value = 1.0;
+    double_in_place(value);
+    values[1] = 2.0;
+    double_in_place(values[1]);
+    type_5 _expr14 = Constructtype_5(asfloat(storage.Load(0)));
+    local = _expr14;
+    bump(local);
+    type_5 _expr16 = local;
+    {
+        type_5 _value2 = _expr16;
+        storage.Store(0, asuint(_value2.member));
+    }
+    float _expr17 = value;
+    float _expr18 = values[1];
+    float _expr19 = (_expr17 + _expr18);
+    o_color = float4(_expr19, _expr19, _expr19, 1.0);
+    return;
+}
+
+float4 main() : SV_Target0
+{
+    main_1();
+    float4 _expr1 = o_color;
+    return _expr1;
+}
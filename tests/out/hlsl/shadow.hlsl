@@ -23,8 +23,8 @@ struct Light {
     float4 color;
 };
 
-cbuffer u_globals : register(b0) { Globals u_globals; }
-cbuffer u_entity : register(b0, space1) { Entity u_entity; }
+ConstantBuffer<Globals> u_globals : register(b0);
+ConstantBuffer<Entity> u_entity : register(b0, space1);
 ByteAddressBuffer s_lights : register(t1);
 cbuffer u_lights : register(b1) { Light u_lights[10]; }
 Texture2DArray<float> t_shadow : register(t2);
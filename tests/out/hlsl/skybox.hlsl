@@ -15,7 +15,7 @@ struct Data {
     row_major float4x4 view;
 };
 
-cbuffer r_data : register(b0) { Data r_data; }
+ConstantBuffer<Data> r_data : register(b0);
 TextureCube<float4> r_texture : register(t0);
 SamplerState r_sampler : register(s0, space1);
 
@@ -1,5 +1,5 @@
 struct AlignedWrapper {
-    value: i32,
+    @size(8) value: i32,
 }
 
 struct Bar {
@@ -84,7 +84,7 @@ fn foo_vert(@builtin(vertex_index) vi: u32) -> @builtin(position) vec4<f32> {
     let arr = bar.arr;
     let b = bar._matrix[3][0];
     let a_1 = bar.data[(arrayLength((&bar.data)) - 2u)].value;
-    let c_1 = qux;
+    let c2_ = qux;
     let data_pointer = (&bar.data[0].value);
     let _e30 = read_from_private((&foo));
     c = array<i32,5>(a_1, i32(b), 3, 4, 5);
@@ -17,7 +17,7 @@ struct Particles {
     particles: array<Particle>,
 }
 
-let NUM_PARTICLES: u32 = 1500u;
+const NUM_PARTICLES: u32 = 1500u;
 
 @group(0) @binding(0) 
 var<uniform> params: SimParams;
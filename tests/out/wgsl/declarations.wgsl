@@ -0,0 +1,22 @@
+const count: i32 = 4;
+
+const scale: vec2<f32> = vec2<f32>(0.5, 2.0);
+fn main() -> f32 {
+    var total: f32 = 0.0;
+    var i: i32 = 0;
+
+    let doubled = (count * 2);
+    loop {
+        let _e8 = i;
+        if (_e8 >= doubled) {
+            break;
+        }
+        let _e10 = total;
+        total = (_e10 + vec2<f32>(0.5, 2.0).x);
+        let _e13 = i;
+        i = (_e13 + 1);
+    }
+    let _e16 = total;
+    return (_e16 * vec2<f32>(0.5, 2.0).y);
+}
+
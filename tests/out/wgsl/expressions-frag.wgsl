@@ -356,6 +356,21 @@ fn ternary(a_20: bool) {
     return;
 }
 
+fn testUnaryOpUint(a_22: u32, b_18: vec2<u32>) {
+    var a_23: u32;
+    var b_19: vec2<u32>;
+    var v_9: u32;
+    var w: vec2<u32>;
+
+    a_23 = a_22;
+    b_19 = b_18;
+    let _e5 = a_23;
+    v_9 = bitcast<u32>(-(bitcast<i32>(_e5)));
+    let _e10 = b_19;
+    w = bitcast<vec2<u32>>(-(bitcast<vec2<i32>>(_e10)));
+    return;
+}
+
 fn main_1() {
     var local_5: f32;
 
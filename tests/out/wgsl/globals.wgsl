@@ -3,7 +3,7 @@ struct Foo {
     v1_: f32,
 }
 
-let Foo_2: bool = true;
+const Foo_2: bool = true;
 
 var<workgroup> wg: array<f32,10u>;
 var<workgroup> at_1: atomic<u32>;
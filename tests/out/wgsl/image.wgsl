@@ -128,5 +128,6 @@ fn depth_no_comparison() -> @location(0) vec4<f32> {
     let tc_3 = vec2<f32>(0.5);
     let s2d_2 = textureSample(image_2d_depth, sampler_reg, tc_3);
     let s2d_gather = textureGather(image_2d_depth, sampler_reg, tc_3);
-    return (vec4<f32>(s2d_2) + s2d_gather);
+    let s2d_level_1 = textureSampleLevel(image_2d_depth, sampler_reg, tc_3, i32(f32(1)));
+    return ((vec4<f32>(s2d_2) + s2d_gather) + vec4<f32>(s2d_level_1));
 }
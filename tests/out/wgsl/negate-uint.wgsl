@@ -0,0 +1,13 @@
+fn function_() {
+    var local: u32;
+
+    local = 1u;
+    let _e6 = local;
+    local = bitcast<u32>(-(bitcast<i32>(_e6)));
+    return;
+}
+
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    function_();
+}
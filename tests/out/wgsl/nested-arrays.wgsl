@@ -0,0 +1,24 @@
+struct Item {
+    a: f32,
+    b: vec2<f32>,
+}
+
+struct Data {
+    grid: array<array<vec2<f32>,3>,5>,
+    items: array<Item,2>,
+    rest: array<array<f32,4>>,
+}
+
+@group(0) @binding(0) 
+var<storage, read_write> data: Data;
+
+@compute @workgroup_size(1, 1, 1) 
+fn main() {
+    var local: array<array<i32,2>,3>;
+
+    let _e11 = data.rest[1][3];
+    let _e16 = data.items[1].b;
+    data.grid[4][2] = (_e11 * _e16);
+    local[2][1] = 1;
+    return;
+}
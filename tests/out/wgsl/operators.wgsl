@@ -3,10 +3,10 @@ struct Foo {
     b: i32,
 }
 
-let v_f32_one: vec4<f32> = vec4<f32>(1.0, 1.0, 1.0, 1.0);
-let v_f32_zero: vec4<f32> = vec4<f32>(0.0, 0.0, 0.0, 0.0);
-let v_f32_half: vec4<f32> = vec4<f32>(0.5, 0.5, 0.5, 0.5);
-let v_i32_one: vec4<i32> = vec4<i32>(1, 1, 1, 1);
+const v_f32_one: vec4<f32> = vec4<f32>(1.0, 1.0, 1.0, 1.0);
+const v_f32_zero: vec4<f32> = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+const v_f32_half: vec4<f32> = vec4<f32>(0.5, 0.5, 0.5, 0.5);
+const v_i32_one: vec4<i32> = vec4<i32>(1, 1, 1, 1);
 fn builtins() -> vec4<f32> {
     let s1_ = select(0, 1, true);
     let s2_ = select(vec4<f32>(0.0, 0.0, 0.0, 0.0), vec4<f32>(1.0, 1.0, 1.0, 1.0), true);
@@ -203,6 +203,8 @@ fn comparison() {
 fn assignment() {
     var a: i32 = 1;
     var vec0_: vec3<i32> = vec3<i32>(0, 0, 0);
+    var vec1_: vec3<f32>;
+    var mat0_: mat2x2<f32>;
 
     let _e6 = a;
     a = (_e6 + 1);
@@ -234,6 +236,19 @@ fn assignment() {
     vec0_.y = (_e46 + 1);
     let _e51 = vec0_.y;
     vec0_.y = (_e51 - 1);
+    vec1_ = vec3<f32>(1.0);
+    let _e57 = vec1_;
+    vec1_ = (_e57 + vec3<f32>(2.0));
+    let _e61 = vec1_;
+    vec1_ = (_e61 * 2.0);
+    let _e65 = vec1_.y;
+    vec1_.y = (_e65 - 1.0);
+    mat0_ = mat2x2<f32>(vec2<f32>(1.0), vec2<f32>(1.0));
+    let _e74 = mat0_;
+    let _e75 = mat0_;
+    mat0_ = (_e74 + _e75);
+    let _e77 = mat0_;
+    mat0_ = (_e77 * 0.5);
     return;
 }
 
@@ -17,17 +17,34 @@ struct Test3_ {
     b: f32,
 }
 
+struct Test4_ {
+    a: vec3<f32>,
+    @align(32) b: f32,
+    c: f32,
+}
+
+struct Test5_ {
+    a: f32,
+    @align(16) b: vec2<f32>,
+}
+
 @group(0) @binding(0) 
 var<uniform> input1_: Test;
 @group(0) @binding(1) 
 var<uniform> input2_: Test2_;
 @group(0) @binding(2) 
 var<uniform> input3_: Test3_;
+@group(0) @binding(3) 
+var<uniform> input4_: Test4_;
+@group(0) @binding(4) 
+var<uniform> input5_: Test5_;
 
 @vertex 
 fn vertex() -> @builtin(position) vec4<f32> {
-    let _e6 = input1_.b;
-    let _e9 = input2_.b;
-    let _e12 = input3_.b;
-    return (((vec4<f32>(1.0) * _e6) * _e9) * _e12);
+    let _e8 = input1_.b;
+    let _e11 = input2_.b;
+    let _e14 = input3_.b;
+    let _e17 = input4_.c;
+    let _e21 = input5_.b.x;
+    return (((((vec4<f32>(1.0) * _e8) * _e11) * _e14) * _e17) * _e21);
 }
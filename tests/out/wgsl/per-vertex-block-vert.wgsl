@@ -0,0 +1,23 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+}
+
+var<private> a_pos_1: vec2<f32>;
+var<private> gl_Position: vec4<f32>;
+var<private> gl_PointSize: f32;
+
+fn main_1() {
+    let _e2 = a_pos_1;
+    gl_Position = vec4<f32>(_e2.x, _e2.y, 0.0, 1.0);
+    gl_PointSize = 4.0;
+    return;
+}
+
+@vertex 
+fn main(@location(0) a_pos: vec2<f32>) -> VertexOutput {
+    a_pos_1 = a_pos;
+    main_1();
+    let _e5 = gl_Position;
+    let _e7 = gl_PointSize;
+    return VertexOutput(_e5);
+}
@@ -0,0 +1,47 @@
+struct type_5 {
+    member: f32,
+}
+
+var<private> o_color: vec4<f32>;
+@group(0) @binding(0) 
+var<storage, read_write> storage_: type_5;
+
+fn double_in_place(x: ptr<function, f32>) {
+    let _e12 = (*x);
+    (*x) = (_e12 * 2.0);
+    return;
+}
+
+fn bump(counter: ptr<function, type_5>) {
+    let _e13 = (*counter).member;
+    (*counter).member = (_e13 + 1.0);
+    return;
+}
+
+fn main_1() {
+    var value: f32;
+    var values: array<f32,2u>;
+    var local: type_5;
+
+    value = 1.0;
+    double_in_place((&value));
+    values[1] = 2.0;
+    double_in_place((&values[1]));
+    let _e14 = storage_;
+    local = _e14;
+    bump((&local));
+    let _e16 = local;
+    storage_ = _e16;
+    let _e17 = value;
+    let _e18 = values[1];
+    let _e19 = (_e17 + _e18);
+    o_color = vec4<f32>(_e19, _e19, _e19, 1.0);
+    return;
+}
+
+@fragment 
+fn main() -> @location(0) vec4<f32> {
+    main_1();
+    let _e1 = o_color;
+    return _e1;
+}
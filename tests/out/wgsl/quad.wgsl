@@ -3,7 +3,7 @@ struct VertexOutput {
     @builtin(position) position: vec4<f32>,
 }
 
-let c_scale: f32 = 1.2000000476837158;
+const c_scale: f32 = 1.2000000476837158;
 
 @group(0) @binding(0) 
 var u_texture: texture_2d<f32>;
@@ -0,0 +1,28 @@
+struct Light {
+    color: vec4<f32>,
+    intensity: f32,
+}
+
+var<private> color: vec4<f32>;
+
+fn tint(param: f32) -> vec4<f32> {
+    return vec4<f32>(param, param, param, 1.0);
+}
+
+fn make_light(param_1: vec4<f32>) -> Light {
+    return Light(param_1, 0.5);
+}
+
+fn function_() {
+    let _e7 = tint(0.5);
+    let _e8 = make_light(_e7);
+    color = (_e8.color * _e8.intensity);
+    return;
+}
+
+@fragment 
+fn main() -> @location(0) vec4<f32> {
+    function_();
+    let _e1 = color;
+    return _e1;
+}
@@ -391,6 +391,21 @@ fn write_output_wgsl(
 
     let string = wgsl::write_string(module, info, flags).expect("WGSL write failed");
 
+    // Make sure the output can be read back in.
+    #[cfg(feature = "wgsl-in")]
+    {
+        let module = match naga::front::wgsl::parse_str(&string) {
+            Ok(module) => module,
+            Err(e) => panic!("{}", e.emit_to_string(&string)),
+        };
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .expect("WGSL output validation failed");
+    }
+
     fs::write(destination.join(format!("wgsl/{}.wgsl", file_name)), string).unwrap();
 }
 
@@ -503,10 +518,21 @@ fn convert_wgsl() {
             Targets::SPIRV | Targets::METAL | Targets::GLSL | Targets::HLSL | Targets::WGSL,
         ),
         ("cubeArrayShadow", Targets::GLSL),
+        (
+            "early-depth-test",
+            Targets::SPIRV | Targets::METAL | Targets::GLSL,
+        ),
         (
             "binding-arrays",
             Targets::WGSL | Targets::HLSL | Targets::METAL | Targets::SPIRV,
         ),
+        ("declarations", Targets::WGSL),
+        ("nested-arrays", Targets::WGSL),
+        ("bitcast", Targets::METAL | Targets::GLSL | Targets::HLSL),
+        ("identity-f64", Targets::HLSL),
+        ("resource-bindings", Targets::GLSL),
+        ("resource-bindings-legacy", Targets::GLSL),
+        ("sample-mask", Targets::GLSL),
     ];
 
     for &(name, targets) in inputs.iter() {
@@ -522,11 +548,11 @@ fn convert_wgsl() {
 }
 
 #[cfg(feature = "spv-in")]
-fn convert_spv(name: &str, adjust_coordinate_space: bool, targets: Targets) {
+fn parse_spv(name: &str, adjust_coordinate_space: bool) -> naga::Module {
     let _ = env_logger::try_init();
 
     let root = env!("CARGO_MANIFEST_DIR");
-    let module = naga::front::spv::parse_u8_slice(
+    naga::front::spv::parse_u8_slice(
         &fs::read(format!("{}/{}/spv/{}.spv", root, BASE_DIR_IN, name))
             .expect("Couldn't find spv file"),
         &naga::front::spv::Options {
@@ -535,7 +561,12 @@ fn convert_spv(name: &str, adjust_coordinate_space: bool, targets: Targets) {
             block_ctx_dump_prefix: None,
         },
     )
-    .unwrap();
+    .unwrap()
+}
+
+#[cfg(feature = "spv-in")]
+fn convert_spv(name: &str, adjust_coordinate_space: bool, targets: Targets) {
+    let module = parse_spv(name, adjust_coordinate_space);
     check_targets(&module, name, targets);
     naga::valid::Validator::new(
         naga::valid::ValidationFlags::all(),
@@ -570,6 +601,52 @@ fn convert_spv_all() {
         Targets::HLSL | Targets::WGSL | Targets::METAL,
     );
     convert_spv("degrees", false, Targets::empty());
+    convert_spv("pointer-args", true, Targets::WGSL | Targets::HLSL);
+    convert_spv("clip-distance", false, Targets::SPIRV | Targets::GLSL);
+    convert_spv("return-composite", false, Targets::WGSL);
+    convert_spv("negate-uint", false, Targets::WGSL);
+}
+
+#[cfg(feature = "spv-in")]
+#[test]
+fn convert_spv_f16() {
+    // Half precision floats need `Capabilities::FLOAT16`, which `convert_spv`
+    // doesn't enable for its final validation pass.
+    let module = parse_spv("f16", false);
+    check_targets(&module, "f16", Targets::GLSL | Targets::SPIRV);
+
+    // The other backends can't write half precision floats.
+    #[allow(unused_variables)]
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::FLOAT16,
+    )
+    .validate(&module)
+    .unwrap();
+    #[cfg(feature = "msl-out")]
+    assert!(matches!(
+        naga::back::msl::write_string(&module, &info, &Default::default(), &Default::default()),
+        Err(naga::back::msl::Error::CapabilityNotSupported(
+            naga::valid::Capabilities::FLOAT16
+        ))
+    ));
+    #[cfg(feature = "hlsl-out")]
+    assert!(matches!(
+        naga::back::hlsl::Writer::new(&mut String::new(), &Default::default())
+            .write(&module, &info),
+        Err(naga::back::hlsl::Error::UnsupportedScalar(
+            naga::ScalarKind::Float,
+            2
+        ))
+    ));
+    #[cfg(feature = "wgsl-out")]
+    assert!(matches!(
+        naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty()),
+        Err(naga::back::wgsl::Error::UnsupportedScalar(
+            naga::ScalarKind::Float,
+            2
+        ))
+    ));
 }
 
 #[cfg(feature = "glsl-in")]
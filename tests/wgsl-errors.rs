@@ -166,6 +166,24 @@ fn bad_type_cast() {
     );
 }
 
+#[test]
+fn bad_bitcast() {
+    check(
+        r#"
+            fn x() -> u32 {
+                return bitcast<u32>(vec2<f32>(0.0));
+            }
+        "#,
+        r#"error: cannot cast a vec2<f32> to a u32
+  ┌─ wgsl:3:32
+  │
+3 │                 return bitcast<u32>(vec2<f32>(0.0));
+  │                                ^^^ cannot cast a vec2<f32> to a u32
+
+"#,
+    );
+}
+
 #[test]
 fn type_not_constructible() {
     check(
@@ -463,6 +481,42 @@ fn struct_member_zero_align() {
     );
 }
 
+#[test]
+fn struct_member_non_po2_align() {
+    check(
+        r#"
+            struct Bar {
+                @align(7) data: array<f32>
+            }
+        "#,
+        r#"error: struct member alignment must be a power of 2
+  ┌─ wgsl:3:24
+  │
+3 │                 @align(7) data: array<f32>
+  │                        ^ must be a power of 2
+
+"#,
+    );
+}
+
+#[test]
+fn struct_member_size_too_low() {
+    check(
+        r#"
+            struct Bar {
+                @size(8) data: vec3<f32>
+            }
+        "#,
+        r#"error: struct member size `8` is smaller than the size of its type
+  ┌─ wgsl:3:23
+  │
+3 │                 @size(8) data: vec3<f32>
+  │                       ^ must be at least 12
+
+"#,
+    );
+}
+
 #[test]
 fn inconsistent_binding() {
     check(
@@ -615,6 +669,60 @@ fn postfix_pointers() {
     );
 }
 
+#[test]
+fn assignment_to_value() {
+    check(
+        r#"
+            fn main() {
+                let a = 1;
+                a += 1;
+            }
+        "#,
+        r#"error: the left-hand side of an assignment must be a reference
+  ┌─ wgsl:4:17
+  │
+4 │                 a += 1;
+  │                 ^ expression is not a reference
+
+"#,
+    );
+
+    check(
+        r#"
+            fn main() {
+                let a = 1;
+                a++;
+            }
+        "#,
+        r#"error: the left-hand side of an assignment must be a reference
+  ┌─ wgsl:4:17
+  │
+4 │                 a++;
+  │                 ^ expression is not a reference
+
+"#,
+    );
+}
+
+#[test]
+fn bad_increment_type() {
+    check(
+        r#"
+            fn main() {
+                var f = 1.0;
+                f++;
+            }
+        "#,
+        r#"error: increment/decrement operation requires reference type to be one of i32 or u32
+  ┌─ wgsl:4:17
+  │
+4 │                 f++;
+  │                 ^ must be a reference type of i32 or u32
+
+"#,
+    );
+}
+
 #[test]
 fn reserved_keyword() {
     // global var
@@ -930,7 +1038,15 @@ fn invalid_arrays() {
 
     check_validation! {
         "type Bad = array<f32, 0>;",
-        "type Bad = array<f32, -1>;":
+        "type Bad = array<f32, -1>;",
+        r#"
+            let length: u32 = 0u;
+            type Bad = array<f32, length>;
+        "#,
+        r#"
+            let length: i32 = -4;
+            type Bad = array<f32, length>;
+        "#:
         Err(naga::valid::ValidationError::Type {
             error: naga::valid::TypeError::NonPositiveArrayLength(_),
             ..
@@ -938,6 +1054,35 @@ fn invalid_arrays() {
     }
 }
 
+#[test]
+fn invalid_matrices() {
+    // Integer matrices are rejected by the front end, and the IR has no way
+    // to express them. Without `FLOAT64`, only 32-bit float matrices pass.
+    check_validation! {
+        "var<private> m: mat2x2<f64>;",
+        "var<private> m: array<mat4x3<f64>, 2>;":
+        Err(naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::InvalidWidth(naga::ScalarKind::Float, 8),
+            ..
+        })
+    }
+}
+
+#[test]
+fn invalid_composite_constants() {
+    check_validation! {
+        "let v = vec2<f32>(1, 2);",
+        "let v = vec3<i32>(1, 2u, 3);",
+        "let m = mat2x2<f32>(vec2<f32>(1.0, 2.0), vec2<i32>(3, 4));":
+        Err(naga::valid::ValidationError::Constant {
+            error: naga::valid::ConstantError::Compose(
+                naga::valid::ComposeError::ComponentType { .. },
+            ),
+            ..
+        })
+    }
+}
+
 #[test]
 fn invalid_structs() {
     check_validation! {
@@ -1242,6 +1387,43 @@ fn dead_code() {
     }
 }
 
+#[test]
+fn shadowing_globals() {
+    let source = "
+        var<private> value: f32;
+        let scale: f32 = 2.0;
+
+        fn scaled(value: f32) -> f32 {
+            var scale: f32 = 1.0;
+            return value * scale;
+        }
+    ";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    // Shadowing is allowed by default.
+    let mut validator = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    );
+    validator.validate(&module).unwrap();
+    assert!(validator.warnings().is_empty());
+
+    // When reported, it's only a warning.
+    validator.report_shadowing(true).validate(&module).unwrap();
+    let names: Vec<_> = validator
+        .warnings()
+        .iter()
+        .map(|warning| match *warning.as_inner() {
+            naga::valid::ValidationError::Function {
+                error: naga::valid::FunctionError::ShadowingGlobal { ref name },
+                ..
+            } => name.as_str(),
+            ref other => panic!("Unexpected warning {:?}", other),
+        })
+        .collect();
+    assert_eq!(names, ["value", "scale"]);
+}
+
 #[test]
 fn invalid_runtime_sized_arrays() {
     // You can't have structs whose last member is an unsized struct. An unsized
@@ -1271,6 +1453,45 @@ fn invalid_runtime_sized_arrays() {
         })
         if struct_name == "Outer" && member_name == "_unsized"
     }
+
+    // Only storage buffers may contain runtime-sized arrays.
+    check_validation! {
+        "
+        struct Unsized {
+            legit: i32,
+            arr: array<f32>
+        }
+
+        @group(0) @binding(0) var<uniform> u: Unsized;
+        ",
+        "
+        @group(0) @binding(0) var<uniform> u: array<vec4<f32>>;
+        ",
+        "
+        var<private> p: array<f32>;
+        ",
+        "
+        var<workgroup> w: array<u32>;
+        ":
+        Err(naga::valid::ValidationError::GlobalVariable {
+            error: naga::valid::GlobalVariableError::UnsizedType(_, _),
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        struct Unsized {
+            arr: array<f32>,
+            legit: i32
+        }
+        ":
+        Err(naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::InvalidDynamicArray(member_name, _),
+            ..
+        })
+        if member_name == "arr"
+    }
 }
 
 #[test]
@@ -1356,6 +1577,50 @@ fn missing_default_case() {
     }
 }
 
+#[test]
+fn switch_selector_and_cases() {
+    check_validation! {
+        "
+        fn test_float_selector() {
+          switch(1.0) {
+            default: {}
+          }
+        }
+        ",
+        "
+        fn test_vector_selector() {
+          switch(vec2<i32>(0)) {
+            default: {}
+          }
+        }
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                error: naga::valid::FunctionError::InvalidSwitchType(_),
+                ..
+            },
+        )
+    }
+
+    check_validation! {
+        "
+        fn test_duplicate_case() {
+          switch(0) {
+            case 1: {}
+            case 0, 1: {}
+            default: {}
+          }
+        }
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                error: naga::valid::FunctionError::DuplicateSwitchCase(1),
+                ..
+            },
+        )
+    }
+}
+
 #[test]
 fn wrong_access_mode() {
     // The assignments to `global.i` should be forbidden, because they are in
@@ -1372,10 +1637,25 @@ fn wrong_access_mode() {
             fn store(v: i32) {
                 globals.i = v;
             }
-        ",
+        ":
+        Err(
+            naga::valid::ValidationError::Function {
+                name,
+                error: naga::valid::FunctionError::InvalidStorePointer(_),
+                ..
+            },
+        )
+            if name == "store"
+    }
+}
+
+#[test]
+fn write_to_uniform() {
+    check_validation! {
         "
             struct Globals {
-                i: i32
+                i: i32,
+                v: vec4<f32>,
             }
 
             @group(0) @binding(0)
@@ -1384,11 +1664,24 @@ fn wrong_access_mode() {
             fn store(v: i32) {
                 globals.i = v;
             }
+        ",
+        "
+            struct Globals {
+                i: i32,
+                v: vec4<f32>,
+            }
+
+            @group(0) @binding(0)
+            var<uniform> globals: Globals;
+
+            fn store(x: f32) {
+                globals.v.y = x;
+            }
         ":
         Err(
             naga::valid::ValidationError::Function {
                 name,
-                error: naga::valid::FunctionError::InvalidStorePointer(_),
+                error: naga::valid::FunctionError::WriteToUniform(_),
                 ..
             },
         )
@@ -1483,7 +1776,10 @@ fn host_shareable_types() {
                 naga::valid::ValidationError::GlobalVariable {
                     name,
                     handle: _,
-                    error: naga::valid::GlobalVariableError::MissingTypeFlags { .. },
+                    error: naga::valid::GlobalVariableError::TypeNotHostShareable(
+                        _,
+                        naga::AddressSpace::Storage { .. },
+                    ),
                 },
             )
             if name == "sbuf"
@@ -1494,10 +1790,1213 @@ fn host_shareable_types() {
             Err(naga::valid::ValidationError::GlobalVariable {
                     name,
                     handle: _,
-                    error: naga::valid::GlobalVariableError::MissingTypeFlags { .. },
+                    error: naga::valid::GlobalVariableError::TypeNotHostShareable(
+                        _,
+                        naga::AddressSpace::Uniform,
+                    ),
                 },
             )
             if name == "ubuf"
         }
     }
 }
+
+#[test]
+fn atomic_compare_exchange() {
+    check_validation! {
+        "
+        @group(0) @binding(0)
+        var<storage, read_write> counter: atomic<u32>;
+
+        fn exchange() -> u32 {
+            let result = atomicCompareExchangeWeak(&counter, 0u, 1u);
+            return result.x;
+        }
+        ",
+        "
+        var<workgroup> counter: atomic<i32>;
+
+        fn exchange() -> i32 {
+            let result = atomicCompareExchangeWeak(&counter, 0, 1);
+            return result.x;
+        }
+        ":
+        Ok(_)
+    }
+}
+
+#[test]
+fn invalid_atomic_pointer_space() {
+    // Atomics in read-only storage buffers can't be modified.
+    check_validation! {
+        "
+        @group(0) @binding(0)
+        var<storage> counter: atomic<u32>;
+
+        fn exchange() -> u32 {
+            let result = atomicCompareExchangeWeak(&counter, 0u, 1u);
+            return result.x;
+        }
+        ",
+        "
+        @group(0) @binding(0)
+        var<storage, read> counter: atomic<u32>;
+
+        fn increment() -> u32 {
+            return atomicAdd(&counter, 1u);
+        }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::InvalidAtomic(
+                naga::valid::AtomicError::InvalidPointerSpace(
+                    _,
+                    naga::AddressSpace::Storage { .. },
+                ),
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn invalid_entry_point_names() {
+    let source = "
+        @fragment
+        fn fs_main() {}
+
+        @vertex
+        fn vs_main() -> @builtin(position) vec4<f32> {
+            return vec4<f32>(0.0);
+        }
+    ";
+    let validate = |names: [&str; 2]| {
+        let mut module = naga::front::wgsl::parse_str(source).unwrap();
+        for (ep, &name) in module.entry_points.iter_mut().zip(names.iter()) {
+            ep.name = name.to_string();
+        }
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .map_err(|e| e.into_inner())
+    };
+
+    // The same name may be used in different stages.
+    assert!(validate(["main", "main"]).is_ok());
+    assert!(validate(["fs_principal", "vs_héros"]).is_ok());
+
+    assert!(matches!(
+        validate(["", "vs_main"]),
+        Err(naga::valid::ValidationError::EntryPoint {
+            stage: naga::ShaderStage::Fragment,
+            error: naga::valid::EntryPointError::EmptyName,
+            ..
+        })
+    ));
+
+    for name in ["0main", "fs main", "fs-main"] {
+        assert!(matches!(
+            validate([name, "vs_main"]),
+            Err(naga::valid::ValidationError::EntryPoint {
+                stage: naga::ShaderStage::Fragment,
+                error: naga::valid::EntryPointError::InvalidName,
+                ..
+            })
+        ));
+    }
+
+    let mut module = naga::front::wgsl::parse_str(source).unwrap();
+    let duplicate = naga::EntryPoint {
+        name: "fs_main".to_string(),
+        stage: naga::ShaderStage::Fragment,
+        early_depth_test: None,
+        workgroup_size: [0; 3],
+        function: naga::Function::default(),
+    };
+    module.entry_points.push(duplicate);
+    let error = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .map_err(|e| e.into_inner());
+    assert!(matches!(
+        error,
+        Err(naga::valid::ValidationError::EntryPoint {
+            stage: naga::ShaderStage::Fragment,
+            error: naga::valid::EntryPointError::Conflict,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn barrier_outside_compute() {
+    check_validation! {
+        "
+        @vertex
+        fn main() -> @builtin(position) vec4<f32> {
+            workgroupBarrier();
+            return vec4<f32>(0.0);
+        }
+        ",
+        "
+        fn helper() {
+            storageBarrier();
+        }
+
+        @fragment
+        fn main() {
+            helper();
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::ForbiddenStageOperations,
+            ..
+        })
+    }
+}
+
+#[test]
+fn early_depth_test_with_depth_write() {
+    check_validation! {
+        "
+        @fragment
+        @early_depth_test
+        fn main(@builtin(position) position: vec4<f32>) -> @builtin(frag_depth) f32 {
+            return position.z;
+        }
+        ",
+        "
+        struct FragmentOutput {
+            @location(0) color: vec4<f32>,
+            @builtin(frag_depth) depth: f32,
+        }
+
+        @fragment
+        @early_depth_test
+        fn main() -> FragmentOutput {
+            return FragmentOutput(vec4<f32>(1.0), 0.5);
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::EarlyDepthTestWithDepthWrite,
+            ..
+        })
+    }
+}
+
+#[test]
+fn sample_gradient_dimension_mismatch() {
+    check_validation! {
+        "
+        @group(0) @binding(0)
+        var texture: texture_2d<f32>;
+        @group(0) @binding(1)
+        var sampler_: sampler;
+
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return textureSampleGrad(texture, sampler_, vec2<f32>(0.5), vec3<f32>(1.0), vec3<f32>(1.0));
+        }
+        ",
+        "
+        @group(0) @binding(0)
+        var texture: texture_cube<f32>;
+        @group(0) @binding(1)
+        var sampler_: sampler;
+
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return textureSampleGrad(texture, sampler_, vec3<f32>(0.5), vec2<f32>(1.0), vec2<f32>(1.0));
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Function(
+                naga::valid::FunctionError::Expression {
+                    error: naga::valid::ExpressionError::GradientDimensionMismatch(..),
+                    ..
+                }
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn zero_workgroup_size() {
+    check_validation! {
+        "
+        @compute @workgroup_size(0, 1, 1)
+        fn main() {}
+        ",
+        "
+        @compute @workgroup_size(64, 0)
+        fn main() {}
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::ZeroWorkgroupSize,
+            ..
+        })
+    }
+}
+
+#[test]
+fn inter_stage_location_limit() {
+    let source = "
+        struct VertexOutput {
+            @builtin(position) position: vec4<f32>,
+            @location(15) color: vec4<f32>,
+            @location(16) normal: vec3<f32>,
+        }
+
+        @vertex
+        fn main() -> VertexOutput {
+            return VertexOutput(vec4<f32>(0.0), vec4<f32>(1.0), vec3<f32>(0.0));
+        }
+    ";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+    let validate = |max| {
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .max_inter_stage_locations(max)
+        .validate(&module)
+        .map_err(|e| e.into_inner())
+    };
+
+    assert!(validate(None).is_ok());
+    assert!(validate(Some(17)).is_ok());
+    assert!(matches!(
+        validate(Some(16)),
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::LocationTooLarge {
+                    location: 16,
+                    max: 16
+                }
+            ),
+            ..
+        })
+    ));
+
+    // Vertex inputs and fragment outputs are limited as well.
+    let validate = |source, max| {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .max_inter_stage_locations(max)
+        .validate(&module)
+        .map_err(|e| e.into_inner())
+    };
+    let vertex_input = "
+        @vertex
+        fn main(@location(16) position: vec4<f32>) -> @builtin(position) vec4<f32> {
+            return position;
+        }
+    ";
+    let fragment_output = "
+        @fragment
+        fn main() -> @location(16) vec4<f32> {
+            return vec4<f32>(1.0);
+        }
+    ";
+
+    assert!(validate(vertex_input, Some(17)).is_ok());
+    assert!(matches!(
+        validate(vertex_input, Some(16)),
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Argument(
+                0,
+                naga::valid::VaryingError::LocationTooLarge {
+                    location: 16,
+                    max: 16
+                }
+            ),
+            ..
+        })
+    ));
+    assert!(validate(fragment_output, Some(17)).is_ok());
+    assert!(matches!(
+        validate(fragment_output, Some(16)),
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::LocationTooLarge {
+                    location: 16,
+                    max: 16
+                }
+            ),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn return_value_mismatch() {
+    check_validation! {
+        "fn f() { return 1; }",
+        r#"
+            fn f(x: i32) -> i32 {
+                if (x > 0) {
+                    return 1;
+                }
+            }
+        "#:
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::ReturnValueMismatch(_),
+            ..
+        })
+    }
+}
+
+#[test]
+fn invalid_image_format() {
+    check_validation! {
+        "
+            @group(0) @binding(0)
+            var image: texture_storage_2d<r8unorm, write>;
+        ",
+        "
+            @group(0) @binding(0)
+            var image: texture_storage_2d<rg11b10float, write>;
+        ":
+        Err(naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::InvalidImageFormat(
+                naga::ImageDimension::D2,
+                naga::ImageClass::Storage { .. },
+            ),
+            ..
+        })
+    }
+
+    // The extended formats are allowed with the matching capability.
+    let module = naga::front::wgsl::parse_str(
+        "
+            @group(0) @binding(0)
+            var image: texture_storage_2d<r8unorm, write>;
+        ",
+    )
+    .unwrap();
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::STORAGE_TEXTURE_EXTENDED_FORMATS,
+    )
+    .validate(&module)
+    .unwrap();
+}
+
+#[test]
+fn wrong_builtin_type() {
+    check_validation! {
+        "
+            @vertex
+            fn main(@builtin(vertex_index) idx: f32) -> @builtin(position) vec4<f32> {
+                return vec4<f32>(idx);
+            }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Argument(
+                0,
+                naga::valid::VaryingError::WrongBuiltinType {
+                    builtin: naga::BuiltIn::VertexIndex,
+                    expected: "u32",
+                },
+            ),
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+            @fragment
+            fn main(@builtin(front_facing) facing: u32) -> @location(0) vec4<f32> {
+                return vec4<f32>(f32(facing));
+            }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Argument(
+                0,
+                naga::valid::VaryingError::WrongBuiltinType {
+                    builtin: naga::BuiltIn::FrontFacing,
+                    expected: "bool",
+                },
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn non_integer_index() {
+    check_validation! {
+        "
+            fn f(i: f32) -> f32 {
+                var a = array<f32, 4>(1.0, 2.0, 3.0, 4.0);
+                return a[i];
+            }
+        ",
+        "
+            fn f(v: vec4<f32>, b: bool) -> f32 {
+                return v[b];
+            }
+        ",
+        "
+            fn f(m: mat2x2<f32>, i: vec2<u32>) -> vec2<f32> {
+                return m[i];
+            }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::Expression {
+                error: naga::valid::ExpressionError::NonIntegerIndex(_),
+                ..
+            },
+            ..
+        })
+    }
+}
+
+#[test]
+fn non_bool_condition() {
+    check_validation! {
+        "
+            fn f(x: i32) {
+                if (x) {
+                    return;
+                }
+            }
+        ",
+        "
+            fn f(x: vec2<bool>) {
+                if (x) {
+                    return;
+                }
+            }
+        ",
+        "
+            fn f(x: f32) {
+                loop {
+                    if (x) {
+                        break;
+                    }
+                }
+            }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::NonBoolCondition(_),
+            ..
+        })
+    }
+}
+
+#[test]
+fn resource_in_private_or_function() {
+    check_validation! {
+        "var<private> t: texture_2d<f32>;",
+        "var<private> s: sampler;",
+        "var<private> a: atomic<u32>;",
+        "
+        struct Counters {
+            value: array<atomic<i32>, 4>,
+        }
+        var<private> c: Counters;
+        ":
+        Err(naga::valid::ValidationError::GlobalVariable {
+            error: naga::valid::GlobalVariableError::InvalidType(naga::AddressSpace::Private),
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        fn f() {
+            var a: atomic<u32>;
+        }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::LocalVariable {
+                error: naga::valid::LocalVariableError::InvalidType(_),
+                ..
+            },
+            ..
+        })
+    }
+}
+
+#[test]
+fn resource_in_output() {
+    // Structs can't have resource members, so output structs are rejected
+    // along with their type.
+    check_validation! {
+        "
+        struct FragmentOutput {
+            @location(0) color: vec4<f32>,
+            @location(1) s: sampler,
+        }
+
+        @fragment
+        fn main() -> FragmentOutput {
+            var output: FragmentOutput;
+            return output;
+        }
+        ":
+        Err(naga::valid::ValidationError::Type {
+            error: naga::valid::TypeError::InvalidData(_),
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        @group(0) @binding(0)
+        var s: sampler;
+
+        @fragment
+        fn main() -> @location(0) sampler {
+            return s;
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            stage: naga::ShaderStage::Fragment,
+            error: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::NotIOShareableType(_),
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn fragment_builtins() {
+    // `primitive_index` needs the `PRIMITIVE_INDEX` capability.
+    check_validation! {
+        "
+        @fragment
+        fn main(@builtin(primitive_index) primitive: u32) -> @location(0) vec4<f32> {
+            return vec4<f32>(f32(primitive));
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Argument(
+                0,
+                naga::valid::VaryingError::UnsupportedCapability(
+                    naga::valid::Capabilities::PRIMITIVE_INDEX,
+                ),
+            ),
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        @vertex
+        fn main(@builtin(sample_mask) mask: u32) -> @builtin(position) vec4<f32> {
+            return vec4<f32>(f32(mask));
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Argument(
+                0,
+                naga::valid::VaryingError::InvalidBuiltInStage(naga::BuiltIn::SampleMask),
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn store_to_non_storage_image() {
+    check_validation! {
+        "
+        @group(0) @binding(0)
+        var image: texture_2d<f32>;
+
+        fn store() {
+            textureStore(image, vec2<i32>(0, 0), vec4<f32>(1.0));
+        }
+        ",
+        "
+        @group(0) @binding(0)
+        var image: texture_storage_2d<rgba8unorm, read>;
+
+        fn store() {
+            textureStore(image, vec2<i32>(0, 0), vec4<f32>(1.0));
+        }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::StoreToNonStorageImage(_),
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        @group(0) @binding(0)
+        var image: texture_storage_2d<rgba8unorm, write>;
+
+        fn store() {
+            textureStore(image, vec2<i32>(0, 0), vec4<f32>(1.0));
+        }
+        ":
+        Ok(_)
+    }
+}
+
+#[test]
+fn binary_operand_types() {
+    check_validation! {
+        "fn f() -> vec3<f32> { return vec2<f32>(1.0) + vec3<f32>(1.0); }",
+        "fn f() -> f32 { return 1.0 + 1; }",
+        "fn f() -> bool { return true && 1; }",
+        "fn f() -> vec2<f32> { return mat2x2<f32>() * vec3<f32>(); }",
+        "fn f() -> i32 { return 1 << 1; }",
+        "fn f() -> bool { return true < false; }",
+        "fn f() -> bool { return mat2x2<f32>() == mat2x2<f32>(); }":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::Expression {
+                error: naga::valid::ExpressionError::IncompatibleOperands { .. },
+                ..
+            },
+            ..
+        })
+    }
+
+    check_validation! {
+        "fn f() -> vec3<f32> { return mat3x3<f32>() * vec3<f32>(); }",
+        "fn f() -> vec2<f32> { return vec3<f32>() * mat2x3<f32>(); }",
+        "fn f() -> mat2x2<f32> { return mat3x2<f32>() * mat2x3<f32>(); }",
+        "fn f() -> vec4<f32> { return 2.0 * vec4<f32>(); }",
+        "fn f() -> vec2<i32> { return vec2<i32>(1) % 2; }",
+        "fn f() -> vec2<u32> { return vec2<u32>(1u) << vec2<u32>(2u); }",
+        "fn f() -> vec2<bool> { return vec2<i32>(1) == vec2<i32>(2); }",
+        "fn f() -> bool { return true || false; }":
+        Ok(_)
+    }
+}
+
+#[test]
+fn unary_operand_types() {
+    check_validation! {
+        "fn f() -> bool { return -true; }",
+        "fn f(x: u32) -> u32 { return -x; }",
+        "fn f() -> vec2<u32> { return -vec2<u32>(1u); }",
+        "fn f() -> f32 { return !1.0; }",
+        "fn f() -> mat2x2<f32> { return ~mat2x2<f32>(); }":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::Expression {
+                error: naga::valid::ExpressionError::InvalidUnaryOperand { .. },
+                ..
+            },
+            ..
+        })
+    }
+
+    check_validation! {
+        "fn f() -> i32 { return -1; }",
+        "fn f() -> vec3<f32> { return -vec3<f32>(1.0); }",
+        "fn f() -> bool { return !true; }",
+        "fn f() -> vec2<u32> { return ~vec2<u32>(1u); }",
+        "fn f() -> i32 { return ~1; }":
+        Ok(_)
+    }
+}
+
+#[test]
+fn global_var_address_space() {
+    check_validation! {
+        "@group(0) @binding(0) var<uniform> flag: bool;",
+        "
+        struct Flags {
+            value: vec2<bool>,
+        }
+        @group(0) @binding(0) var<storage> flags: Flags;
+        ":
+        Err(naga::valid::ValidationError::GlobalVariable {
+            error: naga::valid::GlobalVariableError::TypeNotHostShareable(_, _),
+            ..
+        })
+    }
+
+    check_validation! {
+        "var<workgroup> w: u32 = 1u;":
+        Err(naga::valid::ValidationError::GlobalVariable {
+            error: naga::valid::GlobalVariableError::InitializerNotAllowed(
+                naga::AddressSpace::WorkGroup,
+            ),
+            ..
+        })
+    }
+
+    check_validation! {
+        "var<private> p: u32 = 1u;",
+        "var<workgroup> w: array<bool, 4>;":
+        Ok(_)
+    }
+}
+
+#[test]
+fn struct_compose() {
+    check_validation! {
+        "
+        struct Pair { a: f32, b: vec2<f32> }
+        fn f() -> Pair { return Pair(1.0, vec3<f32>(1.0)); }
+        ",
+        "
+        struct Inner { a: f32, b: vec2<f32> }
+        struct Outer { inner: Inner, c: u32 }
+        fn f() -> Outer { return Outer(Inner(1.0, vec2<f32>(1.0)), 1); }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::Expression {
+                error: naga::valid::ExpressionError::Compose(
+                    naga::valid::ComposeError::ComponentType { index: 1 },
+                ),
+                ..
+            },
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        struct Pair { a: f32, b: vec2<f32> }
+        fn f() -> Pair { return Pair(1.0); }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::Expression {
+                error: naga::valid::ExpressionError::Compose(
+                    naga::valid::ComposeError::ComponentCount {
+                        given: 1,
+                        expected: 2,
+                    },
+                ),
+                ..
+            },
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        struct Inner { a: f32, b: vec2<f32> }
+        struct Outer { inner: Inner, c: u32 }
+        fn f() -> Outer { return Outer(Inner(1.0, vec2<f32>(1.0)), 1u); }
+        ":
+        Ok(_)
+    }
+}
+
+#[test]
+fn override_array_size() {
+    check_validation! {
+        "
+        @id(0) override SCALE: f32 = 1.5;
+        fn f() -> f32 { return SCALE; }
+        ":
+        Ok(_)
+    }
+
+    check_validation! {
+        "
+        @id(0) override SIZE: u32 = 64u;
+        var<workgroup> data: array<f32, SIZE>;
+
+        @compute @workgroup_size(64)
+        fn main(@builtin(local_invocation_index) index: u32) {
+            data[index] = f32(index);
+        }
+        ":
+        Ok(_)
+    }
+
+    // The layout of the array depends on the pipeline.
+    check_validation! {
+        "
+        @id(0) override SIZE: u32 = 64u;
+        @group(0) @binding(0) var<storage> data: array<f32, SIZE>;
+        ":
+        Err(naga::valid::ValidationError::GlobalVariable {
+            error: naga::valid::GlobalVariableError::TypeNotHostShareable(..),
+            ..
+        })
+    }
+}
+
+#[test]
+fn pointer_argument_address_space() {
+    check_validation! {
+        "
+        var<private> value: f32;
+        fn f(p: ptr<private, f32>) -> f32 { return *p; }
+        fn g() -> f32 { return f(&value); }
+        ":
+        Ok(_)
+    }
+
+    check_validation! {
+        "
+        fn f(p: ptr<private, f32>) -> f32 { return *p; }
+        fn g() -> f32 {
+            var value: f32;
+            return f(&value);
+        }
+        ",
+        "
+        var<workgroup> value: f32;
+        fn f(p: ptr<private, f32>) -> f32 { return *p; }
+        fn g() -> f32 { return f(&value); }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            name: function_name,
+            error: naga::valid::FunctionError::InvalidCall {
+                error: naga::valid::CallError::PointerAddressSpaceMismatch { index: 0 },
+                ..
+            },
+            ..
+        })
+        if function_name == "g"
+    }
+}
+
+#[test]
+fn vertex_position_output() {
+    let mut validator = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    );
+    validator.require_vertex_position(true);
+
+    let valid = [
+        "
+        @vertex
+        fn main() -> @builtin(position) vec4<f32> {
+            return vec4<f32>(0.0);
+        }
+        ",
+        "
+        struct VertexOutput {
+            @location(0) color: vec4<f32>,
+            @builtin(position) position: vec4<f32>,
+        }
+        @vertex
+        fn main() -> VertexOutput {
+            return VertexOutput(vec4<f32>(1.0), vec4<f32>(0.0));
+        }
+        ",
+        "
+        @fragment
+        fn main() {}
+        ",
+    ];
+    for source in valid.iter() {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        validator.validate(&module).unwrap();
+    }
+
+    let missing = [
+        "
+        @vertex
+        fn main() {}
+        ",
+        "
+        @vertex
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(0.0);
+        }
+        ",
+    ];
+    for source in missing.iter() {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        let error = validator.validate(&module).unwrap_err().into_inner();
+        assert!(matches!(
+            error,
+            naga::valid::ValidationError::EntryPoint {
+                stage: naga::ShaderStage::Vertex,
+                error: naga::valid::EntryPointError::MissingVertexPosition,
+                ..
+            }
+        ));
+    }
+
+    // The position is only required when asked for.
+    check_validation! {
+        "
+        @vertex
+        fn main() {}
+        ":
+        Ok(_)
+    }
+
+    check_validation! {
+        "
+        @vertex
+        fn main() -> @builtin(position) vec3<f32> {
+            return vec3<f32>(0.0);
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            stage: naga::ShaderStage::Vertex,
+            error: naga::valid::EntryPointError::Result(
+                naga::valid::VaryingError::WrongBuiltinType {
+                    builtin: naga::BuiltIn::Position { .. },
+                    expected: "vec4<f32>",
+                },
+            ),
+            ..
+        })
+    }
+}
+
+#[test]
+fn local_redefinition() {
+    check(
+        "fn f() { let x = 1; let x = 2; }",
+        r###"error: redefinition of `x`
+  ┌─ wgsl:1:14
+  │
+1 │ fn f() { let x = 1; let x = 2; }
+  │              ^          ^ redefinition of `x`
+  │              │           
+  │              previous definition of `x`
+
+"###,
+    );
+}
+
+#[test]
+fn image_queries() {
+    check_validation! {
+        "
+        @group(0) @binding(0) var t: texture_2d<f32>;
+        fn f() -> i32 { return textureNumLayers(t); }
+        ",
+        "
+        @group(0) @binding(0) var t: texture_2d<f32>;
+        fn f() -> i32 { return textureNumSamples(t); }
+        ",
+        "
+        @group(0) @binding(0) var t: texture_multisampled_2d<f32>;
+        fn f() -> i32 { return textureNumLevels(t); }
+        ",
+        "
+        @group(0) @binding(0) var t: texture_storage_2d<rgba8unorm, write>;
+        fn f() -> i32 { return textureNumLevels(t); }
+        ":
+        Err(naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::Expression {
+                error: naga::valid::ExpressionError::InvalidImageQuery(..),
+                ..
+            },
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        @group(0) @binding(0) var t: texture_2d_array<f32>;
+        fn f() -> i32 { return textureNumLayers(t) + textureNumLevels(t); }
+        ",
+        "
+        @group(0) @binding(0) var t: texture_multisampled_2d<f32>;
+        fn f() -> i32 { return textureNumSamples(t); }
+        ":
+        Ok(_)
+    }
+}
+
+#[test]
+fn unreachable_continuing() {
+    let source = "
+        fn f() -> i32 {
+            var i = 0;
+            loop {
+                if i > 4 {
+                    break;
+                } else {
+                    return i;
+                }
+                continuing {
+                    i = i + 1;
+                }
+            }
+            return i;
+        }
+    ";
+    let module = naga::front::wgsl::parse_str(source).unwrap();
+
+    // The check is opt-in.
+    let mut validator = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    );
+    validator.validate(&module).unwrap();
+
+    let error = validator
+        .report_dead_code(true)
+        .validate(&module)
+        .unwrap_err()
+        .into_inner();
+    assert!(matches!(
+        error,
+        naga::valid::ValidationError::Function {
+            error: naga::valid::FunctionError::UnreachableContinuing,
+            ..
+        }
+    ));
+
+    // Valid code, like a `for` loop that always returns, is still accepted
+    // by default.
+    let module = naga::front::wgsl::parse_str(
+        "
+        fn first(n: i32) -> i32 {
+            for (var i = 0; i < n; i = i + 1) {
+                return i;
+            }
+            return -1;
+        }
+        ",
+    )
+    .unwrap();
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::default(),
+        naga::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .unwrap();
+
+    // Loops that can reach their continuing block are accepted.
+    let sources = [
+        "
+        fn f() {
+            var i = 0;
+            loop {
+                if i > 4 {
+                    break;
+                }
+                continuing {
+                    i = i + 1;
+                }
+            }
+        }
+        ",
+        "
+        fn f() {
+            for (var i = 0; i < 4; i = i + 1) {
+                if i == 2 {
+                    continue;
+                }
+                return;
+            }
+        }
+        ",
+        "
+        fn f() {
+            loop {
+                break;
+            }
+            }
+            ",
+    ];
+    for source in sources.iter() {
+        let module = naga::front::wgsl::parse_str(source).unwrap();
+        validator.validate(&module).unwrap();
+    }
+}
+
+#[test]
+fn workgroup_atomics_outside_compute() {
+    check_validation! {
+        "
+        var<workgroup> counter: atomic<u32>;
+
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(f32(atomicAdd(&counter, 1u)));
+        }
+        ",
+        "
+        var<workgroup> counter: atomic<u32>;
+
+        fn bump() {
+            atomicStore(&counter, 0u);
+        }
+
+        @vertex
+        fn main() -> @builtin(position) vec4<f32> {
+            bump();
+            return vec4<f32>(0.0);
+        }
+        ",
+        "
+        var<workgroup> counter: atomic<u32>;
+
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(f32(atomicLoad(&counter)));
+        }
+        ":
+        Err(naga::valid::ValidationError::EntryPoint {
+            error: naga::valid::EntryPointError::Function(
+                naga::valid::FunctionError::AtomicOutsideCompute { .. }
+            ),
+            ..
+        })
+    }
+
+    check_validation! {
+        "
+        var<workgroup> counter: atomic<u32>;
+
+        @compute @workgroup_size(1)
+        fn main() {
+            atomicAdd(&counter, 1u);
+        }
+        ",
+        "
+        struct Counter { value: atomic<u32> }
+        @group(0) @binding(0) var<storage, read_write> counter: Counter;
+
+        @fragment
+        fn main() -> @location(0) vec4<f32> {
+            return vec4<f32>(f32(atomicAdd(&counter.value, 1u)));
+        }
+        ":
+        Ok(_)
+    }
+}
+
+#[test]
+fn misspelled_new_built_ins() {
+    check(
+        r#"
+            @fragment
+            fn main(@builtin(primitive_id) id: u32) {}
+        "#,
+        r#"error: unknown builtin: 'primitive_id'
+  ┌─ wgsl:3:30
+  │
+3 │             fn main(@builtin(primitive_id) id: u32) {}
+  │                              ^^^^^^^^^^^^ unknown builtin
+
+"#,
+    );
+
+    check(
+        r#"
+            @fragment
+            fn main() -> @builtin(sample_masks) u32 {
+                return 0u;
+            }
+        "#,
+        r#"error: unknown builtin: 'sample_masks'
+  ┌─ wgsl:3:35
+  │
+3 │             fn main() -> @builtin(sample_masks) u32 {
+  │                                   ^^^^^^^^^^^^ unknown builtin
+
+"#,
+    );
+}